@@ -0,0 +1,18 @@
+use rmath::{
+    plot::{PlotType, plot},
+    simulation::{QueueParams, simulate_queue},
+};
+use statrs::distribution::Exp;
+
+fn main() {
+    let params = QueueParams::new(Exp::new(0.8).unwrap(), Exp::new(1.).unwrap(), 1, 500.);
+    let sim = simulate_queue(&params);
+    println!("utilisation {}", sim.utilisation);
+    println!("mean queue length {}", sim.mean_queue_length);
+    plot(
+        sim.queue_length.times(),
+        sim.queue_length.values(),
+        PlotType::Line,
+        None,
+    );
+}
@@ -6,7 +6,7 @@ use rmath::{
 fn main() {
     let n = 1000;
     let x = vector::seq(vector::SeqParams::from(2..=n));
-    let is_prime = vector::map(&x, is_prime);
+    let is_prime = vector::vectorize(is_prime)(&x);
     let prime = vector::cumsum(vector::map(is_prime, |x| x as usize));
     let prime_density = vector::div(vector::cast::<_, f64>(prime), vector::cast::<_, f64>(&x));
     plot(&x, &prime_density, PlotType::Line, None);
@@ -0,0 +1,43 @@
+use rmath::{
+    matrix::column,
+    ode::{OdeMethod, OdeParams, ode},
+    plot::{Plot, PlotType, plot},
+    vector::{self, SeqParams, seq},
+};
+
+fn main() {
+    sir(&SirParams {
+        beta: 0.3,
+        gamma: 0.1,
+        infected: 0.001,
+        days: 160,
+    });
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SirParams {
+    /// contacts per day that transmit the infection
+    pub beta: f64,
+    /// recoveries per day
+    pub gamma: f64,
+    /// initial fraction of the population infected
+    pub infected: f64,
+    pub days: usize,
+}
+#[rustfmt::skip]
+fn sir(params: &SirParams) {
+    let &SirParams { beta, gamma, .. } = params;
+    let f = |_: f64, y: &[f64]| {
+        let (s, i) = (y[0], y[1]);
+        vec![-beta * s * i, beta * s * i - gamma * i, gamma * i]
+    };
+    let times = vector::cast::<_, f64>(seq(SeqParams::from(0..=params.days)));
+    let solution = ode(f, [1. - params.infected, params.infected, 0.],
+        OdeParams::new(OdeMethod::Dopri5, times));
+    let time = column(&solution.out, 0);
+    let mut p = Plot::new();
+    for compartment in 1..=3 {
+        plot(&time, column(&solution.out, compartment), PlotType::Line, Some(&mut p));
+    }
+    p.show();
+}
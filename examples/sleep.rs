@@ -0,0 +1,12 @@
+use rmath::htest::{TTestParams, WilcoxParams, t_test2, wilcox_test2};
+
+fn main() {
+    // R's `sleep`: extra hours of sleep for ten patients on each of two drugs
+    let group1 = [0.7, -1.6, -0.2, -1.2, -0.1, 3.4, 3.7, 0.8, 0.0, 2.0];
+    let group2 = [1.9, 0.8, 1.1, 0.1, -0.1, 4.4, 5.5, 1.6, 4.6, 3.4];
+    print!("{}", t_test2(group1, group2, TTestParams::new()));
+    let mut params = TTestParams::new();
+    params.paired = true;
+    print!("{}", t_test2(group1, group2, params));
+    print!("{}", wilcox_test2(group1, group2, WilcoxParams::new()));
+}
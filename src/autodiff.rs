@@ -0,0 +1,300 @@
+use std::{
+    num::FpCategory,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign},
+};
+
+use num_traits::{Float, FloatConst, FromPrimitive, Num, NumCast, One, ToPrimitive, Zero};
+
+use crate::matrix::{FillOrdering, MatrixBuf, matrix};
+
+/// `value + deriv ε` with `ε² = 0`; propagates the derivative of `value` through arithmetic.
+///
+/// Comparisons only look at `value`. Nesting `Dual<Dual<T>>` yields second derivatives.
+///
+/// ref: <https://en.wikipedia.org/wiki/Dual_number#Differentiation>
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Dual<T> {
+    pub value: T,
+    pub deriv: T,
+}
+impl<T> Dual<T>
+where
+    T: Float,
+{
+    pub fn new(value: T, deriv: T) -> Self {
+        Self { value, deriv }
+    }
+    pub fn constant(value: T) -> Self {
+        Self::new(value, T::zero())
+    }
+    /// Seed of the independent variable.
+    pub fn variable(value: T) -> Self {
+        Self::new(value, T::one())
+    }
+    /// Apply `f` whose derivative at `self.value` is `df`. A constant stays constant even where
+    /// `df` is infinite, as for `sqrt` and `ln` at 0.
+    fn chain(self, f: T, df: T) -> Self {
+        match self.deriv.is_zero() {
+            true => Self::new(f, T::zero()),
+            false => Self::new(f, df * self.deriv),
+        }
+    }
+}
+
+/// `(f(x), f'(x))`, the shape [`crate::roots::newton_raphson`] expects.
+pub fn derivative<T>(f: impl Fn(Dual<T>) -> Dual<T>, x: T) -> (T, T)
+where
+    T: Float,
+{
+    let y = f(Dual::variable(x));
+    (y.value, y.deriv)
+}
+/// `(f(x), ∇f(x))` with one forward pass per coordinate.
+pub fn gradient<T>(f: impl Fn(&[Dual<T>]) -> Dual<T>, x: impl AsRef<[T]>) -> (T, Vec<T>)
+where
+    T: Float,
+{
+    let (value, jacobian) = jacobian(|x| vec![f(x)], x);
+    (value[0], jacobian.data().to_vec())
+}
+/// `(f(x), J)` where `J` has one row per output of `f` and one column per input.
+pub fn jacobian<T>(
+    f: impl Fn(&[Dual<T>]) -> Vec<Dual<T>>,
+    x: impl AsRef<[T]>,
+) -> (Vec<T>, MatrixBuf<T>)
+where
+    T: Float,
+{
+    let x = x.as_ref();
+    let n = x.len();
+    let mut seeds: Vec<Dual<T>> = x.iter().map(|&x| Dual::constant(x)).collect();
+    let mut value = vec![];
+    let mut columns = Vec::with_capacity(n);
+    for j in 0..n {
+        seeds[j].deriv = T::one();
+        let y = f(&seeds);
+        seeds[j].deriv = T::zero();
+        value = y.iter().map(|y| y.value).collect();
+        columns.push(y.iter().map(|y| y.deriv).collect::<Vec<T>>());
+    }
+    if n == 0 {
+        value = f(&seeds).iter().map(|y| y.value).collect();
+    }
+    let m = value.len();
+    let data: Vec<T> = (0..m * n).map(|k| columns[k % n][k / n]).collect();
+    (value, matrix(data, [n, m], FillOrdering::RowByRow))
+}
+
+impl<T: PartialEq> PartialEq for Dual<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+impl<T: PartialOrd> PartialOrd for Dual<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+#[rustfmt::skip]
+mod arithmetic {
+    use super::*;
+    impl<T: Float> Neg for Dual<T> { type Output = Self;
+        fn neg(self) -> Self { Self::new(-self.value, -self.deriv) } }
+    impl<T: Float> Add for Dual<T> { type Output = Self;
+        fn add(self, rhs: Self) -> Self { Self::new(self.value + rhs.value, self.deriv + rhs.deriv) } }
+    impl<T: Float> Sub for Dual<T> { type Output = Self;
+        fn sub(self, rhs: Self) -> Self { Self::new(self.value - rhs.value, self.deriv - rhs.deriv) } }
+    impl<T: Float> Mul for Dual<T> { type Output = Self;
+        fn mul(self, rhs: Self) -> Self {
+            Self::new(self.value * rhs.value, self.deriv * rhs.value + self.value * rhs.deriv) } }
+    impl<T: Float> Div for Dual<T> { type Output = Self;
+        fn div(self, rhs: Self) -> Self {
+            let value = self.value / rhs.value;
+            Self::new(value, (self.deriv - value * rhs.deriv) / rhs.value) } }
+    impl<T: Float> Rem for Dual<T> { type Output = Self;
+        fn rem(self, rhs: Self) -> Self {
+            let q = (self.value / rhs.value).trunc();
+            Self::new(self.value % rhs.value, self.deriv - q * rhs.deriv) } }
+    impl<T: Float> AddAssign for Dual<T> { fn add_assign(&mut self, rhs: Self) { *self = *self + rhs } }
+    impl<T: Float> SubAssign for Dual<T> { fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs } }
+    impl<T: Float> MulAssign for Dual<T> { fn mul_assign(&mut self, rhs: Self) { *self = *self * rhs } }
+    impl<T: Float> DivAssign for Dual<T> { fn div_assign(&mut self, rhs: Self) { *self = *self / rhs } }
+    impl<T: Float> RemAssign for Dual<T> { fn rem_assign(&mut self, rhs: Self) { *self = *self % rhs } }
+    impl<T: Float> Zero for Dual<T> {
+        fn zero() -> Self { Self::constant(T::zero()) }
+        fn is_zero(&self) -> bool { self.value.is_zero() } }
+    impl<T: Float> One for Dual<T> { fn one() -> Self { Self::constant(T::one()) } }
+    impl<T: Float> Num for Dual<T> { type FromStrRadixErr = T::FromStrRadixErr;
+        fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+            T::from_str_radix(str, radix).map(Self::constant) } }
+    impl<T: Float> ToPrimitive for Dual<T> {
+        fn to_i64(&self) -> Option<i64> { self.value.to_i64() }
+        fn to_u64(&self) -> Option<u64> { self.value.to_u64() }
+        fn to_f64(&self) -> Option<f64> { self.value.to_f64() } }
+    impl<T: Float> NumCast for Dual<T> {
+        fn from<N: ToPrimitive>(n: N) -> Option<Self> { T::from(n).map(Self::constant) } }
+    impl<T: Float> FromPrimitive for Dual<T> {
+        fn from_i64(n: i64) -> Option<Self> { T::from(n).map(Self::constant) }
+        fn from_u64(n: u64) -> Option<Self> { T::from(n).map(Self::constant) }
+        fn from_f64(n: f64) -> Option<Self> { T::from(n).map(Self::constant) } }
+}
+
+macro_rules! float_consts {
+    ($($name:ident),*) => {
+        impl<T: Float + FloatConst> FloatConst for Dual<T> {
+            $(fn $name() -> Self { Self::constant(T::$name()) })*
+        }
+    };
+}
+#[rustfmt::skip]
+float_consts!(
+    E, FRAC_1_PI, FRAC_1_SQRT_2, FRAC_2_PI, FRAC_2_SQRT_PI, FRAC_PI_2, FRAC_PI_3, FRAC_PI_4,
+    FRAC_PI_6, FRAC_PI_8, LN_10, LN_2, LOG10_E, LOG2_E, PI, SQRT_2, TAU, LOG10_2, LOG2_10
+);
+
+#[rustfmt::skip]
+impl<T: Float> Float for Dual<T> {
+    fn nan() -> Self { Self::constant(T::nan()) }
+    fn infinity() -> Self { Self::constant(T::infinity()) }
+    fn neg_infinity() -> Self { Self::constant(T::neg_infinity()) }
+    fn neg_zero() -> Self { Self::constant(T::neg_zero()) }
+    fn min_value() -> Self { Self::constant(T::min_value()) }
+    fn min_positive_value() -> Self { Self::constant(T::min_positive_value()) }
+    fn max_value() -> Self { Self::constant(T::max_value()) }
+    fn epsilon() -> Self { Self::constant(T::epsilon()) }
+    fn is_nan(self) -> bool { self.value.is_nan() }
+    fn is_infinite(self) -> bool { self.value.is_infinite() }
+    fn is_finite(self) -> bool { self.value.is_finite() }
+    fn is_normal(self) -> bool { self.value.is_normal() }
+    fn classify(self) -> FpCategory { self.value.classify() }
+    fn integer_decode(self) -> (u64, i16, i8) { self.value.integer_decode() }
+    fn is_sign_positive(self) -> bool { self.value.is_sign_positive() }
+    fn is_sign_negative(self) -> bool { self.value.is_sign_negative() }
+
+    // piecewise constant
+    fn floor(self) -> Self { Self::constant(self.value.floor()) }
+    fn ceil(self) -> Self { Self::constant(self.value.ceil()) }
+    fn round(self) -> Self { Self::constant(self.value.round()) }
+    fn trunc(self) -> Self { Self::constant(self.value.trunc()) }
+    fn signum(self) -> Self { Self::constant(self.value.signum()) }
+    fn fract(self) -> Self { Self::new(self.value.fract(), self.deriv) }
+    fn abs(self) -> Self { if self.value.is_sign_negative() { -self } else { self } }
+    fn max(self, other: Self) -> Self { if other.value.is_nan() || other.value <= self.value { self } else { other } }
+    fn min(self, other: Self) -> Self { if other.value.is_nan() || self.value <= other.value { self } else { other } }
+    fn abs_sub(self, other: Self) -> Self { if self.value <= other.value { Self::zero() } else { self - other } }
+    fn copysign(self, sign: Self) -> Self {
+        if self.value.is_sign_negative() == sign.value.is_sign_negative() { self } else { -self } }
+
+    fn mul_add(self, a: Self, b: Self) -> Self { self * a + b }
+    fn recip(self) -> Self { let r = self.value.recip(); self.chain(r, -r * r) }
+    fn powi(self, n: i32) -> Self {
+        if n == 0 { return Self::one(); }
+        self.chain(self.value.powi(n), T::from(n).unwrap() * self.value.powi(n - 1)) }
+    fn powf(self, n: Self) -> Self {
+        let value = self.value.powf(n.value);
+        let dn = if n.deriv.is_zero() { T::zero() } else { value * self.value.ln() * n.deriv };
+        let dx = if self.deriv.is_zero() { T::zero() } else { n.value * self.value.powf(n.value - T::one()) * self.deriv };
+        Self::new(value, dx + dn) }
+    fn sqrt(self) -> Self { let s = self.value.sqrt(); self.chain(s, (s + s).recip()) }
+    fn cbrt(self) -> Self { let c = self.value.cbrt(); self.chain(c, (T::from(3).unwrap() * c * c).recip()) }
+    fn hypot(self, other: Self) -> Self { (self * self + other * other).sqrt() }
+    fn exp(self) -> Self { let e = self.value.exp(); self.chain(e, e) }
+    fn exp2(self) -> Self { let e = self.value.exp2(); self.chain(e, e * T::from(2).unwrap().ln()) }
+    fn exp_m1(self) -> Self { self.chain(self.value.exp_m1(), self.value.exp()) }
+    fn ln(self) -> Self { self.chain(self.value.ln(), self.value.recip()) }
+    fn log(self, base: Self) -> Self { self.ln() / base.ln() }
+    fn log2(self) -> Self { self.chain(self.value.log2(), (self.value * T::from(2).unwrap().ln()).recip()) }
+    fn log10(self) -> Self { self.chain(self.value.log10(), (self.value * T::from(10).unwrap().ln()).recip()) }
+    fn ln_1p(self) -> Self { self.chain(self.value.ln_1p(), (T::one() + self.value).recip()) }
+
+    fn sin(self) -> Self { self.chain(self.value.sin(), self.value.cos()) }
+    fn cos(self) -> Self { self.chain(self.value.cos(), -self.value.sin()) }
+    fn tan(self) -> Self { let t = self.value.tan(); self.chain(t, T::one() + t * t) }
+    fn sin_cos(self) -> (Self, Self) { (self.sin(), self.cos()) }
+    fn asin(self) -> Self { self.chain(self.value.asin(), (T::one() - self.value * self.value).sqrt().recip()) }
+    fn acos(self) -> Self { self.chain(self.value.acos(), -(T::one() - self.value * self.value).sqrt().recip()) }
+    fn atan(self) -> Self { self.chain(self.value.atan(), (T::one() + self.value * self.value).recip()) }
+    fn atan2(self, other: Self) -> Self {
+        let r2 = self.value * self.value + other.value * other.value;
+        Self::new(self.value.atan2(other.value), (other.value * self.deriv - self.value * other.deriv) / r2) }
+    fn sinh(self) -> Self { self.chain(self.value.sinh(), self.value.cosh()) }
+    fn cosh(self) -> Self { self.chain(self.value.cosh(), self.value.sinh()) }
+    fn tanh(self) -> Self { let t = self.value.tanh(); self.chain(t, T::one() - t * t) }
+    fn asinh(self) -> Self { self.chain(self.value.asinh(), (self.value * self.value + T::one()).sqrt().recip()) }
+    fn acosh(self) -> Self { self.chain(self.value.acosh(), (self.value * self.value - T::one()).sqrt().recip()) }
+    fn atanh(self) -> Self { self.chain(self.value.atanh(), (T::one() - self.value * self.value).recip()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        matrix::entry,
+        roots::{IterParams, newton_raphson},
+        vector,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_derivative() {
+        let f = |x: Dual<f64>| x.ln() - (-x).exp();
+        let (y, d) = derivative(f, 2.);
+        assert_eq!(y, 2_f64.ln() - (-2_f64).exp());
+        assert!((d - (0.5 + (-2_f64).exp())).abs() < 1e-15);
+        let a = newton_raphson(
+            |x| derivative(f, x),
+            IterParams {
+                start: 2.,
+                tolerance: 1e-10,
+                max_iter: 100,
+            },
+        );
+        assert!((a.root - 1.30979958580415).abs() < 1e-10);
+        // second derivative through nesting
+        let (_, d) = derivative(|x: Dual<Dual<f64>>| x.powi(3), Dual::variable(2.));
+        assert_eq!((d.value, d.deriv), (12., 12.));
+        let (_, d) = derivative(|x| x.powf(x), 2_f64);
+        assert!((d - 4. * (2_f64.ln() + 1.)).abs() < 1e-14);
+    }
+
+    #[test]
+    fn test_vector_functions() {
+        // d/dx mean(sin(x * 1:3)) = mean(1:3 * cos(x * 1:3))
+        let x = 0.7;
+        let (_, d) = derivative(
+            |x| {
+                let v: Vec<Dual<f64>> = (1..=3).map(|k| x * Dual::constant(k as f64)).collect();
+                vector::mean(vector::sin(v))
+            },
+            x,
+        );
+        let exact = (1..=3)
+            .map(|k| k as f64 * (k as f64 * x).cos())
+            .sum::<f64>()
+            / 3.;
+        assert!((d - exact).abs() < 1e-15);
+        let (y, g) = gradient(|x| vector::sum(vector::exp(x)), [0., 1., 2.]);
+        assert!((y - (1. + 1_f64.exp() + 2_f64.exp())).abs() < 1e-14);
+        assert_eq!(g, vec![1., 1_f64.exp(), 2_f64.exp()]);
+        // constants where the derivative is infinite
+        let zero = Dual::constant(0.);
+        let (y, g) = gradient(|x| x[0] * x[1] + zero.sqrt() + zero.ln().exp(), [2., 3.]);
+        assert_eq!(y, 6.);
+        assert_eq!(g, vec![3., 2.]);
+    }
+
+    #[test]
+    fn test_jacobian() {
+        let f = |x: &[Dual<f64>]| vec![x[0] * x[1], x[0].sin() + x[1] * x[1], x[1].exp()];
+        let (y, j) = jacobian(f, [1., 2.]);
+        assert_eq!(y, vec![2., 1_f64.sin() + 4., 2_f64.exp()]);
+        assert_eq!(j.dim(), [2, 3]);
+        let exact = [[2., 1.], [1_f64.cos(), 4.], [0., 2_f64.exp()]];
+        for (row, exact) in exact.iter().enumerate() {
+            for (col, &exact) in exact.iter().enumerate() {
+                assert_eq!(entry(&j, [col, row]), exact);
+            }
+        }
+    }
+}
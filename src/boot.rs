@@ -0,0 +1,345 @@
+use rand::{SeedableRng, rngs::StdRng};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+pub use crate::htest::Alternative;
+use crate::{
+    matrix::{FillOrdering, MatrixBuf, entry, matrix},
+    vector::{SampleParams, mean, quantile, sample, var},
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct BootParams {
+    /// number of bootstrap replicates
+    pub r: usize,
+    pub seed: u64,
+}
+impl BootParams {
+    pub fn new(r: usize) -> Self {
+        Self { r, seed: 0 }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<BootParams> for BootParams { fn as_ref(&self) -> &BootParams { self } }
+
+/// Bootstrap replicates of a statistic, like the object returned by R's `boot`.
+#[derive(Debug, Clone)]
+pub struct Boot {
+    /// the statistic on the original data
+    pub t0: f64,
+    /// the statistic on each resample
+    pub t: Vec<f64>,
+    /// leave-one-out values of the statistic, needed by the BCa interval; `None` for the
+    /// parametric bootstrap
+    pub jackknife: Option<Vec<f64>>,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BootCiType {
+    /// `t0 - bias ± z se`
+    Normal,
+    /// `2 t0` minus the upper and lower percentiles
+    Basic,
+    Percentile,
+    /// bias-corrected and accelerated percentiles
+    Bca,
+}
+impl Boot {
+    pub fn bias(&self) -> f64 {
+        mean(&self.t) - self.t0
+    }
+    pub fn std_error(&self) -> f64 {
+        var(&self.t).sqrt()
+    }
+    /// ref: <https://github.com/cran/boot/blob/master/R/bootfuns.q>
+    pub fn conf_int(&self, ty: BootCiType, level: f64) -> [f64; 2] {
+        assert!(
+            0. < level && level < 1.,
+            "'conf' must be a single number between 0 and 1"
+        );
+        let normal = Normal::new(0., 1.).unwrap();
+        let alpha = [(1. - level) / 2., (1. + level) / 2.];
+        match ty {
+            BootCiType::Normal => {
+                let z = normal.inverse_cdf(alpha[1]);
+                let center = self.t0 - self.bias();
+                [center - z * self.std_error(), center + z * self.std_error()]
+            }
+            BootCiType::Basic => {
+                let q = quantile(&self.t, alpha);
+                [2. * self.t0 - q[1], 2. * self.t0 - q[0]]
+            }
+            BootCiType::Percentile => {
+                let q = quantile(&self.t, alpha);
+                [q[0], q[1]]
+            }
+            BootCiType::Bca => {
+                let jackknife = self
+                    .jackknife
+                    .as_ref()
+                    .expect("BCa intervals cannot be calculated for parametric bootstrap");
+                let below = self.t.iter().filter(|&&t| t < self.t0).count();
+                let z0 = normal.inverse_cdf(below as f64 / self.t.len() as f64);
+                assert!(z0.is_finite(), "estimated adjustment 'z0' is infinite");
+                let jack_mean = mean(jackknife);
+                let (num, den) = jackknife.iter().fold((0., 0.), |(num, den), &j| {
+                    let d = jack_mean - j;
+                    (num + d.powi(3), den + d.powi(2))
+                });
+                let acceleration = if den == 0. {
+                    0.
+                } else {
+                    num / (6. * den.powf(1.5))
+                };
+                let adjusted = alpha.map(|a| {
+                    let z = z0 + normal.inverse_cdf(a);
+                    normal.cdf(z0 + z / (1. - acceleration * z))
+                });
+                let q = quantile(&self.t, adjusted);
+                [q[0], q[1]]
+            }
+        }
+    }
+}
+
+/// Nonparametric bootstrap of `statistic` over resamples of `data` with replacement.
+pub fn boot(
+    data: impl AsRef<[f64]>,
+    statistic: impl Fn(&[f64]) -> f64,
+    params: impl AsRef<BootParams>,
+) -> Boot {
+    let data = data.as_ref();
+    let params = params.as_ref();
+    assert!(1 < data.len(), "need at least two observations");
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    let resample = SampleParams {
+        replace: true,
+        ..SampleParams::new(data.len())
+    };
+    let t = (0..params.r)
+        .map(|_| statistic(&sample(data, &resample, &mut rng)))
+        .collect();
+    let jackknife = (0..data.len())
+        .map(|i| {
+            let rest: Vec<f64> = data[..i].iter().chain(&data[i + 1..]).copied().collect();
+            statistic(&rest)
+        })
+        .collect();
+    Boot {
+        t0: statistic(data),
+        t,
+        jackknife: Some(jackknife),
+    }
+}
+/// Nonparametric bootstrap of `statistic` over resamples of the rows of `data`.
+pub fn boot_rows(
+    data: impl AsRef<MatrixBuf<f64>>,
+    statistic: impl Fn(&MatrixBuf<f64>) -> f64,
+    params: impl AsRef<BootParams>,
+) -> Boot {
+    let data = data.as_ref();
+    let params = params.as_ref();
+    let [ncol, nrow] = data.dim();
+    assert!(1 < nrow, "need at least two observations");
+    let rows = |index: &[usize]| {
+        let values: Vec<f64> = index
+            .iter()
+            .flat_map(|&row| (0..ncol).map(move |col| entry(data, [col, row])))
+            .collect();
+        matrix(values, [ncol, index.len()], FillOrdering::RowByRow)
+    };
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    let all: Vec<usize> = (0..nrow).collect();
+    let resample = SampleParams {
+        replace: true,
+        ..SampleParams::new(nrow)
+    };
+    let t = (0..params.r)
+        .map(|_| statistic(&rows(&sample(&all, &resample, &mut rng))))
+        .collect();
+    let jackknife = (0..nrow)
+        .map(|i| {
+            let rest: Vec<usize> = all.iter().copied().filter(|&j| j != i).collect();
+            statistic(&rows(&rest))
+        })
+        .collect();
+    Boot {
+        t0: statistic(data),
+        t,
+        jackknife: Some(jackknife),
+    }
+}
+/// Parametric bootstrap: `statistic` over datasets drawn by `generate` from the fitted model,
+/// R's `boot(sim = "parametric", ran.gen = )`.
+pub fn boot_parametric(
+    data: impl AsRef<[f64]>,
+    statistic: impl Fn(&[f64]) -> f64,
+    generate: impl Fn(&mut StdRng) -> Vec<f64>,
+    params: impl AsRef<BootParams>,
+) -> Boot {
+    let params = params.as_ref();
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    let t = (0..params.r)
+        .map(|_| statistic(&generate(&mut rng)))
+        .collect();
+    Boot {
+        t0: statistic(data.as_ref()),
+        t,
+        jackknife: None,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PermutationParams {
+    /// number of random permutations
+    pub r: usize,
+    pub alternative: Alternative,
+    pub seed: u64,
+}
+impl PermutationParams {
+    pub fn new(r: usize) -> Self {
+        Self {
+            r,
+            alternative: Alternative::TwoSided,
+            seed: 0,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<PermutationParams> for PermutationParams { fn as_ref(&self) -> &PermutationParams { self } }
+#[derive(Debug, Clone)]
+pub struct PermutationTest {
+    pub statistic: f64,
+    pub p_value: f64,
+    /// the statistic under each permutation
+    pub t: Vec<f64>,
+}
+/// Two-sample permutation test of `statistic(x, y)`, such as a difference in means, by
+/// reassigning the pooled observations to the groups at random.
+///
+/// The p-value counts the observed labelling among the permutations, `(1 + #extreme) / (r + 1)`.
+pub fn permutation_test(
+    x: impl AsRef<[f64]>,
+    y: impl AsRef<[f64]>,
+    statistic: impl Fn(&[f64], &[f64]) -> f64,
+    params: impl AsRef<PermutationParams>,
+) -> PermutationTest {
+    let x = x.as_ref();
+    let y = y.as_ref();
+    let params = params.as_ref();
+    assert!(!x.is_empty() && !y.is_empty(), "not enough observations");
+    let pooled: Vec<f64> = x.iter().chain(y).copied().collect();
+    let t0 = statistic(x, y);
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    let shuffle = SampleParams::new(pooled.len());
+    let t: Vec<f64> = (0..params.r)
+        .map(|_| {
+            let permuted = sample(&pooled, &shuffle, &mut rng);
+            let (a, b) = permuted.split_at(x.len());
+            statistic(a, b)
+        })
+        .collect();
+    // tolerate rounding in statistics that tie with the observed one
+    let tol = 1e-12 * t0.abs().max(1.);
+    let extreme = t
+        .iter()
+        .filter(|&&t| match params.alternative {
+            Alternative::TwoSided => t0.abs() - tol <= t.abs(),
+            Alternative::Less => t <= t0 + tol,
+            Alternative::Greater => t0 - tol <= t,
+        })
+        .count();
+    PermutationTest {
+        statistic: t0,
+        p_value: (1 + extreme) as f64 / (params.r + 1) as f64,
+        t,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::distributions::Distribution;
+    use statrs::distribution::Exp;
+
+    use super::*;
+
+    #[test]
+    fn test_boot() {
+        let x: Vec<f64> = (1..=30).map(|i| (i as f64).powf(1.5) % 17.).collect();
+        let b = boot(&x, |x| mean(x), BootParams::new(4000));
+        // the standard error of the mean is close to sd / sqrt(n)
+        let se = (var(&x) / 30.).sqrt();
+        assert!((b.std_error() - se).abs() < 0.1 * se);
+        assert!(b.bias().abs() < 0.1 * se);
+        for ty in [
+            BootCiType::Normal,
+            BootCiType::Basic,
+            BootCiType::Percentile,
+            BootCiType::Bca,
+        ] {
+            let [lo, hi] = b.conf_int(ty, 0.95);
+            assert!(lo < b.t0 && b.t0 < hi, "{ty:?}");
+            assert!(((hi - lo) / (2. * 1.96 * se) - 1.).abs() < 0.15, "{ty:?}");
+        }
+        let [lo, hi] = b.conf_int(BootCiType::Percentile, 0.95);
+        let [basic_lo, basic_hi] = b.conf_int(BootCiType::Basic, 0.95);
+        assert!((basic_lo - (2. * b.t0 - hi)).abs() < 1e-12);
+        assert!((basic_hi - (2. * b.t0 - lo)).abs() < 1e-12);
+
+        // correlation of the columns, resampling whole rows
+        let data: Vec<f64> = (0..40)
+            .flat_map(|i| {
+                let u = i as f64;
+                [u, u + 5. * (u * 0.7).sin()]
+            })
+            .collect();
+        let m = matrix(data, [2, 40], FillOrdering::RowByRow);
+        let corr = |m: &MatrixBuf<f64>| {
+            let n = m.dim()[1];
+            let col = |c| (0..n).map(|r| entry(m, [c, r])).collect::<Vec<f64>>();
+            let (a, b) = (col(0), col(1));
+            let (ma, mb) = (mean(&a), mean(&b));
+            let cov: f64 = a.iter().zip(&b).map(|(a, b)| (a - ma) * (b - mb)).sum();
+            cov / ((var(&a) * var(&b)).sqrt() * (n - 1) as f64)
+        };
+        let b = boot_rows(&m, corr, BootParams::new(2000));
+        assert!(0.9 < b.t0 && b.t0 < 1.);
+        let [lo, hi] = b.conf_int(BootCiType::Bca, 0.9);
+        assert!(lo < b.t0 && b.t0 < hi && hi <= 1.);
+    }
+
+    #[test]
+    fn test_parametric() {
+        // the rate of an exponential sample, refitted to samples from the fitted model
+        let exp = Exp::new(2.).unwrap();
+        let mut rng = StdRng::seed_from_u64(9);
+        let x: Vec<f64> = (0..200).map(|_| exp.sample(&mut rng)).collect();
+        let rate = |x: &[f64]| 1. / mean(x);
+        let fitted = Exp::new(rate(&x)).unwrap();
+        let b = boot_parametric(
+            &x,
+            rate,
+            |rng| (0..200).map(|_| fitted.sample(rng)).collect(),
+            BootParams::new(2000),
+        );
+        assert!(b.jackknife.is_none());
+        // asymptotic standard error rate / sqrt(n)
+        assert!((b.std_error() - b.t0 / 200_f64.sqrt()).abs() < 0.02);
+        let [lo, hi] = b.conf_int(BootCiType::Percentile, 0.95);
+        assert!(lo < 2. && 2. < hi);
+    }
+
+    #[test]
+    fn test_permutation() {
+        let x = [12.1, 14.3, 13.8, 15.2, 14.9, 13.5, 16.0, 14.4];
+        let y = [11.2, 12.0, 12.9, 11.8, 13.1, 12.4, 11.5, 12.2];
+        let diff = |a: &[f64], b: &[f64]| mean(a) - mean(b);
+        let test = permutation_test(x, y, diff, PermutationParams::new(4999));
+        assert!(test.statistic > 0.);
+        assert!(test.p_value < 0.01);
+        let mut params = PermutationParams::new(4999);
+        params.alternative = Alternative::Less;
+        assert!(0.99 < permutation_test(x, y, diff, params).p_value);
+        // no difference between the groups
+        let test = permutation_test(x, x, diff, PermutationParams::new(999));
+        assert_eq!(test.p_value, 1.);
+    }
+}
@@ -0,0 +1,232 @@
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+
+use super::HTest;
+use crate::{
+    matrix::{MatrixBuf, entry},
+    warning::warning,
+};
+
+#[derive(Debug, Clone)]
+pub struct ChisqParams {
+    /// Yates' continuity correction for 2 by 2 tables
+    pub correct: bool,
+    /// expected probabilities for the goodness-of-fit test; `None` for equal probabilities
+    pub p: Option<Vec<f64>>,
+    /// whether `p` is scaled to sum to 1
+    pub rescale_p: bool,
+}
+impl Default for ChisqParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl ChisqParams {
+    pub fn new() -> Self {
+        Self {
+            correct: true,
+            p: None,
+            rescale_p: false,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<ChisqParams> for ChisqParams { fn as_ref(&self) -> &ChisqParams { self } }
+
+/// Goodness-of-fit `chisq.test(x, p = p)` of counts against expected probabilities.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/chisq.test.R>
+pub fn chisq_test(x: impl AsRef<[f64]>, params: impl AsRef<ChisqParams>) -> HTest {
+    let x = x.as_ref();
+    let params = params.as_ref();
+    assert!(2 <= x.len(), "'x' must at least have 2 elements");
+    assert!(
+        x.iter().all(|&x| 0. <= x && x.is_finite()),
+        "all entries of 'x' must be nonnegative and finite"
+    );
+    let n: f64 = x.iter().sum();
+    assert!(0. < n, "at least one entry of 'x' must be positive");
+    let p = match &params.p {
+        Some(p) => {
+            assert_eq!(
+                p.len(),
+                x.len(),
+                "'x' and 'p' must have the same number of elements"
+            );
+            assert!(
+                p.iter().all(|&p| 0. <= p),
+                "probabilities must be non-negative."
+            );
+            let total: f64 = p.iter().sum();
+            if params.rescale_p {
+                p.iter().map(|p| p / total).collect()
+            } else {
+                assert!(
+                    (total - 1.).abs() <= (f64::EPSILON).sqrt(),
+                    "probabilities must sum to 1."
+                );
+                p.clone()
+            }
+        }
+        None => vec![1. / x.len() as f64; x.len()],
+    };
+    let expected: Vec<f64> = p.iter().map(|p| n * p).collect();
+    let statistic = x
+        .iter()
+        .zip(&expected)
+        .map(|(x, e)| (x - e).powi(2) / e)
+        .sum();
+    finish(
+        statistic,
+        (x.len() - 1) as f64,
+        &expected,
+        "Chi-squared test for given probabilities",
+    )
+}
+/// Pearson's `chisq.test(x)` of independence of the rows and columns of a contingency table.
+pub fn chisq_test2(x: impl AsRef<MatrixBuf<f64>>, params: impl AsRef<ChisqParams>) -> HTest {
+    let x = x.as_ref();
+    let params = params.as_ref();
+    let [nc, nr] = x.dim();
+    assert!(
+        2 <= nr && 2 <= nc,
+        "'x' must at least have 2 rows and columns"
+    );
+    let cells: Vec<(usize, usize)> = (0..nr)
+        .flat_map(|row| (0..nc).map(move |col| (row, col)))
+        .collect();
+    let observed: Vec<f64> = cells
+        .iter()
+        .map(|&(row, col)| entry(x, [col, row]))
+        .collect();
+    assert!(
+        observed.iter().all(|&x| 0. <= x && x.is_finite()),
+        "all entries of 'x' must be nonnegative and finite"
+    );
+    let row_sums: Vec<f64> = (0..nr)
+        .map(|row| (0..nc).map(|col| entry(x, [col, row])).sum())
+        .collect();
+    let col_sums: Vec<f64> = (0..nc)
+        .map(|col| (0..nr).map(|row| entry(x, [col, row])).sum())
+        .collect();
+    let n: f64 = row_sums.iter().sum();
+    assert!(0. < n, "at least one entry of 'x' must be positive");
+    let expected: Vec<f64> = cells
+        .iter()
+        .map(|&(row, col)| row_sums[row] * col_sums[col] / n)
+        .collect();
+    let mut method = "Pearson's Chi-squared test".to_string();
+    let yates = if params.correct && nr == 2 && nc == 2 {
+        let yates = observed
+            .iter()
+            .zip(&expected)
+            .map(|(x, e)| (x - e).abs())
+            .fold(0.5, f64::min);
+        if 0. < yates {
+            method.push_str(" with Yates' continuity correction");
+        }
+        yates
+    } else {
+        0.
+    };
+    let statistic = observed
+        .iter()
+        .zip(&expected)
+        .map(|(x, e)| ((x - e).abs() - yates).powi(2) / e)
+        .sum();
+    finish(statistic, ((nr - 1) * (nc - 1)) as f64, &expected, &method)
+}
+
+fn finish(statistic: f64, df: f64, expected: &[f64], method: &str) -> HTest {
+    if expected.iter().any(|&e| e < 5.) {
+        warning("Chi-squared approximation may be incorrect");
+    }
+    HTest {
+        statistic: Some(("X-squared".to_string(), statistic)),
+        parameter: vec![("df".to_string(), df)],
+        p_value: ChiSquared::new(df).unwrap().sf(statistic),
+        conf_int: None,
+        estimate: vec![],
+        null_value: vec![],
+        alternative: None,
+        method: method.to_string(),
+        data_name: "x".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        matrix::{FillOrdering, matrix},
+        warning::take_warnings,
+    };
+
+    #[test]
+    fn test_goodness_of_fit() {
+        let test = chisq_test([20., 15., 25.], ChisqParams::new());
+        assert!((test.statistic() - 2.5).abs() < 1e-12);
+        assert!((test.p_value - 0.2865048).abs() < 1e-7);
+
+        let mut params = ChisqParams::new();
+        params.p = Some(vec![1., 2., 3.]);
+        params.rescale_p = true;
+        let test = chisq_test([20., 15., 25.], params);
+        // R: chisq.test(c(20, 15, 25), p = c(1, 2, 3), rescale.p = TRUE)
+        assert!((test.statistic() - 12.08333).abs() < 1e-5);
+    }
+
+    #[test]
+    #[should_panic(expected = "'x' must at least have 2 elements")]
+    fn test_single_count() {
+        chisq_test([5.], ChisqParams::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "all entries of 'x' must be nonnegative and finite")]
+    fn test_infinite_count() {
+        chisq_test([5., f64::INFINITY], ChisqParams::new());
+    }
+
+    #[test]
+    fn test_contingency() {
+        take_warnings();
+        let x = matrix(
+            [762., 327., 468., 484., 239., 477.],
+            [3, 2],
+            FillOrdering::RowByRow,
+        );
+        let test = chisq_test2(&x, ChisqParams::new());
+        assert!((test.statistic() - 30.07015).abs() < 1e-5);
+        assert_eq!(test.parameter[0].1, 2.);
+        assert!((test.p_value - 2.953589e-07).abs() < 1e-12);
+        assert!(take_warnings().is_empty());
+        let expected = [
+            "",
+            "\tPearson's Chi-squared test",
+            "",
+            "data:  x",
+            "X-squared = 30.07, df = 2, p-value = 2.954e-07",
+            "",
+            "",
+        ];
+        assert_eq!(test.to_string(), expected.join("\n"));
+    }
+
+    #[test]
+    fn test_yates() {
+        take_warnings();
+        // R: chisq.test(matrix(c(3, 1, 1, 3), 2))
+        let x = matrix([3., 1., 1., 3.], [2, 2], FillOrdering::RowByRow);
+        let test = chisq_test2(&x, ChisqParams::new());
+        assert!((test.statistic() - 0.5).abs() < 1e-12);
+        assert!((test.p_value - 0.4795001).abs() < 1e-7);
+        assert_eq!(
+            test.method,
+            "Pearson's Chi-squared test with Yates' continuity correction"
+        );
+        assert_eq!(
+            take_warnings(),
+            ["Chi-squared approximation may be incorrect"]
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use statrs::function::factorial::ln_binomial;
+
+use super::{Alternative, ConfInt, HTest};
+use crate::{
+    matrix::{MatrixBuf, entry},
+    roots::{BracketParams, uniroot},
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct FisherParams {
+    pub alternative: Alternative,
+    /// the odds ratio under the null hypothesis
+    pub or: f64,
+    pub conf_level: f64,
+}
+impl Default for FisherParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl FisherParams {
+    pub fn new() -> Self {
+        Self {
+            alternative: Alternative::TwoSided,
+            or: 1.,
+            conf_level: 0.95,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<FisherParams> for FisherParams { fn as_ref(&self) -> &FisherParams { self } }
+
+/// `fisher.test(x)` for a 2 by 2 table of counts, with the conditional maximum likelihood
+/// estimate of the odds ratio and its exact confidence interval.
+///
+/// Only 2 by 2 tables are supported; R's network algorithm for r by c tables is not ported, and
+/// any other shape panics.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/fisher.test.R>
+pub fn fisher_test(x: impl AsRef<MatrixBuf<f64>>, params: impl AsRef<FisherParams>) -> HTest {
+    let x = x.as_ref();
+    let params = params.as_ref();
+    assert_eq!(x.dim(), [2, 2], "only 2 by 2 tables are supported");
+    let cell = |row: usize, col: usize| {
+        let v = entry(x, [col, row]);
+        assert!(
+            0. <= v && v.fract() == 0.,
+            "all entries of 'x' must be nonnegative and finite"
+        );
+        v as u64
+    };
+    let m = cell(0, 0) + cell(1, 0);
+    let n = cell(0, 1) + cell(1, 1);
+    let k = cell(0, 0) + cell(0, 1);
+    let x = cell(0, 0);
+    let hyper = NoncentralHyper::new(m, n, k);
+    let (lo, hi) = (hyper.lo, hyper.hi);
+    let or = params.or;
+    assert!(0. <= or, "'or' must be a single number between 0 and Inf");
+
+    let p_value = match params.alternative {
+        Alternative::Less => hyper.cdf(x, or, false),
+        Alternative::Greater => hyper.cdf(x, or, true),
+        Alternative::TwoSided if or == 0. => f64::from(x == lo),
+        Alternative::TwoSided if or == f64::INFINITY => f64::from(x == hi),
+        Alternative::TwoSided => {
+            let rel_err = 1. + 1e-7;
+            let d = hyper.density(or);
+            let dx = d[(x - lo) as usize];
+            d.iter().filter(|&&d| d <= dx * rel_err).sum()
+        }
+    };
+
+    let level = params.conf_level;
+    let ncp_upper = |alpha: f64| {
+        if x == hi {
+            return f64::INFINITY;
+        }
+        let p = hyper.cdf(x, 1., false);
+        let f = |t: f64| hyper.cdf(x, t, false) - alpha;
+        let g = |t: f64| hyper.cdf(x, 1. / t, false) - alpha;
+        root_or_inverse(p < alpha, alpha < p, f, g)
+    };
+    let ncp_lower = |alpha: f64| {
+        if x == lo {
+            return 0.;
+        }
+        let p = hyper.cdf(x, 1., true);
+        let f = |t: f64| hyper.cdf(x, t, true) - alpha;
+        let g = |t: f64| hyper.cdf(x, 1. / t, true) - alpha;
+        root_or_inverse(alpha < p, p < alpha, f, g)
+    };
+    let [lower, upper] = match params.alternative {
+        Alternative::Less => [0., ncp_upper(1. - level)],
+        Alternative::Greater => [ncp_lower(1. - level), f64::INFINITY],
+        Alternative::TwoSided => {
+            let alpha = (1. - level) / 2.;
+            [ncp_lower(alpha), ncp_upper(alpha)]
+        }
+    };
+
+    let estimate = if x == lo {
+        0.
+    } else if x == hi {
+        f64::INFINITY
+    } else {
+        let mu = hyper.mean(1.);
+        let x = x as f64;
+        let f = |t: f64| hyper.mean(t) - x;
+        let g = |t: f64| hyper.mean(1. / t) - x;
+        root_or_inverse(x < mu, mu < x, f, g)
+    };
+
+    HTest {
+        statistic: None,
+        parameter: vec![],
+        p_value: p_value.min(1.),
+        conf_int: Some(ConfInt {
+            lower,
+            upper,
+            level,
+        }),
+        estimate: vec![("odds ratio".to_string(), estimate)],
+        null_value: vec![("odds ratio".to_string(), or)],
+        alternative: Some(params.alternative),
+        method: "Fisher's Exact Test for Count Data".to_string(),
+        data_name: "x".to_string(),
+    }
+}
+
+/// The root of `f` on `[0, 1]` when `below`, the reciprocal of the root of `g`, which is `f` at
+/// `1 / t`, on `[eps, 1]` when `above`, and 1 otherwise.
+fn root_or_inverse(
+    below: bool,
+    above: bool,
+    f: impl Fn(f64) -> f64,
+    g: impl Fn(f64) -> f64,
+) -> f64 {
+    if below {
+        uniroot(f, BracketParams::new(0., 1.)).root
+    } else if above {
+        1. / uniroot(g, BracketParams::new(f64::EPSILON, 1.)).root
+    } else {
+        1.
+    }
+}
+
+/// Fisher's noncentral hypergeometric distribution of the top left cell of a 2 by 2 table with
+/// column sums `m`, `n` and first row sum `k`.
+struct NoncentralHyper {
+    lo: u64,
+    hi: u64,
+    /// `log(dhyper(support, m, n, k))` up to a constant
+    log_density: Vec<f64>,
+}
+impl NoncentralHyper {
+    fn new(m: u64, n: u64, k: u64) -> Self {
+        let lo = k.saturating_sub(n);
+        let hi = k.min(m);
+        let log_density = (lo..=hi)
+            .map(|x| ln_binomial(m, x) + ln_binomial(n, k - x))
+            .collect();
+        Self {
+            lo,
+            hi,
+            log_density,
+        }
+    }
+    fn support(&self) -> impl Iterator<Item = u64> + '_ {
+        self.lo..=self.hi
+    }
+    fn density(&self, ncp: f64) -> Vec<f64> {
+        let d: Vec<f64> = self
+            .log_density
+            .iter()
+            .zip(self.support())
+            .map(|(d, x)| d + ncp.ln() * x as f64)
+            .collect();
+        let max = d.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let d: Vec<f64> = d.iter().map(|d| (d - max).exp()).collect();
+        let total: f64 = d.iter().sum();
+        d.iter().map(|d| d / total).collect()
+    }
+    fn mean(&self, ncp: f64) -> f64 {
+        if ncp == 0. {
+            return self.lo as f64;
+        }
+        if ncp == f64::INFINITY {
+            return self.hi as f64;
+        }
+        self.density(ncp)
+            .iter()
+            .zip(self.support())
+            .map(|(d, x)| d * x as f64)
+            .sum()
+    }
+    /// `P(X <= q)`, or `P(X >= q)` for the upper tail.
+    fn cdf(&self, q: u64, ncp: f64, upper_tail: bool) -> f64 {
+        let degenerate = |at: u64| f64::from(if upper_tail { q <= at } else { at <= q });
+        if ncp == 0. {
+            return degenerate(self.lo);
+        }
+        if ncp == f64::INFINITY {
+            return degenerate(self.hi);
+        }
+        self.density(ncp)
+            .iter()
+            .zip(self.support())
+            .filter(|(_, x)| if upper_tail { q <= *x } else { *x <= q })
+            .map(|(d, _)| d)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::{FillOrdering, matrix};
+
+    fn assert_close(a: f64, b: f64, tol: f64) {
+        assert!((a - b).abs() <= tol * b.abs(), "{a} != {b}");
+    }
+
+    #[test]
+    fn test_tea_tasting() {
+        let x = matrix([3., 1., 1., 3.], [2, 2], FillOrdering::RowByRow);
+        let mut params = FisherParams::new();
+        params.alternative = Alternative::Greater;
+        let test = fisher_test(&x, params);
+        assert_close(test.p_value, 0.2428571, 1e-6);
+        assert_close(test.estimate[0].1, 6.408309, 1e-4);
+        assert_close(test.conf_int.unwrap().lower, 0.3135693, 1e-4);
+        assert_eq!(test.conf_int.unwrap().upper, f64::INFINITY);
+
+        let test = fisher_test(&x, FisherParams::new());
+        assert_close(test.p_value, 0.4857143, 1e-6);
+        assert_close(test.conf_int.unwrap().lower, 0.2117329, 1e-4);
+        assert_close(test.conf_int.unwrap().upper, 621.9337505, 1e-4);
+    }
+
+    #[test]
+    fn test_convictions() {
+        let x = matrix([2., 15., 10., 3.], [2, 2], FillOrdering::RowByRow);
+        let mut params = FisherParams::new();
+        params.alternative = Alternative::Less;
+        let test = fisher_test(&x, params);
+        assert_close(test.p_value, 0.0004651809, 1e-6);
+        assert_close(test.estimate[0].1, 0.04693661, 1e-4);
+        assert_close(test.conf_int.unwrap().upper, 0.2849601, 1e-4);
+        let printed = test.to_string();
+        assert!(printed.contains("p-value = 0.0004652\n"));
+        assert!(printed.contains("true odds ratio is less than 1\n"));
+    }
+}
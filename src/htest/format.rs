@@ -0,0 +1,225 @@
+//! Number formatting after R's `format` and `format.pval`.
+
+/// `format(x, digits = digits)`: every element rounded to `digits` significant digits, then all
+/// shown with the same number of decimals, or in scientific notation when that is narrower, and
+/// right-justified to a common width.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/main/format.c>
+pub(crate) fn format_numbers(x: impl AsRef<[f64]>, digits: usize) -> Vec<String> {
+    let x = x.as_ref();
+    let digits = digits.clamp(1, 15);
+    let finite: Vec<Scientific> = x
+        .iter()
+        .filter(|x| x.is_finite())
+        .map(|&x| Scientific::new(x, digits))
+        .collect();
+    let fixed = finite.iter().all(|s| s.exponent.abs() < 100) && {
+        let left = finite
+            .iter()
+            .map(|s| usize::from(s.negative) + (s.exponent.max(0) as usize + 1))
+            .max()
+            .unwrap_or(1);
+        let w_fixed = left + decimals(&finite) + usize::from(0 < decimals(&finite));
+        let sig = finite.iter().map(|s| s.sig).max().unwrap_or(1);
+        let exp_width = if finite.iter().any(|s| 100 <= s.exponent.abs()) {
+            3
+        } else {
+            2
+        };
+        let negative = finite.iter().any(|s| s.negative);
+        let w_sci = usize::from(negative) + sig + usize::from(1 < sig) + 2 + exp_width;
+        w_fixed <= w_sci
+    };
+    let sig = finite.iter().map(|s| s.sig).max().unwrap_or(1);
+    let rgt = decimals(&finite);
+    let out: Vec<String> = x
+        .iter()
+        .map(|&x| {
+            if x.is_nan() {
+                "NaN".to_string()
+            } else if x.is_infinite() {
+                if x < 0. { "-Inf" } else { "Inf" }.to_string()
+            } else if fixed {
+                let s = format!("{x:.rgt$}");
+                // no negative zero after rounding
+                if s.starts_with('-') && s[1..].chars().all(|c| c == '0' || c == '.') {
+                    s[1..].to_string()
+                } else {
+                    s
+                }
+            } else {
+                r_exponent(&format!("{:.*e}", sig - 1, x))
+            }
+        })
+        .collect();
+    let width = out.iter().map(|s| s.len()).max().unwrap_or(0);
+    out.into_iter().map(|s| format!("{s:>width$}")).collect()
+}
+/// `format(x, digits = digits)` for a single number.
+pub(crate) fn format_number(x: f64, digits: usize) -> String {
+    format_numbers([x], digits).pop().unwrap()
+}
+/// `format.pval(pv, digits = digits)`, without the leading `=`.
+pub(crate) fn format_pval(pv: f64, digits: usize) -> String {
+    format_pvals([pv], digits).pop().unwrap()
+}
+/// `format.pval(pv, digits = digits)`: p-values below machine epsilon as `< eps`, the others
+/// formatted together, fixed down to 1e-3 and scientific below.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/base/R/format.R>
+pub(crate) fn format_pvals(pv: impl AsRef<[f64]>, digits: usize) -> Vec<String> {
+    let pv = pv.as_ref();
+    let mut out = vec!["NA".to_string(); pv.len()];
+    let is0 = |p: f64| p < f64::EPSILON;
+    for fixed in [true, false] {
+        let group: Vec<usize> = (0..pv.len())
+            .filter(|&i| !pv[i].is_nan() && !is0(pv[i]))
+            .filter(|&i| (-3. <= pv[i].log10().floor()) == fixed)
+            .collect();
+        let formatted = format_numbers(group.iter().map(|&i| pv[i]).collect::<Vec<f64>>(), digits);
+        for (i, s) in group.into_iter().zip(formatted) {
+            out[i] = s;
+        }
+    }
+    if pv.iter().any(|&p| is0(p)) {
+        let mut digits = digits.saturating_sub(2).max(1);
+        let others = (0..pv.len()).filter(|&i| !pv[i].is_nan() && !is0(pv[i]));
+        let sep = match others.map(|i| out[i].len()).max() {
+            Some(nc) => {
+                if 1 < digits && nc < digits + 6 {
+                    digits = nc.saturating_sub(7).max(1);
+                }
+                if digits == 1 && nc <= 6 { "" } else { " " }
+            }
+            None if digits == 1 => "",
+            None => " ",
+        };
+        let eps = format!("<{sep}{}", format_number(f64::EPSILON, digits));
+        for (out, _) in out.iter_mut().zip(pv).filter(|(_, p)| is0(**p)) {
+            *out = eps.clone();
+        }
+    }
+    out
+}
+/// `formatC(x, digits = digits)`, C's `%.*g`: `digits` significant digits without trailing zeros,
+/// in scientific notation for exponents below -4 or from `digits` up.
+pub(crate) fn format_c(x: f64, digits: usize) -> String {
+    if !x.is_finite() {
+        return format_number(x, digits);
+    }
+    let digits = digits.max(1);
+    let sci = format!("{:.*e}", digits - 1, x);
+    let (mantissa, exponent) = sci.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    let trim = |s: &str| match s.contains('.') {
+        true => s.trim_end_matches('0').trim_end_matches('.').to_string(),
+        false => s.to_string(),
+    };
+    if exponent < -4 || digits as i32 <= exponent {
+        r_exponent(&format!("{}e{exponent}", trim(mantissa)))
+    } else {
+        let decimals = (digits as i32 - 1 - exponent) as usize;
+        trim(&format!("{x:.decimals$}"))
+    }
+}
+
+/// `x` rounded to `digits` significant digits as `mantissa × 10^exponent`, with `sig` the
+/// significant digits left after dropping trailing zeros.
+struct Scientific {
+    negative: bool,
+    exponent: i32,
+    sig: usize,
+}
+impl Scientific {
+    fn new(x: f64, digits: usize) -> Self {
+        if x == 0. {
+            return Self {
+                negative: false,
+                exponent: 0,
+                sig: 1,
+            };
+        }
+        let s = format!("{:.*e}", digits - 1, x.abs());
+        let (mantissa, exponent) = s.split_once('e').unwrap();
+        let sig = mantissa.trim_end_matches('0').trim_end_matches('.').len();
+        Self {
+            negative: x < 0.,
+            exponent: exponent.parse().unwrap(),
+            // the decimal point does not count
+            sig: if 1 < sig { sig - 1 } else { sig },
+        }
+    }
+}
+/// Decimals needed to show each number's significant digits.
+fn decimals(numbers: &[Scientific]) -> usize {
+    numbers
+        .iter()
+        .map(|s| (s.sig as i32 - 1 - s.exponent).max(0) as usize)
+        .max()
+        .unwrap_or(0)
+}
+/// Rust's `1.5e-7` as R's `1.5e-07`.
+fn r_exponent(s: &str) -> String {
+    let (mantissa, exponent) = s.split_once('e').unwrap();
+    let (sign, digits) = match exponent.strip_prefix('-') {
+        Some(digits) => ('-', digits),
+        None => ('+', exponent),
+    };
+    format!("{mantissa}e{sign}{digits:0>2}")
+}
+
+/// `strwrap(text)` at the default width of 72 columns.
+pub(crate) fn wrap(text: &str) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && 71 < line.len() + 1 + word.len() {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(line);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format_number(-1.860813, 5), "-1.8608");
+        assert_eq!(format_number(17.77647, 5), "17.776");
+        assert_eq!(format_number(9., 5), "9");
+        assert_eq!(format_number(2.953883e-07, 4), "2.954e-07");
+        assert_eq!(format_number(0.07939414, 4), "0.07939");
+        assert_eq!(
+            format_numbers([-3.3654832, 0.2054832], 7),
+            ["-3.3654832", " 0.2054832"]
+        );
+        assert_eq!(format_numbers([0.75, 2.33], 7), ["0.75", "2.33"]);
+        assert_eq!(
+            format_numbers([0.3135693, f64::INFINITY], 7),
+            ["0.3135693", "      Inf"]
+        );
+        assert_eq!(format_numbers([1e10, 1.], 7), ["1e+10", "1e+00"]);
+        assert_eq!(format_pval(1e-20, 4), "< 2.2e-16");
+        assert_eq!(format_pval(1., 4), "1");
+        assert_eq!(
+            format_pvals([8.2e-20, 1.29e-10], 3),
+            ["< 2e-16", "1.29e-10"]
+        );
+        assert_eq!(
+            format_pvals([0.0123, 0.5, 2e-5], 3),
+            ["0.0123", "0.5000", "2e-05"]
+        );
+        assert_eq!(format_pvals([1e-20, 0.0123], 4), ["<2e-16", "0.0123"]);
+        assert_eq!(format_c(0.752833, 4), "0.7528");
+        assert_eq!(format_c(91.37533, 4), "91.38");
+        assert_eq!(format_c(0.5, 4), "0.5");
+        assert_eq!(format_c(1234567., 4), "1.235e+06");
+    }
+}
@@ -0,0 +1,319 @@
+use nalgebra::DMatrix;
+use statrs::function::factorial::ln_binomial;
+
+use super::{Alternative, HTest};
+use crate::warning::warning;
+
+#[derive(Debug, Clone, Copy)]
+pub struct KsParams {
+    pub alternative: Alternative,
+    /// exact p-value; `None` for exact with fewer than 100 observations, or fewer than 10000
+    /// pairs of observations for two samples, when there are no ties
+    pub exact: Option<bool>,
+}
+impl Default for KsParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl KsParams {
+    pub fn new() -> Self {
+        Self {
+            alternative: Alternative::TwoSided,
+            exact: None,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<KsParams> for KsParams { fn as_ref(&self) -> &KsParams { self } }
+
+/// One-sample `ks.test(x, cdf)` of `x` against a continuous distribution function.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/ks.test.R>
+pub fn ks_test(
+    x: impl AsRef<[f64]>,
+    cdf: impl Fn(f64) -> f64,
+    params: impl AsRef<KsParams>,
+) -> HTest {
+    let params = params.as_ref();
+    let mut x = x.as_ref().to_vec();
+    let n = x.len();
+    assert!(0 < n, "not enough 'x' data");
+    x.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let ties = x.windows(2).any(|w| w[0] == w[1]);
+    if ties {
+        warning("ties should not be present for the one-sample Kolmogorov-Smirnov test");
+    }
+    let nf = n as f64;
+    // F(x_i) - (i - 1) / n: the gap below the step at each observation
+    let below: Vec<f64> = x
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| cdf(x) - i as f64 / nf)
+        .collect();
+    let d_plus = below
+        .iter()
+        .map(|d| 1. / nf - d)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let d_minus = below.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let statistic = match params.alternative {
+        Alternative::TwoSided => d_plus.max(d_minus),
+        Alternative::Greater => d_plus,
+        Alternative::Less => d_minus,
+    };
+    let exact = params.exact.unwrap_or(n < 100 && !ties);
+    let p_value = match (exact, params.alternative) {
+        (true, Alternative::TwoSided) => 1. - kolmogorov_exact(statistic, n),
+        (true, _) => 1. - kolmogorov_one_sided_exact(statistic, n),
+        (false, Alternative::TwoSided) => 1. - kolmogorov_limit(nf.sqrt() * statistic),
+        (false, _) => (-2. * nf * statistic.powi(2)).exp(),
+    };
+    let method = match exact {
+        true => "Exact one-sample Kolmogorov-Smirnov test",
+        false => "Asymptotic one-sample Kolmogorov-Smirnov test",
+    };
+    finish(statistic, p_value, params.alternative, method, "x")
+}
+/// Two-sample `ks.test(x, y)` of whether `x` and `y` come from the same continuous distribution.
+pub fn ks_test2(x: impl AsRef<[f64]>, y: impl AsRef<[f64]>, params: impl AsRef<KsParams>) -> HTest {
+    let (x, y) = (x.as_ref(), y.as_ref());
+    let params = params.as_ref();
+    let (nx, ny) = (x.len(), y.len());
+    assert!(0 < nx, "not enough 'x' data");
+    assert!(0 < ny, "not enough 'y' data");
+    let mut w: Vec<(f64, bool)> = x
+        .iter()
+        .map(|&x| (x, true))
+        .chain(y.iter().map(|&y| (y, false)))
+        .collect();
+    w.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let ties = w.windows(2).any(|w| w[0].0 == w[1].0);
+    // Fx - Fy after each distinct value
+    let mut z = vec![];
+    let mut diff = 0.;
+    for (i, &(value, from_x)) in w.iter().enumerate() {
+        diff += if from_x {
+            1. / nx as f64
+        } else {
+            -1. / ny as f64
+        };
+        if w.get(i + 1).is_none_or(|next| next.0 != value) {
+            z.push(diff);
+        }
+    }
+    let statistic = match params.alternative {
+        Alternative::TwoSided => z.iter().fold(0., |d: f64, z| d.max(z.abs())),
+        Alternative::Greater => z.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        Alternative::Less => -z.iter().copied().fold(f64::INFINITY, f64::min),
+    };
+    let exact = params.exact.unwrap_or(nx * ny < 10000 && !ties);
+    if exact && ties {
+        warning("cannot compute exact p-value with ties");
+    }
+    let exact = exact && !ties;
+    let p_value = if exact {
+        1. - smirnov_exact(statistic, nx, ny, params.alternative)
+    } else {
+        let m = (nx * ny) as f64 / (nx + ny) as f64;
+        match params.alternative {
+            Alternative::TwoSided => 1. - kolmogorov_limit(m.sqrt() * statistic),
+            _ => (-2. * m * statistic.powi(2)).exp(),
+        }
+    };
+    let method = match exact {
+        true => "Exact two-sample Kolmogorov-Smirnov test",
+        false => "Asymptotic two-sample Kolmogorov-Smirnov test",
+    };
+    finish(statistic, p_value, params.alternative, method, "x and y")
+}
+
+fn finish(
+    statistic: f64,
+    p_value: f64,
+    alternative: Alternative,
+    method: &str,
+    data_name: &str,
+) -> HTest {
+    let name = match alternative {
+        Alternative::TwoSided => "D",
+        Alternative::Greater => "D^+",
+        Alternative::Less => "D^-",
+    };
+    HTest {
+        statistic: Some((name.to_string(), statistic)),
+        parameter: vec![],
+        p_value: p_value.clamp(0., 1.),
+        conf_int: None,
+        estimate: vec![],
+        null_value: vec![],
+        alternative: Some(alternative),
+        method: method.to_string(),
+        data_name: data_name.to_string(),
+    }
+}
+
+/// `P(D_n < d)` for the two-sided one-sample statistic, by Marsaglia, Tsang and Wang's matrix
+/// power.
+///
+/// ref: <https://doi.org/10.18637/jss.v008.i18>
+fn kolmogorov_exact(d: f64, n: usize) -> f64 {
+    let nf = n as f64;
+    let k = (nf * d) as usize + 1;
+    let m = 2 * k - 1;
+    let h = k as f64 - nf * d;
+    let mut hm = DMatrix::from_fn(m, m, |i, j| f64::from(j <= i + 1));
+    for i in 0..m {
+        hm[(i, 0)] -= h.powi(i as i32 + 1);
+        hm[(m - 1, i)] -= h.powi((m - i) as i32);
+    }
+    if 0. < 2. * h - 1. {
+        hm[(m - 1, 0)] += (2. * h - 1.).powi(m as i32);
+    }
+    for i in 0..m {
+        for j in 0..m {
+            if j <= i + 1 {
+                for g in 1..=(i + 1 - j) {
+                    hm[(i, j)] /= g as f64;
+                }
+            }
+        }
+    }
+    let (q, mut exponent) = matrix_power(&hm, n);
+    let mut s = q[(k - 1, k - 1)];
+    for i in 1..=n {
+        s *= i as f64 / nf;
+        if s < 1e-140 {
+            s *= 1e140;
+            exponent -= 140;
+        }
+    }
+    s * 10f64.powi(exponent)
+}
+/// `a^n` as a matrix and a power of ten, keeping the middle entry in range.
+fn matrix_power(a: &DMatrix<f64>, n: usize) -> (DMatrix<f64>, i32) {
+    if n == 1 {
+        return (a.clone(), 0);
+    }
+    let (half, exponent) = matrix_power(a, n / 2);
+    let mut b = &half * &half;
+    let mut exponent = 2 * exponent;
+    if n % 2 == 1 {
+        b = a * b;
+    }
+    let center = b.nrows() / 2;
+    if 1e140 < b[(center, center)] {
+        b /= 1e140;
+        exponent += 140;
+    }
+    (b, exponent)
+}
+/// `P(D_n^+ < d)` by the Birnbaum-Tingey formula.
+fn kolmogorov_one_sided_exact(d: f64, n: usize) -> f64 {
+    if d <= 0. {
+        return 0.;
+    }
+    if 1. <= d {
+        return 1.;
+    }
+    let nf = n as f64;
+    let terms: f64 = (0..=(nf * (1. - d)).floor() as u64)
+        .map(|j| {
+            let j_n = j as f64 / nf;
+            (ln_binomial(n as u64, j)
+                + (nf - j as f64) * (1. - d - j_n).ln()
+                + (j as f64 - 1.) * (d + j_n).ln())
+            .exp()
+        })
+        .sum();
+    1. - d * terms
+}
+/// The limiting distribution `P(sqrt(n) D_n < x)`.
+fn kolmogorov_limit(x: f64) -> f64 {
+    use std::f64::consts::PI;
+    if x <= 0. {
+        return 0.;
+    }
+    if x < 1. {
+        let w = -PI.powi(2) / (8. * x * x);
+        let s: f64 = (1..=20)
+            .map(|k| (w * ((2 * k - 1) as f64).powi(2)).exp())
+            .sum();
+        (2. * PI).sqrt() / x * s
+    } else {
+        let s: f64 = (1..=20)
+            .map(|k| {
+                let sign = if k % 2 == 1 { 1. } else { -1. };
+                sign * (-2. * (k * k) as f64 * x * x).exp()
+            })
+            .sum();
+        1. - 2. * s
+    }
+}
+/// `P(D < d)` for the two-sample statistic without ties, counting the lattice paths that stay
+/// within `d` of the diagonal on the side, or sides, given by `alternative`.
+fn smirnov_exact(d: f64, nx: usize, ny: usize, alternative: Alternative) -> f64 {
+    let (mf, nf) = (nx as f64, ny as f64);
+    let q = (0.5 + (d * mf * nf - 1e-7).floor()) / (mf * nf);
+    let outside = |i: usize, j: usize| {
+        let diff = i as f64 / mf - j as f64 / nf;
+        q < match alternative {
+            Alternative::TwoSided => diff.abs(),
+            Alternative::Greater => diff,
+            Alternative::Less => -diff,
+        }
+    };
+    // u[j]: probability of reaching (i, j) inside the band, scaled by the path count
+    let mut u: Vec<f64> = (0..=ny).map(|j| f64::from(!outside(0, j))).collect();
+    for i in 1..=nx {
+        let w = i as f64 / (i + ny) as f64;
+        u[0] = if outside(i, 0) { 0. } else { w * u[0] };
+        for j in 1..=ny {
+            u[j] = if outside(i, j) {
+                0.
+            } else {
+                w * u[j] + u[j - 1]
+            };
+        }
+    }
+    u[ny]
+}
+
+#[cfg(test)]
+mod tests {
+    use statrs::distribution::{ContinuousCDF, Normal};
+
+    use super::*;
+
+    #[test]
+    fn test_one_sample() {
+        // the 5% critical value of D for n = 10 is 0.40925
+        assert!((kolmogorov_exact(0.40925, 10) - 0.95).abs() < 1e-4);
+        assert!((kolmogorov_limit(1.3581) - 0.95).abs() < 1e-4);
+        let x = [-1.2, -0.6, -0.3, 0.1, 0.2, 0.4, 0.7, 1.1, 1.5, 2.2];
+        let normal = Normal::standard();
+        let test = ks_test(x, |x| normal.cdf(x), KsParams::new());
+        // the largest gap is at 0.1, where F = 0.5398 after 3 of 10 observations
+        assert!((test.statistic() - (normal.cdf(0.1) - 0.3)).abs() < 1e-12);
+        assert!(0.5 < test.p_value);
+        assert_eq!(test.method, "Exact one-sample Kolmogorov-Smirnov test");
+
+        let mut params = KsParams::new();
+        params.alternative = Alternative::Less;
+        let test = ks_test(x, |x| normal.cdf(x), params);
+        assert_eq!(test.statistic.as_ref().unwrap().0, "D^-");
+    }
+
+    #[test]
+    fn test_two_sample() {
+        let test = ks_test2([1., 2.], [3., 4.], KsParams::new());
+        assert_eq!(test.statistic(), 1.);
+        assert!((test.p_value - 1. / 3.).abs() < 1e-12);
+
+        let mut params = KsParams::new();
+        params.alternative = Alternative::Greater;
+        let test = ks_test2([3., 4.], [1., 2.], params);
+        // Fx never rises above Fy
+        assert_eq!(test.statistic(), 0.);
+        assert_eq!(test.p_value, 1.);
+    }
+}
@@ -0,0 +1,203 @@
+mod chisq;
+mod fisher;
+pub(crate) mod format;
+mod ks;
+mod normality;
+mod p_adjust;
+mod power;
+mod prop;
+mod t;
+mod var;
+mod wilcox;
+use std::fmt;
+
+pub use chisq::*;
+pub use fisher::*;
+pub use ks::*;
+pub use normality::*;
+pub use p_adjust::*;
+pub use power::*;
+pub use prop::*;
+pub use t::*;
+pub use var::*;
+pub use wilcox::*;
+
+use self::format::{format_number, format_numbers, format_pval, wrap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alternative {
+    TwoSided,
+    Less,
+    Greater,
+}
+impl Alternative {
+    /// R's name, as in `alternative = "two.sided"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Alternative::TwoSided => "two.sided",
+            Alternative::Less => "less",
+            Alternative::Greater => "greater",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfInt {
+    pub lower: f64,
+    pub upper: f64,
+    pub level: f64,
+}
+
+/// The result of a hypothesis test, like R's `htest` object. Named values keep R's names, which
+/// `Display` prints the way `print.htest` does.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HTest {
+    pub statistic: Option<(String, f64)>,
+    pub parameter: Vec<(String, f64)>,
+    pub p_value: f64,
+    pub conf_int: Option<ConfInt>,
+    pub estimate: Vec<(String, f64)>,
+    /// the hypothesized value of each estimate
+    pub null_value: Vec<(String, f64)>,
+    /// `None` for tests without a direction, like the chi-squared tests
+    pub alternative: Option<Alternative>,
+    pub method: String,
+    pub data_name: String,
+}
+impl HTest {
+    pub fn statistic(&self) -> f64 {
+        self.statistic.as_ref().unwrap().1
+    }
+}
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/htest.R>
+impl fmt::Display for HTest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f)?;
+        for line in wrap(&self.method) {
+            writeln!(f, "\t{line}")?;
+        }
+        writeln!(f)?;
+        writeln!(f, "data:  {}", self.data_name)?;
+        let mut out: Vec<String> = self
+            .statistic
+            .iter()
+            .chain(&self.parameter)
+            .map(|(name, value)| format!("{name} = {}", format_number(*value, 5)))
+            .collect();
+        let p_value = format_pval(self.p_value, 4);
+        out.push(match p_value.starts_with('<') {
+            true => format!("p-value {p_value}"),
+            false => format!("p-value = {p_value}"),
+        });
+        for line in wrap(&out.join(", ")) {
+            writeln!(f, "{line}")?;
+        }
+        if let Some(alternative) = self.alternative {
+            write!(f, "alternative hypothesis: ")?;
+            match self.null_value.as_slice() {
+                [(name, value)] => {
+                    let relation = match alternative {
+                        Alternative::TwoSided => "not equal to",
+                        Alternative::Less => "less than",
+                        Alternative::Greater => "greater than",
+                    };
+                    let value = format_number(*value, 7);
+                    writeln!(f, "true {name} is {relation} {value}")?;
+                }
+                [] => writeln!(f, "{}", alternative.name())?,
+                null_value => {
+                    writeln!(f, "{}", alternative.name())?;
+                    writeln!(f, "null values:")?;
+                    write_named(f, null_value)?;
+                }
+            }
+        }
+        if let Some(conf_int) = &self.conf_int {
+            let bounds = format_numbers([conf_int.lower, conf_int.upper], 7);
+            writeln!(
+                f,
+                "{} percent confidence interval:",
+                format_number(100. * conf_int.level, 7)
+            )?;
+            writeln!(f, " {}", bounds.join(" "))?;
+        }
+        if !self.estimate.is_empty() {
+            writeln!(f, "sample estimates:")?;
+            write_named(f, &self.estimate)?;
+        }
+        writeln!(f)
+    }
+}
+/// `print` of a named vector: names over values, each column right-justified.
+fn write_named(f: &mut fmt::Formatter<'_>, named: &[(String, f64)]) -> fmt::Result {
+    let values: Vec<f64> = named.iter().map(|(_, value)| *value).collect();
+    let values = format_numbers(values, 7);
+    let widths: Vec<usize> = named
+        .iter()
+        .zip(&values)
+        .map(|((name, _), value)| name.len().max(value.len()))
+        .collect();
+    for (width, (name, _)) in widths.iter().zip(named) {
+        write!(f, "{name:>width$} ")?;
+    }
+    writeln!(f)?;
+    for (width, value) in widths.iter().zip(&values) {
+        write!(f, "{value:>width$} ")?;
+    }
+    writeln!(f)
+}
+
+/// Two-sided, lower or upper p-value of an observed statistic, given its CDF and survival
+/// function at the statistic.
+fn p_value(alternative: Alternative, cdf: f64, sf: f64) -> f64 {
+    match alternative {
+        Alternative::TwoSided => (2. * cdf.min(sf)).min(1.),
+        Alternative::Less => cdf,
+        Alternative::Greater => sf,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let test = HTest {
+            statistic: Some(("t".to_string(), -1.860813)),
+            parameter: vec![("df".to_string(), 17.77647)],
+            p_value: 0.07939414,
+            conf_int: Some(ConfInt {
+                lower: -3.3654832,
+                upper: 0.2054832,
+                level: 0.95,
+            }),
+            estimate: vec![
+                ("mean of x".to_string(), 0.75),
+                ("mean of y".to_string(), 2.33),
+            ],
+            null_value: vec![("difference in means".to_string(), 0.)],
+            alternative: Some(Alternative::TwoSided),
+            method: "Welch Two Sample t-test".to_string(),
+            data_name: "extra by group".to_string(),
+        };
+        let expected = [
+            "",
+            "\tWelch Two Sample t-test",
+            "",
+            "data:  extra by group",
+            "t = -1.8608, df = 17.776, p-value = 0.07939",
+            "alternative hypothesis: true difference in means is not equal to 0",
+            "95 percent confidence interval:",
+            " -3.3654832  0.2054832",
+            "sample estimates:",
+            "mean of x mean of y ",
+            "     0.75      2.33 ",
+            "",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(test.to_string(), expected);
+    }
+}
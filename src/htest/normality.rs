@@ -0,0 +1,352 @@
+use statrs::distribution::{ChiSquared, ContinuousCDF, Normal};
+
+use super::HTest;
+use crate::{
+    vector::{mean, var},
+    warning::warning,
+};
+
+/// `shapiro.test(x)`: the Shapiro-Wilk W statistic with Royston's approximation to its p-value,
+/// for 3 to 5000 observations.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/src/swilk.c>
+pub fn shapiro_test(x: impl AsRef<[f64]>) -> HTest {
+    let x = sorted(x.as_ref());
+    let n = x.len();
+    assert!(
+        (3..=5000).contains(&n),
+        "sample size must be between 3 and 5000"
+    );
+    let range = x[n - 1] - x[0];
+    assert!(1e-10 <= range, "all 'x' values are identical");
+    let normal = Normal::standard();
+    let an = n as f64;
+
+    // coefficients of the lower half of the order statistics
+    let half = n / 2;
+    let mut a = vec![0.; half];
+    if n == 3 {
+        a[0] = 0.5f64.sqrt();
+    } else {
+        let an25 = an + 0.25;
+        let m: Vec<f64> = (1..=half)
+            .map(|i| normal.inverse_cdf((i as f64 - 0.375) / an25))
+            .collect();
+        let summ2 = 2. * m.iter().map(|m| m * m).sum::<f64>();
+        let ssumm2 = summ2.sqrt();
+        let rsn = 1. / an.sqrt();
+        let a1 = poly(&SW_C1, rsn) - m[0] / ssumm2;
+        a.copy_from_slice(&m);
+        let (first, fac) = if 5 < n {
+            let a2 = -m[1] / ssumm2 + poly(&SW_C2, rsn);
+            let fac = ((summ2 - 2. * m[0].powi(2) - 2. * m[1].powi(2))
+                / (1. - 2. * a1.powi(2) - 2. * a2.powi(2)))
+            .sqrt();
+            a[1] = a2;
+            (2, fac)
+        } else {
+            let fac = ((summ2 - 2. * m[0].powi(2)) / (1. - 2. * a1.powi(2))).sqrt();
+            (1, fac)
+        };
+        a[0] = a1;
+        a[first..].iter_mut().for_each(|a| *a /= -fac);
+    }
+    // antisymmetric coefficients for every order statistic
+    let coef: Vec<f64> = (0..n)
+        .map(|i| {
+            let j = n - 1 - i;
+            match i.cmp(&j) {
+                std::cmp::Ordering::Less => -a[i],
+                std::cmp::Ordering::Greater => a[j],
+                std::cmp::Ordering::Equal => 0.,
+            }
+        })
+        .collect();
+
+    // W as the squared correlation of the data and the coefficients, through 1 - W to avoid
+    // rounding when W is near 1
+    let sa = mean(&coef);
+    let sx = x.iter().map(|x| x / range).sum::<f64>() / an;
+    let (mut ssa, mut ssx, mut sax) = (0., 0., 0.);
+    for (coef, x) in coef.iter().zip(&x) {
+        let asa = coef - sa;
+        let xsx = x / range - sx;
+        ssa += asa * asa;
+        ssx += xsx * xsx;
+        sax += asa * xsx;
+    }
+    let ssassx = (ssa * ssx).sqrt();
+    let w1 = (ssassx - sax) * (ssassx + sax) / (ssa * ssx);
+    let w = 1. - w1;
+
+    let p_value = if n == 3 {
+        // exact
+        let pi6 = 6. / std::f64::consts::PI;
+        let stqr = std::f64::consts::FRAC_PI_3;
+        (pi6 * (w.sqrt().asin() - stqr)).max(0.)
+    } else if n <= 11 {
+        let gamma = poly(&SW_G, an);
+        let y = w1.ln();
+        if gamma <= y {
+            1e-99
+        } else {
+            let y = -(gamma - y).ln();
+            let m = poly(&SW_C3, an);
+            let s = poly(&SW_C4, an).exp();
+            Normal::new(m, s).unwrap().sf(y)
+        }
+    } else {
+        let m = poly(&SW_C5, an.ln());
+        let s = poly(&SW_C6, an.ln()).exp();
+        Normal::new(m, s).unwrap().sf(w1.ln())
+    };
+    finish(("W", w), vec![], p_value, "Shapiro-Wilk normality test")
+}
+const SW_G: [f64; 2] = [-2.273, 0.459];
+const SW_C1: [f64; 6] = [0., 0.221157, -0.147981, -2.07119, 4.434685, -2.706056];
+const SW_C2: [f64; 6] = [0., 0.042981, -0.293762, -1.752461, 5.682633, -3.582633];
+const SW_C3: [f64; 4] = [0.544, -0.39978, 0.025054, -6.714e-4];
+const SW_C4: [f64; 4] = [1.3822, -0.77857, 0.062767, -0.0020322];
+const SW_C5: [f64; 4] = [-1.5861, -0.31082, -0.083751, 0.0038915];
+const SW_C6: [f64; 3] = [-0.4803, -0.082676, 0.0030302];
+
+/// `ad.test(x)` from nortest: the Anderson-Darling statistic for normality with estimated mean
+/// and variance, for more than 7 observations.
+///
+/// ref: <https://cran.r-project.org/package=nortest>
+pub fn ad_test(x: impl AsRef<[f64]>) -> HTest {
+    let x = sorted(x.as_ref());
+    let n = x.len();
+    assert!(7 < n, "sample size must be greater than 7");
+    let nf = n as f64;
+    let (m, s) = (mean(&x), var(&x).sqrt());
+    let h: f64 = (0..n)
+        .map(|i| {
+            let log_p1 = ln_pnorm((x[i] - m) / s);
+            let log_p2 = ln_pnorm(-(x[n - 1 - i] - m) / s);
+            (2. * i as f64 + 1.) * (log_p1 + log_p2)
+        })
+        .sum();
+    let a = -nf - h / nf;
+    let aa = (1. + 0.75 / nf + 2.25 / nf.powi(2)) * a;
+    let p_value = if aa < 0.2 {
+        1. - (-13.436 + 101.14 * aa - 223.73 * aa.powi(2)).exp()
+    } else if aa < 0.34 {
+        1. - (-8.318 + 42.796 * aa - 59.938 * aa.powi(2)).exp()
+    } else if aa < 0.6 {
+        (0.9177 - 4.279 * aa - 1.38 * aa.powi(2)).exp()
+    } else if aa < 10. {
+        (1.2937 - 5.709 * aa + 0.0186 * aa.powi(2)).exp()
+    } else {
+        3.7e-24
+    };
+    finish(("A", a), vec![], p_value, "Anderson-Darling normality test")
+}
+
+/// `pnorm(z, log.p = TRUE)`, by the asymptotic series of Mills' ratio far in the lower tail
+/// where the cdf itself underflows.
+///
+/// ref: <https://dlmf.nist.gov/7.12#i>
+fn ln_pnorm(z: f64) -> f64 {
+    if -30. < z {
+        return Normal::standard().cdf(z).ln();
+    }
+    let z2 = z * z;
+    let series = 1. - 1. / z2 + 3. / z2.powi(2) - 15. / z2.powi(3) + 105. / z2.powi(4);
+    -z2 / 2. - (-z).ln() - 0.5 * (2. * std::f64::consts::PI).ln() + series.ln()
+}
+
+/// `cvm.test(x)` from nortest: the Cramér-von Mises statistic for normality with estimated mean
+/// and variance, for more than 7 observations.
+pub fn cvm_test(x: impl AsRef<[f64]>) -> HTest {
+    let x = sorted(x.as_ref());
+    let n = x.len();
+    assert!(7 < n, "sample size must be greater than 7");
+    let nf = n as f64;
+    let p = standardized_cdf(&x);
+    let w = 1. / (12. * nf)
+        + p.iter()
+            .enumerate()
+            .map(|(i, p)| (p - (2. * i as f64 + 1.) / (2. * nf)).powi(2))
+            .sum::<f64>();
+    let ww = (1. + 0.5 / nf) * w;
+    let p_value = if ww < 0.0275 {
+        1. - (-13.953 + 775.5 * ww - 12542.61 * ww.powi(2)).exp()
+    } else if ww < 0.051 {
+        1. - (-5.903 + 179.546 * ww - 1515.29 * ww.powi(2)).exp()
+    } else if ww < 0.092 {
+        (0.886 - 31.62 * ww + 10.897 * ww.powi(2)).exp()
+    } else if ww < 1.1 {
+        (1.111 - 34.242 * ww + 12.832 * ww.powi(2)).exp()
+    } else {
+        warning("p-value is smaller than 7.37e-10, cannot be computed more accurately");
+        7.37e-10
+    };
+    finish(("W", w), vec![], p_value, "Cramer-von Mises normality test")
+}
+
+/// `lillie.test(x)` from nortest: the Kolmogorov-Smirnov statistic against a normal with
+/// estimated mean and variance, with Dallal and Wilkinson's approximation to its p-value, for
+/// more than 4 observations.
+pub fn lillie_test(x: impl AsRef<[f64]>) -> HTest {
+    let x = sorted(x.as_ref());
+    let n = x.len();
+    assert!(4 < n, "sample size must be greater than 4");
+    let nf = n as f64;
+    let p = standardized_cdf(&x);
+    let d = p
+        .iter()
+        .enumerate()
+        .map(|(i, p)| ((i as f64 + 1.) / nf - p).max(p - i as f64 / nf))
+        .fold(f64::NEG_INFINITY, f64::max);
+    let (kd, nd) = match n <= 100 {
+        true => (d, nf),
+        false => (d * (nf / 100.).powf(0.49), 100.),
+    };
+    let mut p_value =
+        (-7.01256 * kd.powi(2) * (nd + 2.78019) + 2.99587 * kd * (nd + 2.78019).sqrt() - 0.122119
+            + 0.974598 / nd.sqrt()
+            + 1.67997 / nd)
+            .exp();
+    if 0.1 < p_value {
+        let kk = (nf.sqrt() - 0.01 + 0.85 / nf.sqrt()) * d;
+        p_value = if kk <= 0.302 {
+            1.
+        } else if kk <= 0.5 {
+            2.76773 - 19.828315 * kk + 80.709644 * kk.powi(2) - 138.55152 * kk.powi(3)
+                + 81.218052 * kk.powi(4)
+        } else if kk <= 0.9 {
+            -4.901232 + 40.662806 * kk - 97.490286 * kk.powi(2) + 94.029866 * kk.powi(3)
+                - 32.355711 * kk.powi(4)
+        } else if kk <= 1.31 {
+            6.198765 - 19.558097 * kk + 23.186922 * kk.powi(2) - 12.234627 * kk.powi(3)
+                + 2.423045 * kk.powi(4)
+        } else {
+            0.
+        };
+    }
+    finish(
+        ("D", d),
+        vec![],
+        p_value,
+        "Lilliefors (Kolmogorov-Smirnov) normality test",
+    )
+}
+
+/// `jarque.bera.test(x)` from tseries: skewness and kurtosis against their normal values, with
+/// the asymptotic chi-squared p-value.
+///
+/// ref: <https://cran.r-project.org/package=tseries>
+pub fn jarque_bera_test(x: impl AsRef<[f64]>) -> HTest {
+    let x = x.as_ref();
+    assert!(!x.is_empty(), "not enough 'x' data");
+    let n = x.len() as f64;
+    let m1 = mean(x);
+    let moment = |k: i32| x.iter().map(|x| (x - m1).powi(k)).sum::<f64>() / n;
+    let m2 = moment(2);
+    let b1 = (moment(3) / m2.powf(1.5)).powi(2);
+    let b2 = moment(4) / m2.powi(2);
+    let statistic = n * b1 / 6. + n * (b2 - 3.).powi(2) / 24.;
+    let p_value = ChiSquared::new(2.).unwrap().sf(statistic);
+    finish(
+        ("X-squared", statistic),
+        vec![("df".to_string(), 2.)],
+        p_value,
+        "Jarque Bera Test",
+    )
+}
+
+fn sorted(x: &[f64]) -> Vec<f64> {
+    assert!(x.iter().all(|x| !x.is_nan()), "missing values in 'x'");
+    let mut x = x.to_vec();
+    x.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    x
+}
+/// `pnorm` of the standardized observations.
+fn standardized_cdf(x: &[f64]) -> Vec<f64> {
+    let (m, s) = (mean(x), var(x).sqrt());
+    let normal = Normal::standard();
+    x.iter().map(|x| normal.cdf((x - m) / s)).collect()
+}
+/// `c[0] + c[1] x + c[2] x^2 + ...`
+fn poly(c: &[f64], x: f64) -> f64 {
+    c.iter().rev().fold(0., |acc, c| acc * x + c)
+}
+fn finish(
+    statistic: (&str, f64),
+    parameter: Vec<(String, f64)>,
+    p_value: f64,
+    method: &str,
+) -> HTest {
+    HTest {
+        statistic: Some((statistic.0.to_string(), statistic.1)),
+        parameter,
+        p_value,
+        conf_int: None,
+        estimate: vec![],
+        null_value: vec![],
+        alternative: None,
+        method: method.to_string(),
+        data_name: "x".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// R's `mtcars$mpg`
+    const MPG: [f64; 32] = [
+        21.0, 21.0, 22.8, 21.4, 18.7, 18.1, 14.3, 24.4, 22.8, 19.2, 17.8, 16.4, 17.3, 15.2, 10.4,
+        10.4, 14.7, 32.4, 30.4, 33.9, 21.5, 15.5, 15.2, 13.3, 19.2, 27.3, 26.0, 30.4, 15.8, 19.7,
+        15.0, 21.4,
+    ];
+
+    fn assert_close(a: f64, b: f64, tol: f64) {
+        assert!((a - b).abs() < tol, "{a} != {b}");
+    }
+
+    #[test]
+    fn test_shapiro() {
+        let test = shapiro_test(MPG);
+        assert_close(test.statistic(), 0.94756, 1e-5);
+        assert_close(test.p_value, 0.1229, 1e-4);
+        assert!(test.to_string().contains("W = 0.94756, p-value = 0.1229\n"));
+        // exact for three observations
+        let test = shapiro_test([1., 2., 4.]);
+        assert_close(test.statistic(), 27. / 28., 1e-12);
+        let expected =
+            6. / std::f64::consts::PI * ((27f64 / 28.).sqrt().asin() - std::f64::consts::FRAC_PI_3);
+        assert_close(test.p_value, expected, 1e-12);
+    }
+
+    #[test]
+    fn test_edf() {
+        let test = ad_test(MPG);
+        assert_close(test.statistic(), 0.57968, 1e-5);
+        assert_close(test.p_value, 0.1207, 1e-4);
+        // an outlier about 45 standard deviations out, where the normal tail underflows
+        let mut x: Vec<f64> = (0..1999).map(|i| i as f64 * 1e-6).collect();
+        x.push(1e3);
+        let test = ad_test(&x);
+        assert!(test.statistic().is_finite());
+        assert_eq!(test.p_value, 3.7e-24);
+        assert_close(ln_pnorm(-40.), -804.6084420137538, 1e-10);
+        assert_close(ln_pnorm(-29.), Normal::standard().cdf(-29.).ln(), 1e-10);
+        let test = lillie_test(MPG);
+        assert_close(test.statistic(), 0.1263, 1e-4);
+        assert_close(test.p_value, 0.2171, 1e-4);
+        let test = cvm_test(MPG);
+        assert_close(test.statistic(), 0.088204, 1e-6);
+        assert_close(test.p_value, 0.1558, 1e-4);
+    }
+
+    #[test]
+    fn test_jarque_bera() {
+        // no skew and a kurtosis of 1, so the statistic is n (1 - 3)^2 / 24
+        let x: Vec<f64> = (0..12).map(|i| if i % 2 == 0 { -1. } else { 1. }).collect();
+        let test = jarque_bera_test(&x);
+        assert_close(test.statistic(), 2., 1e-12);
+        assert_close(test.p_value, (-1f64).exp(), 1e-12);
+    }
+}
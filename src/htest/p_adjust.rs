@@ -0,0 +1,148 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PAdjustMethod {
+    Holm,
+    Hochberg,
+    Hommel,
+    Bonferroni,
+    /// Benjamini and Hochberg's false discovery rate
+    Bh,
+    /// Benjamini and Yekutieli's false discovery rate under dependence
+    By,
+    None,
+}
+
+/// `p.adjust(p, method)`: p-values adjusted for multiple comparisons, in the original order.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/p.adjust.R>
+pub fn p_adjust(p: impl AsRef<[f64]>, method: PAdjustMethod) -> Vec<f64> {
+    let p = p.as_ref();
+    assert!(
+        p.iter().all(|p| !p.is_nan()),
+        "missing p-values are not supported"
+    );
+    let n = p.len();
+    if n <= 1 {
+        return p.to_vec();
+    }
+    let method = match (n, method) {
+        (2, PAdjustMethod::Hommel) => PAdjustMethod::Hochberg,
+        _ => method,
+    };
+    let nf = n as f64;
+    // indices of the p-values from smallest to largest
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| p[a].partial_cmp(&p[b]).unwrap());
+    let sorted: Vec<f64> = order.iter().map(|&i| p[i]).collect();
+    let adjusted: Vec<f64> = match method {
+        PAdjustMethod::None => return p.to_vec(),
+        PAdjustMethod::Bonferroni => return p.iter().map(|p| (nf * p).min(1.)).collect(),
+        PAdjustMethod::Holm => {
+            let mut max = 0.;
+            sorted
+                .iter()
+                .enumerate()
+                .map(|(i, p)| {
+                    max = f64::max(max, (nf - i as f64) * p);
+                    max.min(1.)
+                })
+                .collect()
+        }
+        PAdjustMethod::Hochberg | PAdjustMethod::Bh | PAdjustMethod::By => {
+            let q: f64 = match method {
+                PAdjustMethod::By => (1..=n).map(|i| 1. / i as f64).sum(),
+                _ => 1.,
+            };
+            // running minimum from the largest p-value down; `i` counts from 1 at the smallest
+            let mut min = f64::INFINITY;
+            let mut adjusted: Vec<f64> = sorted
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(i, p)| {
+                    let i = (i + 1) as f64;
+                    let factor = match method {
+                        PAdjustMethod::Hochberg => nf + 1. - i,
+                        _ => q * nf / i,
+                    };
+                    min = min.min(factor * p);
+                    min.min(1.)
+                })
+                .collect();
+            adjusted.reverse();
+            adjusted
+        }
+        PAdjustMethod::Hommel => {
+            let initial = sorted
+                .iter()
+                .enumerate()
+                .map(|(i, p)| nf * p / (i + 1) as f64)
+                .fold(f64::INFINITY, f64::min);
+            let mut q = vec![initial; n];
+            let mut pa = vec![initial; n];
+            for m in (2..n).rev() {
+                // the first n - m + 1 p-values, and the rest
+                let split = n - m + 1;
+                let q1 = sorted[split..]
+                    .iter()
+                    .enumerate()
+                    .map(|(k, p)| m as f64 * p / (k + 2) as f64)
+                    .fold(f64::INFINITY, f64::min);
+                for i in 0..split {
+                    q[i] = (m as f64 * sorted[i]).min(q1);
+                }
+                for i in split..n {
+                    q[i] = q[split - 1];
+                }
+                pa.iter_mut().zip(&q).for_each(|(pa, q)| *pa = pa.max(*q));
+            }
+            pa.iter().zip(&sorted).map(|(pa, p)| pa.max(*p)).collect()
+        }
+    };
+    let mut out = vec![0.; n];
+    for (&i, adjusted) in order.iter().zip(adjusted) {
+        out[i] = adjusted;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_all_close(a: &[f64], b: &[f64]) {
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(b) {
+            assert!((a - b).abs() < 1e-12, "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn test_p_adjust() {
+        let p = [0.04, 0.01, 0.05, 0.02, 0.03];
+        assert_all_close(
+            &p_adjust(p, PAdjustMethod::Bonferroni),
+            &[0.2, 0.05, 0.25, 0.1, 0.15],
+        );
+        assert_all_close(
+            &p_adjust(p, PAdjustMethod::Holm),
+            &[0.09, 0.05, 0.09, 0.08, 0.09],
+        );
+        assert_all_close(&p_adjust(p, PAdjustMethod::Hochberg), &[0.05; 5]);
+        assert_all_close(&p_adjust(p, PAdjustMethod::Bh), &[0.05; 5]);
+        let by = 1. + 1. / 2. + 1. / 3. + 1. / 4. + 1. / 5.;
+        assert_all_close(&p_adjust(p, PAdjustMethod::By), &[0.05 * by; 5]);
+        assert_all_close(&p_adjust(p, PAdjustMethod::None), &p);
+    }
+
+    #[test]
+    fn test_hommel() {
+        let p = [0.04, 0.01, 0.02];
+        assert_all_close(&p_adjust(p, PAdjustMethod::Hommel), &[0.04, 0.03, 0.04]);
+        // never larger than Hochberg's
+        let p = [0.001, 0.008, 0.039, 0.041, 0.042, 0.06, 0.074, 0.205];
+        let hommel = p_adjust(p, PAdjustMethod::Hommel);
+        let hochberg = p_adjust(p, PAdjustMethod::Hochberg);
+        assert!(hommel.iter().zip(&hochberg).all(|(a, b)| a <= b));
+        assert!(hommel.iter().zip(&p).all(|(a, p)| p <= a));
+    }
+}
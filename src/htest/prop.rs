@@ -0,0 +1,364 @@
+use statrs::distribution::{
+    Beta, Binomial, ChiSquared, ContinuousCDF, Discrete, DiscreteCDF, Normal,
+};
+
+use super::{Alternative, ConfInt, HTest};
+use crate::warning::warning;
+
+#[derive(Debug, Clone)]
+pub struct PropParams {
+    /// the probabilities of success under the null hypothesis; `None` for 0.5 with one group
+    /// and equal proportions with several
+    pub p: Option<Vec<f64>>,
+    pub alternative: Alternative,
+    pub conf_level: f64,
+    /// Yates' continuity correction, for at most two groups
+    pub correct: bool,
+}
+impl Default for PropParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl PropParams {
+    pub fn new() -> Self {
+        Self {
+            p: None,
+            alternative: Alternative::TwoSided,
+            conf_level: 0.95,
+            correct: true,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<PropParams> for PropParams { fn as_ref(&self) -> &PropParams { self } }
+
+/// `prop.test(x, n)`: successes `x` out of `n` trials in each group, compared with given
+/// probabilities or with each other by a chi-squared test.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/prop.test.R>
+pub fn prop_test(
+    x: impl AsRef<[f64]>,
+    n: impl AsRef<[f64]>,
+    params: impl AsRef<PropParams>,
+) -> HTest {
+    let (x, n) = (x.as_ref(), n.as_ref());
+    let params = params.as_ref();
+    assert_eq!(x.len(), n.len(), "'x' and 'n' must have the same length");
+    assert!(
+        n.iter().all(|&n| 0. < n),
+        "elements of 'n' must be positive"
+    );
+    assert!(
+        x.iter().all(|&x| 0. <= x),
+        "elements of 'x' must be nonnegative"
+    );
+    assert!(
+        x.iter().zip(n).all(|(x, n)| x <= n),
+        "elements of 'x' must not be greater than those of 'n'"
+    );
+    let k = x.len();
+    assert!(0 < k, "not enough data");
+    let p = match (&params.p, k) {
+        (Some(p), _) => {
+            assert_eq!(p.len(), k, "'p' must have the same length as 'x' and 'n'");
+            assert!(
+                p.iter().all(|&p| 0. < p && p < 1.),
+                "elements of 'p' must be in (0,1)"
+            );
+            Some(p.clone())
+        }
+        (None, 1) => Some(vec![0.5]),
+        (None, _) => None,
+    };
+    let alternative = match (k, &p) {
+        (1, _) | (2, None) => params.alternative,
+        _ => Alternative::TwoSided,
+    };
+    let level = params.conf_level;
+    let estimate: Vec<f64> = x.iter().zip(n).map(|(x, n)| x / n).collect();
+    let z = Normal::standard().inverse_cdf(match alternative {
+        Alternative::TwoSided => (1. + level) / 2.,
+        _ => level,
+    });
+    let mut yates: f64 = if params.correct && k <= 2 { 0.5 } else { 0. };
+    let mut delta = 0.;
+    let conf_int = if k == 1 {
+        let (x, n, p, estimate) = (x[0], n[0], p.as_ref().unwrap()[0], estimate[0]);
+        yates = yates.min((x - n * p).abs());
+        let z22n = z.powi(2) / (2. * n);
+        let bound = |p_c: f64, z: f64| {
+            (p_c + z22n + z * (p_c * (1. - p_c) / n + z22n / (2. * n)).sqrt()) / (1. + 2. * z22n)
+        };
+        let p_c = estimate + yates / n;
+        let upper = if 1. <= p_c { 1. } else { bound(p_c, z) };
+        let p_c = estimate - yates / n;
+        let lower = if p_c <= 0. { 0. } else { bound(p_c, -z) };
+        Some(bounds(
+            alternative,
+            lower.max(0.),
+            upper.min(1.),
+            0.,
+            1.,
+            level,
+        ))
+    } else if k == 2 && p.is_none() {
+        delta = estimate[0] - estimate[1];
+        let inverse_n: f64 = n.iter().map(|n| 1. / n).sum();
+        yates = yates.min(delta.abs() / inverse_n);
+        let variance: f64 = estimate.iter().zip(n).map(|(e, n)| e * (1. - e) / n).sum();
+        let width = z * variance.sqrt() + yates * inverse_n;
+        let (lower, upper) = ((delta - width).max(-1.), (delta + width).min(1.));
+        Some(bounds(alternative, lower, upper, -1., 1., level))
+    } else {
+        None
+    };
+    let method = format!(
+        "{} {} continuity correction",
+        match (k, &params.p) {
+            (1, _) => "1-sample proportions test".to_string(),
+            (_, None) => format!("{k}-sample test for equality of proportions"),
+            (_, Some(_)) => format!("{k}-sample test for given proportions"),
+        },
+        if 0. < yates { "with" } else { "without" },
+    );
+    let estimate_names: Vec<String> = match k {
+        1 => vec!["p".to_string()],
+        _ => (1..=k).map(|i| format!("prop {i}")).collect(),
+    };
+    let (expected_p, df, null_value) = match &p {
+        Some(p) => {
+            let null_value = estimate_names
+                .iter()
+                .cloned()
+                .zip(p.iter().copied())
+                .collect();
+            (p.clone(), k as f64, null_value)
+        }
+        None => {
+            let pooled = x.iter().sum::<f64>() / n.iter().sum::<f64>();
+            (vec![pooled; k], (k - 1) as f64, vec![])
+        }
+    };
+    let mut statistic = 0.;
+    let mut small = false;
+    for ((x, n), p) in x.iter().zip(n).zip(&expected_p) {
+        for (observed, expected) in [(*x, n * p), (n - x, n * (1. - p))] {
+            small |= expected < 5.;
+            statistic += ((observed - expected).abs() - yates).powi(2) / expected;
+        }
+    }
+    if small {
+        warning("Chi-squared approximation may be incorrect");
+    }
+    let p_value = match alternative {
+        Alternative::TwoSided => ChiSquared::new(df).unwrap().sf(statistic),
+        _ => {
+            let sign = match k {
+                1 => (estimate[0] - expected_p[0]).signum(),
+                _ => delta.signum(),
+            };
+            let z = sign * statistic.sqrt();
+            let normal = Normal::standard();
+            match alternative {
+                Alternative::Less => normal.cdf(z),
+                _ => normal.sf(z),
+            }
+        }
+    };
+    HTest {
+        statistic: Some(("X-squared".to_string(), statistic)),
+        parameter: vec![("df".to_string(), df)],
+        p_value,
+        conf_int,
+        estimate: estimate_names.into_iter().zip(estimate).collect(),
+        null_value,
+        alternative: Some(alternative),
+        method,
+        data_name: match k {
+            1 => "x out of n, null probability p".to_string(),
+            _ => "x out of n".to_string(),
+        },
+    }
+}
+
+/// The interval for `alternative`, open up to the natural limits `min` and `max` on one side.
+fn bounds(
+    alternative: Alternative,
+    lower: f64,
+    upper: f64,
+    min: f64,
+    max: f64,
+    level: f64,
+) -> ConfInt {
+    let (lower, upper) = match alternative {
+        Alternative::TwoSided => (lower, upper),
+        Alternative::Less => (min, upper),
+        Alternative::Greater => (lower, max),
+    };
+    ConfInt {
+        lower,
+        upper,
+        level,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BinomParams {
+    /// the probability of success under the null hypothesis
+    pub p: f64,
+    pub alternative: Alternative,
+    pub conf_level: f64,
+}
+impl Default for BinomParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl BinomParams {
+    pub fn new() -> Self {
+        Self {
+            p: 0.5,
+            alternative: Alternative::TwoSided,
+            conf_level: 0.95,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<BinomParams> for BinomParams { fn as_ref(&self) -> &BinomParams { self } }
+
+/// Exact `binom.test(x, n)` with the Clopper-Pearson confidence interval.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/binom.test.R>
+pub fn binom_test(x: u64, n: u64, params: impl AsRef<BinomParams>) -> HTest {
+    let params = params.as_ref();
+    let p = params.p;
+    assert!(x <= n, "'x' must be nonnegative and not greater than 'n'");
+    assert!(
+        (0. ..=1.).contains(&p),
+        "'p' must be a single number between 0 and 1"
+    );
+    let level = params.conf_level;
+    let binom = Binomial::new(p, n).unwrap();
+    // P(X >= x)
+    let upper_tail = |x: u64| if x == 0 { 1. } else { binom.sf(x - 1) };
+    let p_value = match params.alternative {
+        Alternative::Less => binom.cdf(x),
+        Alternative::Greater => upper_tail(x),
+        Alternative::TwoSided if p == 0. => f64::from(x == 0),
+        Alternative::TwoSided if p == 1. => f64::from(x == n),
+        Alternative::TwoSided => {
+            let rel_err = 1. + 1e-7;
+            let d = binom.pmf(x);
+            let m = n as f64 * p;
+            let x_f = x as f64;
+            if x_f == m {
+                1.
+            } else if x_f < m {
+                let y = (m.ceil() as u64..=n)
+                    .filter(|&i| binom.pmf(i) <= d * rel_err)
+                    .count() as u64;
+                binom.cdf(x) + upper_tail(n - y + 1)
+            } else {
+                let y = (0..=m.floor() as u64)
+                    .filter(|&i| binom.pmf(i) <= d * rel_err)
+                    .count() as u64;
+                let lower = if y == 0 { 0. } else { binom.cdf(y - 1) };
+                lower + upper_tail(x)
+            }
+        }
+    };
+    let p_lower = |alpha: f64| match x {
+        0 => 0.,
+        _ => Beta::new(x as f64, (n - x + 1) as f64)
+            .unwrap()
+            .inverse_cdf(alpha),
+    };
+    let p_upper = |alpha: f64| match x == n {
+        true => 1.,
+        false => Beta::new((x + 1) as f64, (n - x) as f64)
+            .unwrap()
+            .inverse_cdf(1. - alpha),
+    };
+    let (lower, upper) = match params.alternative {
+        Alternative::Less => (0., p_upper(1. - level)),
+        Alternative::Greater => (p_lower(1. - level), 1.),
+        Alternative::TwoSided => {
+            let alpha = (1. - level) / 2.;
+            (p_lower(alpha), p_upper(alpha))
+        }
+    };
+    HTest {
+        statistic: Some(("number of successes".to_string(), x as f64)),
+        parameter: vec![("number of trials".to_string(), n as f64)],
+        p_value: p_value.min(1.),
+        conf_int: Some(ConfInt {
+            lower,
+            upper,
+            level,
+        }),
+        estimate: vec![("probability of success".to_string(), x as f64 / n as f64)],
+        null_value: vec![("probability of success".to_string(), p)],
+        alternative: Some(params.alternative),
+        method: "Exact binomial test".to_string(),
+        data_name: "x and n".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::warning::take_warnings;
+
+    fn assert_close(a: f64, b: f64, tol: f64) {
+        assert!((a - b).abs() < tol, "{a} != {b}");
+    }
+
+    #[test]
+    fn test_prop_smokers() {
+        take_warnings();
+        let smokers = [83., 90., 129., 70.];
+        let patients = [86., 93., 136., 82.];
+        let test = prop_test(smokers, patients, PropParams::new());
+        assert_close(test.statistic(), 12.6, 1e-3);
+        assert_eq!(test.parameter[0].1, 3.);
+        assert_close(test.p_value, 0.005585476, 1e-8);
+        assert!(test.conf_int.is_none());
+        assert_eq!(
+            test.method,
+            "4-sample test for equality of proportions without continuity correction"
+        );
+        assert!(take_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_prop_one_sample() {
+        // R: prop.test(15, 40)
+        let test = prop_test([15.], [40.], PropParams::new());
+        assert_close(test.statistic(), 2.025, 1e-12);
+        let conf_int = test.conf_int.unwrap();
+        // the Wilson score interval with continuity correction contains the estimate
+        assert!(conf_int.lower < 0.375 && 0.375 < conf_int.upper);
+        assert_eq!(test.null_value, [("p".to_string(), 0.5)]);
+        assert_eq!(
+            test.method,
+            "1-sample proportions test with continuity correction"
+        );
+    }
+
+    #[test]
+    fn test_binom() {
+        let mut params = BinomParams::new();
+        params.p = 0.75;
+        let test = binom_test(682, 925, params);
+        assert_close(test.p_value, 0.3824916, 1e-6);
+        let conf_int = test.conf_int.unwrap();
+        assert_close(conf_int.lower, 0.7076683, 1e-7);
+        assert_close(conf_int.upper, 0.7654066, 1e-7);
+        assert_close(test.estimate[0].1, 0.7372973, 1e-7);
+        assert!(
+            test.to_string()
+                .contains("number of successes = 682, number of trials = 925, p-value = 0.3825\n")
+        );
+    }
+}
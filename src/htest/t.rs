@@ -0,0 +1,228 @@
+use statrs::distribution::{ContinuousCDF, StudentsT};
+
+use super::{Alternative, ConfInt, HTest, p_value};
+use crate::vector::{mean, var};
+
+#[derive(Debug, Clone, Copy)]
+pub struct TTestParams {
+    pub alternative: Alternative,
+    /// the true mean, or difference in means, under the null hypothesis
+    pub mu: f64,
+    /// whether `t_test2` takes differences of paired observations
+    pub paired: bool,
+    /// whether `t_test2` pools the two variances instead of using Welch's approximation
+    pub var_equal: bool,
+    pub conf_level: f64,
+}
+impl Default for TTestParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl TTestParams {
+    pub fn new() -> Self {
+        Self {
+            alternative: Alternative::TwoSided,
+            mu: 0.,
+            paired: false,
+            var_equal: false,
+            conf_level: 0.95,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<TTestParams> for TTestParams { fn as_ref(&self) -> &TTestParams { self } }
+
+/// One-sample `t.test(x)`.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/t.test.R>
+pub fn t_test(x: impl AsRef<[f64]>, params: impl AsRef<TTestParams>) -> HTest {
+    let params = params.as_ref();
+    one_sample(x.as_ref(), params, false, "x")
+}
+/// Two-sample `t.test(x, y)`, or the paired test when `params.paired`.
+pub fn t_test2(
+    x: impl AsRef<[f64]>,
+    y: impl AsRef<[f64]>,
+    params: impl AsRef<TTestParams>,
+) -> HTest {
+    let (x, y) = (x.as_ref(), y.as_ref());
+    let params = params.as_ref();
+    if params.paired {
+        assert_eq!(x.len(), y.len(), "'x' and 'y' must have the same length");
+        let d: Vec<f64> = x.iter().zip(y).map(|(x, y)| x - y).collect();
+        return one_sample(&d, params, true, "x and y");
+    }
+    let (nx, ny) = (x.len() as f64, y.len() as f64);
+    assert!(1. <= nx && 1. <= ny, "not enough observations");
+    assert!(
+        2. < nx + ny && (params.var_equal || (2. <= nx && 2. <= ny)),
+        "not enough 'x' or 'y' observations"
+    );
+    let (mx, my) = (mean(x), mean(y));
+    let var_or_zero = |v: &[f64]| if 1 < v.len() { var(v) } else { 0. };
+    let (vx, vy) = (var_or_zero(x), var_or_zero(y));
+    let (df, std_err) = if params.var_equal {
+        let df = nx + ny - 2.;
+        let v = ((nx - 1.) * vx + (ny - 1.) * vy) / df;
+        (df, (v * (1. / nx + 1. / ny)).sqrt())
+    } else {
+        let (sx2, sy2) = (vx / nx, vy / ny);
+        let std_err = (sx2 + sy2).sqrt();
+        let df = std_err.powi(4) / (sx2.powi(2) / (nx - 1.) + sy2.powi(2) / (ny - 1.));
+        (df, std_err)
+    };
+    assert!(
+        10. * f64::EPSILON * mx.abs().max(my.abs()) <= std_err,
+        "data are essentially constant"
+    );
+    let method = match params.var_equal {
+        true => " Two Sample t-test",
+        false => "Welch Two Sample t-test",
+    };
+    let estimate = vec![("mean of x".to_string(), mx), ("mean of y".to_string(), my)];
+    finish(
+        (mx - my - params.mu) / std_err,
+        df,
+        std_err,
+        params,
+        estimate,
+        "difference in means",
+        method,
+        "x and y",
+    )
+}
+
+fn one_sample(x: &[f64], params: &TTestParams, paired: bool, data_name: &str) -> HTest {
+    let n = x.len() as f64;
+    assert!(2. <= n, "not enough 'x' observations");
+    let mx = mean(x);
+    let std_err = (var(x) / n).sqrt();
+    assert!(
+        10. * f64::EPSILON * mx.abs() <= std_err,
+        "data are essentially constant"
+    );
+    let (method, estimate, null_name) = match paired {
+        true => ("Paired t-test", "mean difference", "mean difference"),
+        false => ("One Sample t-test", "mean of x", "mean"),
+    };
+    finish(
+        (mx - params.mu) / std_err,
+        n - 1.,
+        std_err,
+        params,
+        vec![(estimate.to_string(), mx)],
+        null_name,
+        method,
+        data_name,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn finish(
+    t: f64,
+    df: f64,
+    std_err: f64,
+    params: &TTestParams,
+    estimate: Vec<(String, f64)>,
+    null_name: &str,
+    method: &str,
+    data_name: &str,
+) -> HTest {
+    let dist = StudentsT::new(0., 1., df).unwrap();
+    let level = params.conf_level;
+    assert!(
+        0. < level && level < 1.,
+        "'conf.level' must be a single number between 0 and 1"
+    );
+    let [lower, upper] = match params.alternative {
+        Alternative::TwoSided => {
+            let q = dist.inverse_cdf(1. - (1. - level) / 2.);
+            [t - q, t + q]
+        }
+        Alternative::Less => [f64::NEG_INFINITY, t + dist.inverse_cdf(level)],
+        Alternative::Greater => [t - dist.inverse_cdf(level), f64::INFINITY],
+    };
+    HTest {
+        statistic: Some(("t".to_string(), t)),
+        parameter: vec![("df".to_string(), df)],
+        p_value: p_value(params.alternative, dist.cdf(t), dist.sf(t)),
+        conf_int: Some(ConfInt {
+            lower: params.mu + lower * std_err,
+            upper: params.mu + upper * std_err,
+            level,
+        }),
+        estimate,
+        null_value: vec![(null_name.to_string(), params.mu)],
+        alternative: Some(params.alternative),
+        method: method.to_string(),
+        data_name: data_name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// R's `sleep$extra` for each group
+    const SLEEP: [[f64; 10]; 2] = [
+        [0.7, -1.6, -0.2, -1.2, -0.1, 3.4, 3.7, 0.8, 0.0, 2.0],
+        [1.9, 0.8, 1.1, 0.1, -0.1, 4.4, 5.5, 1.6, 4.6, 3.4],
+    ];
+
+    fn assert_close(a: f64, b: f64, tol: f64) {
+        assert!((a - b).abs() < tol, "{a} != {b}");
+    }
+
+    #[test]
+    fn test_welch() {
+        let test = t_test2(SLEEP[0], SLEEP[1], TTestParams::new());
+        assert_close(test.statistic(), -1.860813, 1e-6);
+        assert_close(test.parameter[0].1, 17.77647, 1e-5);
+        assert_close(test.p_value, 0.07939414, 1e-7);
+        let conf_int = test.conf_int.unwrap();
+        assert_close(conf_int.lower, -3.3654832, 1e-6);
+        assert_close(conf_int.upper, 0.2054832, 1e-6);
+        assert!(
+            test.to_string()
+                .contains("t = -1.8608, df = 17.776, p-value = 0.07939")
+        );
+    }
+
+    #[test]
+    fn test_pooled_and_paired() {
+        let mut params = TTestParams::new();
+        params.var_equal = true;
+        let test = t_test2(SLEEP[0], SLEEP[1], params);
+        assert_eq!(test.parameter[0].1, 18.);
+        assert_close(test.p_value, 0.07918671, 1e-7);
+        assert_close(test.conf_int.unwrap().lower, -3.363874, 1e-6);
+        assert!(test.to_string().starts_with("\n\tTwo Sample t-test\n"));
+
+        params.paired = true;
+        let test = t_test2(SLEEP[0], SLEEP[1], params);
+        assert_close(test.statistic(), -4.062128, 1e-6);
+        assert_close(test.p_value, 0.002832890, 1e-8);
+        assert_close(test.conf_int.unwrap().upper, -0.7001142, 1e-6);
+        assert_close(test.estimate[0].1, -1.58, 1e-12);
+    }
+
+    #[test]
+    fn test_one_sample() {
+        let mut params = TTestParams::new();
+        params.alternative = Alternative::Greater;
+        params.mu = 1.;
+        let test = t_test(SLEEP[1], params);
+        // the same statistic as a two-sided test, half its p-value when above `mu`
+        let two_sided = t_test(
+            SLEEP[1],
+            TTestParams {
+                alternative: Alternative::TwoSided,
+                ..params
+            },
+        );
+        assert_close(test.p_value, two_sided.p_value / 2., 1e-12);
+        assert_eq!(test.conf_int.unwrap().upper, f64::INFINITY);
+        assert!(test.to_string().contains("true mean is greater than 1\n"));
+    }
+}
@@ -0,0 +1,98 @@
+use statrs::distribution::{ContinuousCDF, FisherSnedecor};
+
+use super::{Alternative, ConfInt, HTest, p_value};
+use crate::vector::var;
+
+#[derive(Debug, Clone, Copy)]
+pub struct VarParams {
+    /// the ratio of the variances under the null hypothesis
+    pub ratio: f64,
+    pub alternative: Alternative,
+    pub conf_level: f64,
+}
+impl Default for VarParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl VarParams {
+    pub fn new() -> Self {
+        Self {
+            ratio: 1.,
+            alternative: Alternative::TwoSided,
+            conf_level: 0.95,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<VarParams> for VarParams { fn as_ref(&self) -> &VarParams { self } }
+
+/// `var.test(x, y)`: F test of the ratio of two normal variances.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/var.test.R>
+pub fn var_test(
+    x: impl AsRef<[f64]>,
+    y: impl AsRef<[f64]>,
+    params: impl AsRef<VarParams>,
+) -> HTest {
+    let (x, y) = (x.as_ref(), y.as_ref());
+    let params = params.as_ref();
+    assert!(
+        0. < params.ratio && params.ratio.is_finite(),
+        "'ratio' must be a single positive number"
+    );
+    let (df_x, df_y) = ((x.len() as f64) - 1., (y.len() as f64) - 1.);
+    assert!(1. <= df_x, "not enough 'x' observations");
+    assert!(1. <= df_y, "not enough 'y' observations");
+    let estimate = var(x) / var(y);
+    let statistic = estimate / params.ratio;
+    let level = params.conf_level;
+    let f = FisherSnedecor::new(df_x, df_y).unwrap();
+    let (lower, upper) = match params.alternative {
+        Alternative::TwoSided => {
+            let beta = (1. - level) / 2.;
+            (
+                estimate / f.inverse_cdf(1. - beta),
+                estimate / f.inverse_cdf(beta),
+            )
+        }
+        Alternative::Greater => (estimate / f.inverse_cdf(level), f64::INFINITY),
+        Alternative::Less => (0., estimate / f.inverse_cdf(1. - level)),
+    };
+    HTest {
+        statistic: Some(("F".to_string(), statistic)),
+        parameter: vec![("num df".to_string(), df_x), ("denom df".to_string(), df_y)],
+        p_value: p_value(params.alternative, f.cdf(statistic), f.sf(statistic)),
+        conf_int: Some(ConfInt {
+            lower,
+            upper,
+            level,
+        }),
+        estimate: vec![("ratio of variances".to_string(), estimate)],
+        null_value: vec![("ratio of variances".to_string(), params.ratio)],
+        alternative: Some(params.alternative),
+        method: "F test to compare two variances".to_string(),
+        data_name: "x and y".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_var() {
+        let x = [0.7, -1.6, -0.2, -1.2, -0.1, 3.4, 3.7, 0.8, 0.0, 2.0];
+        let y = [1.9, 0.8, 1.1, 0.1, -0.1, 4.4, 5.5, 1.6, 4.6, 3.4];
+        let test = var_test(x, y, VarParams::new());
+        assert!((test.statistic() - 0.7983426).abs() < 1e-7);
+        assert!((test.p_value - 0.7427199).abs() < 1e-6);
+        let conf_int = test.conf_int.unwrap();
+        // symmetric on the log scale for equal degrees of freedom
+        assert!((conf_int.lower * conf_int.upper - test.statistic().powi(2)).abs() < 1e-12);
+        assert!(
+            test.to_string()
+                .contains("F = 0.79834, num df = 9, denom df = 9, p-value = 0.7427\n")
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use super::{Alternative, HTest, p_value};
+use crate::{vector::rank, warning::warning};
+
+#[derive(Debug, Clone, Copy)]
+pub struct WilcoxParams {
+    pub alternative: Alternative,
+    /// the location, or location shift, under the null hypothesis
+    pub mu: f64,
+    /// whether `wilcox_test2` runs the signed rank test on paired differences
+    pub paired: bool,
+    /// exact p-value; `None` for exact with fewer than 50 observations
+    pub exact: Option<bool>,
+    /// continuity correction in the normal approximation
+    pub correct: bool,
+}
+impl Default for WilcoxParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl WilcoxParams {
+    pub fn new() -> Self {
+        Self {
+            alternative: Alternative::TwoSided,
+            mu: 0.,
+            paired: false,
+            exact: None,
+            correct: true,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<WilcoxParams> for WilcoxParams { fn as_ref(&self) -> &WilcoxParams { self } }
+
+/// Wilcoxon signed rank `wilcox.test(x)`.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/wilcox.test.R>
+pub fn wilcox_test(x: impl AsRef<[f64]>, params: impl AsRef<WilcoxParams>) -> HTest {
+    signed_rank(x.as_ref(), params.as_ref(), "x")
+}
+/// Wilcoxon rank sum (Mann-Whitney) `wilcox.test(x, y)`, or the signed rank test on `x - y`
+/// when `params.paired`.
+pub fn wilcox_test2(
+    x: impl AsRef<[f64]>,
+    y: impl AsRef<[f64]>,
+    params: impl AsRef<WilcoxParams>,
+) -> HTest {
+    let (x, y) = (x.as_ref(), y.as_ref());
+    let params = params.as_ref();
+    if params.paired {
+        assert_eq!(x.len(), y.len(), "'x' and 'y' must have the same length");
+        let d: Vec<f64> = x.iter().zip(y).map(|(x, y)| x - y).collect();
+        return signed_rank(&d, params, "x and y");
+    }
+    assert!(!x.is_empty(), "not enough (non-missing) 'x' observations");
+    assert!(!y.is_empty(), "not enough 'y' observations");
+    let (nx, ny) = (x.len(), y.len());
+    let r = rank(
+        x.iter()
+            .map(|x| x - params.mu)
+            .chain(y.iter().copied())
+            .collect::<Vec<f64>>(),
+    );
+    let w = r[..nx].iter().sum::<f64>() - (nx * (nx + 1)) as f64 / 2.;
+    let ties = tie_sizes(&r);
+    let exact = params.exact.unwrap_or(nx < 50 && ny < 50);
+    let mut method = "Wilcoxon rank sum test".to_string();
+    let p_value = if exact && ties.is_empty() {
+        method = "Wilcoxon rank sum exact test".to_string();
+        let counts = rank_sum_counts(nx, ny);
+        exact_p_value(params.alternative, &counts, w, (nx * ny) as f64 / 2.)
+    } else {
+        let (nx, ny) = (nx as f64, ny as f64);
+        let n = nx + ny;
+        let tie_sum: f64 = ties.iter().map(|t| t.powi(3) - t).sum();
+        let sigma = (nx * ny / 12. * (n + 1. - tie_sum / (n * (n - 1.)))).sqrt();
+        if exact {
+            warning("cannot compute exact p-value with ties");
+        }
+        normal_p_value(params, &mut method, w - nx * ny / 2., sigma)
+    };
+    HTest {
+        statistic: Some(("W".to_string(), w)),
+        parameter: vec![],
+        p_value,
+        conf_int: None,
+        estimate: vec![],
+        null_value: vec![("location shift".to_string(), params.mu)],
+        alternative: Some(params.alternative),
+        method,
+        data_name: "x and y".to_string(),
+    }
+}
+
+fn signed_rank(x: &[f64], params: &WilcoxParams, data_name: &str) -> HTest {
+    let x: Vec<f64> = x.iter().map(|x| x - params.mu).collect();
+    let zeroes = x.contains(&0.);
+    let x: Vec<f64> = x.into_iter().filter(|&x| x != 0.).collect();
+    assert!(!x.is_empty(), "not enough (non-missing) 'x' observations");
+    let n = x.len();
+    let r = rank(x.iter().map(|x| x.abs()).collect::<Vec<f64>>());
+    let v: f64 = r
+        .iter()
+        .zip(&x)
+        .filter(|(_, x)| 0. < **x)
+        .map(|(r, _)| r)
+        .sum();
+    let ties = tie_sizes(&r);
+    let exact = params.exact.unwrap_or(n < 50);
+    let mut method = "Wilcoxon signed rank test".to_string();
+    let p_value = if exact && ties.is_empty() && !zeroes {
+        method = "Wilcoxon signed rank exact test".to_string();
+        let counts = signed_rank_counts(n);
+        exact_p_value(params.alternative, &counts, v, (n * (n + 1)) as f64 / 4.)
+    } else {
+        let n = n as f64;
+        let tie_sum: f64 = ties.iter().map(|t| t.powi(3) - t).sum();
+        let sigma = (n * (n + 1.) * (2. * n + 1.) / 24. - tie_sum / 48.).sqrt();
+        if exact && !ties.is_empty() {
+            warning("cannot compute exact p-value with ties");
+        }
+        if exact && zeroes {
+            warning("cannot compute exact p-value with zeroes");
+        }
+        normal_p_value(params, &mut method, v - n * (n + 1.) / 4., sigma)
+    };
+    let null_name = match data_name {
+        "x" => "location",
+        _ => "location shift",
+    };
+    HTest {
+        statistic: Some(("V".to_string(), v)),
+        parameter: vec![],
+        p_value,
+        conf_int: None,
+        estimate: vec![],
+        null_value: vec![(null_name.to_string(), params.mu)],
+        alternative: Some(params.alternative),
+        method,
+        data_name: data_name.to_string(),
+    }
+}
+
+/// Sizes of the groups of tied ranks.
+fn tie_sizes(ranks: &[f64]) -> Vec<f64> {
+    let mut sorted = ranks.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    sorted
+        .chunk_by(|a, b| a == b)
+        .filter(|group| 1 < group.len())
+        .map(|group| group.len() as f64)
+        .collect()
+}
+/// Normal approximation to the p-value of a statistic `z` away from its mean.
+fn normal_p_value(params: &WilcoxParams, method: &mut String, z: f64, sigma: f64) -> f64 {
+    let correction = match (params.correct, params.alternative) {
+        (false, _) => 0.,
+        (true, Alternative::TwoSided) => 0.5 * z.signum() * f64::from(z != 0.),
+        (true, Alternative::Greater) => 0.5,
+        (true, Alternative::Less) => -0.5,
+    };
+    if params.correct {
+        method.push_str(" with continuity correction");
+    }
+    let z = (z - correction) / sigma;
+    let normal = Normal::standard();
+    p_value(params.alternative, normal.cdf(z), normal.sf(z))
+}
+/// Exact p-value of an integer statistic from the number of arrangements giving each value.
+fn exact_p_value(alternative: Alternative, counts: &[f64], statistic: f64, center: f64) -> f64 {
+    let total: f64 = counts.iter().sum();
+    let k = statistic as usize;
+    let cdf = |k: usize| counts[..=k.min(counts.len() - 1)].iter().sum::<f64>() / total;
+    let upper = |k: usize| 1. - if k == 0 { 0. } else { cdf(k - 1) };
+    match alternative {
+        Alternative::TwoSided => {
+            let p = if center < statistic { upper(k) } else { cdf(k) };
+            (2. * p).min(1.)
+        }
+        Alternative::Less => cdf(k),
+        Alternative::Greater => upper(k),
+    }
+}
+/// Number of subsets of `1..=n` with each sum, the null distribution of the signed rank
+/// statistic (`dsignrank` times `2^n`).
+fn signed_rank_counts(n: usize) -> Vec<f64> {
+    let mut counts = vec![0.; n * (n + 1) / 2 + 1];
+    counts[0] = 1.;
+    for i in 1..=n {
+        for s in (i..=i * (i + 1) / 2).rev() {
+            counts[s] += counts[s - i];
+        }
+    }
+    counts
+}
+/// Number of ways `m` of `m + n` ranks exceed each count of the others, the null distribution of
+/// the rank sum statistic (`dwilcox` times `choose(m + n, m)`).
+fn rank_sum_counts(m: usize, n: usize) -> Vec<f64> {
+    // counts[j][s]: subsets of j of the ranks so far with rank sum s
+    let max_sum = (m * (2 * n + m + 1)) / 2;
+    let mut counts = vec![vec![0.; max_sum + 1]; m + 1];
+    counts[0][0] = 1.;
+    for rank in 1..=m + n {
+        for j in (1..=m.min(rank)).rev() {
+            let (below, above) = counts.split_at_mut(j);
+            for s in (rank..=max_sum).rev() {
+                above[0][s] += below[j - 1][s - rank];
+            }
+        }
+    }
+    counts.swap_remove(m).split_off(m * (m + 1) / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::warning::take_warnings;
+
+    const SLEEP: [[f64; 10]; 2] = [
+        [0.7, -1.6, -0.2, -1.2, -0.1, 3.4, 3.7, 0.8, 0.0, 2.0],
+        [1.9, 0.8, 1.1, 0.1, -0.1, 4.4, 5.5, 1.6, 4.6, 3.4],
+    ];
+
+    #[test]
+    fn test_counts() {
+        // R: dsignrank(0:6, 3) * 8 and dwilcox(0:6, 2, 3) * 10
+        assert_eq!(signed_rank_counts(3), [1., 1., 1., 2., 1., 1., 1.]);
+        assert_eq!(rank_sum_counts(2, 3), [1., 1., 2., 2., 2., 1., 1.]);
+    }
+
+    #[test]
+    fn test_exact() {
+        let x = [1.83, 0.50, 1.62, 2.48, 1.68, 1.88, 1.55, 3.06, 1.30];
+        #[allow(clippy::approx_constant)]
+        let y = [0.878, 0.647, 0.598, 2.05, 1.06, 1.29, 1.06, 3.14, 1.29];
+        let mut params = WilcoxParams::new();
+        params.paired = true;
+        params.alternative = Alternative::Greater;
+        let test = wilcox_test2(x, y, params);
+        assert_eq!(test.statistic(), 40.);
+        assert!((test.p_value - 0.01953125).abs() < 1e-10);
+        assert_eq!(test.method, "Wilcoxon signed rank exact test");
+
+        let x = [0.80, 0.83, 1.89, 1.04, 1.45, 1.38, 1.91, 1.64, 0.73, 1.46];
+        let y = [1.15, 0.88, 0.90, 0.74, 1.21];
+        params.paired = false;
+        let test = wilcox_test2(x, y, params);
+        assert_eq!(test.statistic(), 35.);
+        assert!((test.p_value - 0.1272061).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_ties() {
+        take_warnings();
+        let test = wilcox_test2(SLEEP[0], SLEEP[1], WilcoxParams::new());
+        assert_eq!(test.statistic(), 25.5);
+        assert!((test.p_value - 0.06932758).abs() < 1e-7);
+        assert_eq!(take_warnings(), ["cannot compute exact p-value with ties"]);
+
+        let mut params = WilcoxParams::new();
+        params.paired = true;
+        let test = wilcox_test2(SLEEP[0], SLEEP[1], params);
+        assert_eq!(test.statistic(), 0.);
+        assert!((test.p_value - 0.009090698).abs() < 1e-8);
+        assert_eq!(take_warnings().len(), 2);
+        assert_eq!(
+            test.method,
+            "Wilcoxon signed rank test with continuity correction"
+        );
+    }
+}
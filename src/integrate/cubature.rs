@@ -0,0 +1,321 @@
+use std::collections::BinaryHeap;
+
+use super::gauss_legendre;
+
+#[derive(Debug, Clone)]
+pub struct CubatureParams {
+    pub lower: Vec<f64>,
+    pub upper: Vec<f64>,
+    /// Same meaning as in `all_eq`: the error estimate is compared relative to `scale`, or to
+    /// `|value|` unless that is smaller than `tolerance`, in which case it is absolute.
+    pub tolerance: f64,
+    pub scale: Option<f64>,
+    /// maximum number of integrand evaluations
+    pub max_eval: usize,
+}
+impl CubatureParams {
+    pub fn new(lower: Vec<f64>, upper: Vec<f64>) -> Self {
+        Self {
+            lower,
+            upper,
+            tolerance: 1e-5,
+            scale: None,
+            max_eval: 1_000_000,
+        }
+    }
+    fn within_tolerance(&self, value: f64, abs_error: f64) -> bool {
+        let scale = match self.scale {
+            Some(scale) => scale,
+            None => {
+                let scale = value.abs();
+                let is_abs = !scale.is_finite() || scale < self.tolerance;
+                if is_abs { 1. } else { scale }
+            }
+        };
+        abs_error / scale < self.tolerance
+    }
+    fn dim(&self) -> usize {
+        assert_eq!(self.lower.len(), self.upper.len());
+        self.lower.len()
+    }
+}
+#[rustfmt::skip]
+impl AsRef<CubatureParams> for CubatureParams { fn as_ref(&self) -> &CubatureParams { self } }
+
+#[derive(Debug, Clone, Copy)]
+pub struct Cubature {
+    pub value: f64,
+    pub abs_error: f64,
+    pub evaluations: usize,
+    pub converged: bool,
+}
+
+struct Region {
+    center: Vec<f64>,
+    half_width: Vec<f64>,
+    value: f64,
+    error: f64,
+    split_dim: usize,
+}
+impl PartialEq for Region {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+impl Eq for Region {}
+impl PartialOrd for Region {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Region {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.error.total_cmp(&other.error)
+    }
+}
+
+/// Degree-7 Genz-Malik rule with an embedded degree-5 rule for the error estimate.
+///
+/// ref: <https://doi.org/10.1016/0771-050X(80)90039-X>
+/// ref: <https://github.com/stevengj/cubature/blob/master/hcubature.c#L455>
+struct GenzMalik {
+    dim: usize,
+}
+impl GenzMalik {
+    const LAMBDA2: f64 = 0.358_568_582_800_318_1;
+    const LAMBDA4: f64 = 0.948_683_298_050_513_8;
+    const LAMBDA5: f64 = 0.688_247_201_611_685_3;
+
+    fn points(&self) -> usize {
+        let n = self.dim;
+        1 + 4 * n + 2 * n * (n - 1) + (1 << n)
+    }
+    fn evaluate(
+        &self,
+        f: &impl Fn(&[f64]) -> f64,
+        center: Vec<f64>,
+        half_width: Vec<f64>,
+    ) -> Region {
+        let n = self.dim;
+        let nf = n as f64;
+        let w1 = (12824. - 9120. * nf + 400. * nf * nf) / 19683.;
+        let w2 = 980. / 6561.;
+        let w3 = (1820. - 400. * nf) / 19683.;
+        let w4 = 200. / 19683.;
+        let w5 = 6859. / 19683. / (1_u64 << n) as f64;
+        let e1 = (729. - 950. * nf + 50. * nf * nf) / 729.;
+        let e2 = 245. / 486.;
+        let e3 = (265. - 100. * nf) / 1458.;
+        let e4 = 25. / 729.;
+        let ratio = (Self::LAMBDA2 * Self::LAMBDA2) / (Self::LAMBDA4 * Self::LAMBDA4);
+
+        let mut x = center.clone();
+        let f0 = f(&x);
+        let mut sum2 = 0.;
+        let mut sum3 = 0.;
+        let mut split_dim = 0;
+        let mut max_diff = 0.;
+        for i in 0..n {
+            let mut axis = |lambda: f64| {
+                x[i] = center[i] - lambda * half_width[i];
+                let lo = f(&x);
+                x[i] = center[i] + lambda * half_width[i];
+                let hi = f(&x);
+                x[i] = center[i];
+                lo + hi
+            };
+            let s2 = axis(Self::LAMBDA2);
+            let s3 = axis(Self::LAMBDA4);
+            sum2 += s2;
+            sum3 += s3;
+            // split where the fourth difference is largest, preferring the widest dimension on ties
+            let diff = (s2 - 2. * f0 - ratio * (s3 - 2. * f0)).abs();
+            let tie = (diff - max_diff).abs() <= 1e-14 * max_diff;
+            let wider = half_width[split_dim] < half_width[i];
+            if i == 0 || (max_diff < diff && !tie) || (tie && wider) {
+                max_diff = max_diff.max(diff);
+                split_dim = i;
+            }
+        }
+        let mut sum4 = 0.;
+        for i in 0..n {
+            for j in i + 1..n {
+                for (si, sj) in [(-1., -1.), (-1., 1.), (1., -1.), (1., 1.)] {
+                    x[i] = center[i] + si * Self::LAMBDA4 * half_width[i];
+                    x[j] = center[j] + sj * Self::LAMBDA4 * half_width[j];
+                    sum4 += f(&x);
+                }
+                x[i] = center[i];
+                x[j] = center[j];
+            }
+        }
+        let mut sum5 = 0.;
+        for corner in 0..1_u64 << n {
+            for i in 0..n {
+                let sign = if corner >> i & 1 == 1 { 1. } else { -1. };
+                x[i] = center[i] + sign * Self::LAMBDA5 * half_width[i];
+            }
+            sum5 += f(&x);
+        }
+        let volume: f64 = half_width.iter().map(|h| 2. * h).product();
+        let value = volume * (w1 * f0 + w2 * sum2 + w3 * sum3 + w4 * sum4 + w5 * sum5);
+        let value5 = volume * (e1 * f0 + e2 * sum2 + e3 * sum3 + e4 * sum4);
+        Region {
+            center,
+            half_width,
+            value,
+            error: (value - value5).abs(),
+            split_dim,
+        }
+    }
+}
+
+/// Adaptive cubature over a hyper-rectangle: the region with the largest error estimate is
+/// bisected until the total error is within tolerance or `max_eval` is exhausted.
+///
+/// ref: <https://github.com/stevengj/cubature/blob/master/hcubature.c>
+pub fn cubature(f: impl Fn(&[f64]) -> f64, params: impl AsRef<CubatureParams>) -> Cubature {
+    let params = params.as_ref();
+    let dim = params.dim();
+    assert!((2..=16).contains(&dim));
+    let rule = GenzMalik { dim };
+    let center: Vec<f64> = (0..dim)
+        .map(|i| (params.lower[i] + params.upper[i]) / 2.)
+        .collect();
+    let half_width: Vec<f64> = (0..dim)
+        .map(|i| (params.upper[i] - params.lower[i]) / 2.)
+        .collect();
+    let region = rule.evaluate(&f, center, half_width);
+    let mut evaluations = rule.points();
+    let mut value = region.value;
+    let mut abs_error = region.error;
+    let mut regions = BinaryHeap::from([region]);
+    loop {
+        if params.within_tolerance(value, abs_error) {
+            return Cubature {
+                value,
+                abs_error,
+                evaluations,
+                converged: true,
+            };
+        }
+        if params.max_eval < evaluations + 2 * rule.points() {
+            break;
+        }
+        let region = regions.pop().unwrap();
+        let d = region.split_dim;
+        let mut half_width = region.half_width;
+        half_width[d] /= 2.;
+        let mut lo = region.center;
+        let mut hi = lo.clone();
+        lo[d] -= half_width[d];
+        hi[d] += half_width[d];
+        let lo = rule.evaluate(&f, lo, half_width.clone());
+        let hi = rule.evaluate(&f, hi, half_width);
+        evaluations += 2 * rule.points();
+        value += lo.value + hi.value - region.value;
+        abs_error += lo.error + hi.error - region.error;
+        regions.push(lo);
+        regions.push(hi);
+    }
+    // recompute the sums to shed the rounding error accumulated by the updates
+    let value = regions.iter().map(|r| r.value).sum();
+    let abs_error = regions.iter().map(|r| r.error).sum();
+    Cubature {
+        value,
+        abs_error,
+        evaluations,
+        converged: params.within_tolerance(value, abs_error),
+    }
+}
+
+/// Tensor product of `n`-point Gauss-Legendre rules; the error is estimated against the
+/// `(n - 1)`-point product.
+pub fn product_gauss(
+    f: impl Fn(&[f64]) -> f64,
+    params: impl AsRef<CubatureParams>,
+    n: usize,
+) -> Cubature {
+    let params = params.as_ref();
+    let dim = params.dim();
+    assert!(1 < n && 0 < dim);
+    let product = |n: usize| {
+        let rules: Vec<_> = (0..dim)
+            .map(|i| gauss_legendre(n).rescale(params.lower[i], params.upper[i]))
+            .collect();
+        let mut index = vec![0; dim];
+        let mut x = vec![0.; dim];
+        let mut sum = 0.;
+        loop {
+            let mut w = 1.;
+            for (i, rule) in rules.iter().enumerate() {
+                x[i] = rule.nodes[index[i]];
+                w *= rule.weights[index[i]];
+            }
+            sum += w * f(&x);
+            // odometer increment over the grid of node indices
+            let Some(i) = index.iter().position(|&k| k + 1 < n) else {
+                break;
+            };
+            index[i] += 1;
+            index[..i].fill(0);
+        }
+        sum
+    };
+    let value = product(n);
+    let abs_error = (value - product(n - 1)).abs();
+    Cubature {
+        value,
+        abs_error,
+        evaluations: n.pow(dim as u32) + (n - 1).pow(dim as u32),
+        converged: params.within_tolerance(value, abs_error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn test_cubature() {
+        // ∫∫ over [0, π]² of sin(x) sin(y) = 4
+        let f = |x: &[f64]| x[0].sin() * x[1].sin();
+        let r = cubature(f, CubatureParams::new(vec![0., 0.], vec![PI, PI]));
+        assert!(r.converged);
+        assert!((r.value - 4.).abs() < 4. * 1e-5);
+        assert!((r.value - 4.).abs() <= r.abs_error);
+        // a Gaussian over [-1, 1]ᵈ: (√π erf(1))ᵈ
+        let f = |x: &[f64]| (-x.iter().map(|x| x * x).sum::<f64>()).exp();
+        let exact = 1.493_648_265_624_854_f64.powi(3);
+        let params = CubatureParams {
+            tolerance: 1e-7,
+            ..CubatureParams::new(vec![-1.; 3], vec![1.; 3])
+        };
+        let r = cubature(f, &params);
+        assert!(r.converged);
+        assert!((r.value - exact).abs() / exact < 1e-7);
+        let exact = 1.493_648_265_624_854_f64.powi(5);
+        let params = CubatureParams {
+            tolerance: 1e-10,
+            ..CubatureParams::new(vec![-1.; 5], vec![1.; 5])
+        };
+        let r = product_gauss(f, &params, 10);
+        assert!(r.converged);
+        assert!((r.value - exact).abs() / exact < 1e-10);
+        // a tiny integral is judged on the absolute scale, as in `all_eq`
+        let r = cubature(|_| 1e-9, CubatureParams::new(vec![0., 0.], vec![1., 1.]));
+        assert!(r.converged);
+        // budget exhausted on a discontinuity
+        let params = CubatureParams {
+            tolerance: 1e-12,
+            max_eval: 2000,
+            ..CubatureParams::new(vec![0., 0.], vec![1., 1.])
+        };
+        let r = cubature(|x| if x[0] + x[1] < 1. { 1. } else { 0. }, params);
+        assert!(!r.converged);
+        assert!((r.value - 0.5).abs() < 1e-2);
+        assert!(r.evaluations <= 2000);
+    }
+}
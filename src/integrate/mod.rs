@@ -0,0 +1,915 @@
+mod cubature;
+pub use cubature::*;
+
+use crate::vector;
+
+/// Composite trapezoid rule over samples `y` at (possibly unevenly spaced) points `x`.
+pub fn trapz(x: impl AsRef<[f64]>, y: impl AsRef<[f64]>) -> f64 {
+    let x = x.as_ref();
+    let y = y.as_ref();
+    assert_eq!(x.len(), y.len());
+    let mut sum = 0.;
+    for i in 1..x.len() {
+        sum += (x[i] - x[i - 1]) * (y[i] + y[i - 1]) / 2.;
+    }
+    sum
+}
+/// Composite Simpson's rule over samples `y` at (possibly unevenly spaced) points `x`.
+///
+/// An odd number of intervals is handled by correcting the last one with a quadratic through
+/// the final three points.
+///
+/// ref: <https://doi.org/10.1016/j.amc.2017.02.013>
+pub fn simpson(x: impl AsRef<[f64]>, y: impl AsRef<[f64]>) -> f64 {
+    let x = x.as_ref();
+    let y = y.as_ref();
+    assert_eq!(x.len(), y.len());
+    let n = x.len() - 1;
+    if n < 2 {
+        return trapz(x, y);
+    }
+    let h: Vec<f64> = (0..n).map(|i| x[i + 1] - x[i]).collect();
+    let mut sum = 0.;
+    let mut i = 0;
+    while i + 1 < n {
+        let (h0, h1) = (h[i], h[i + 1]);
+        let h01 = h0 + h1;
+        sum += h01 / 6.
+            * ((2. - h1 / h0) * y[i]
+                + h01 * h01 / (h0 * h1) * y[i + 1]
+                + (2. - h0 / h1) * y[i + 2]);
+        i += 2;
+    }
+    if !n.is_multiple_of(2) {
+        let (h0, h1) = (h[n - 2], h[n - 1]);
+        let alpha = (2. * h1 * h1 + 3. * h0 * h1) / (6. * (h0 + h1));
+        let beta = (h1 * h1 + 3. * h0 * h1) / (6. * h0);
+        let eta = h1 * h1 * h1 / (6. * h0 * (h0 + h1));
+        sum += alpha * y[n] + beta * y[n - 1] - eta * y[n - 2];
+    }
+    sum
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RuleParams {
+    pub lower: f64,
+    pub upper: f64,
+    /// number of intervals
+    pub n: usize,
+}
+#[rustfmt::skip]
+impl AsRef<RuleParams> for RuleParams { fn as_ref(&self) -> &RuleParams { self } }
+fn rule_samples(f: impl Fn(f64) -> f64, params: &RuleParams) -> (Vec<f64>, Vec<f64>) {
+    assert!(0 < params.n);
+    let h = (params.upper - params.lower) / params.n as f64;
+    let x: Vec<f64> = (0..=params.n)
+        .map(|i| params.lower + h * i as f64)
+        .collect();
+    let y = vector::sapply(&x, f);
+    (x, y)
+}
+pub fn trapezoid_rule(f: impl Fn(f64) -> f64, params: impl AsRef<RuleParams>) -> f64 {
+    let (x, y) = rule_samples(f, params.as_ref());
+    trapz(x, y)
+}
+pub fn simpson_rule(f: impl Fn(f64) -> f64, params: impl AsRef<RuleParams>) -> f64 {
+    let (x, y) = rule_samples(f, params.as_ref());
+    simpson(x, y)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct IntegrateParams {
+    pub lower: f64,
+    pub upper: f64,
+    /// maximum number of subintervals
+    pub subdivisions: usize,
+    pub rel_tol: f64,
+    pub abs_tol: f64,
+}
+impl IntegrateParams {
+    /// R's defaults: `subdivisions = 100`, `rel.tol = .Machine$double.eps^0.25`, `abs.tol = rel.tol`.
+    pub fn new(lower: f64, upper: f64) -> Self {
+        let tol = f64::EPSILON.powf(0.25);
+        Self {
+            lower,
+            upper,
+            subdivisions: 100,
+            rel_tol: tol,
+            abs_tol: tol,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<IntegrateParams> for IntegrateParams { fn as_ref(&self) -> &IntegrateParams { self } }
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/integrate.R#L80>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntegrateStatus {
+    Ok,
+    MaxSubdivisions,
+    RoundOff,
+    BadIntegrand,
+    RoundOffInExtrapolation,
+    ProbablyDivergent,
+    InvalidInput,
+}
+#[derive(Debug, Clone, Copy)]
+pub struct Integral {
+    pub value: f64,
+    pub abs_error: f64,
+    pub subdivisions: usize,
+    pub status: IntegrateStatus,
+}
+
+/// Adaptive quadrature with Wynn's epsilon extrapolation, as in R's `integrate()`: 21-point
+/// Gauss-Kronrod on finite ranges (QUADPACK `dqags`) and 15-point Gauss-Kronrod after mapping
+/// infinite ranges onto `(0, 1]` (QUADPACK `dqagi`).
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/appl/integrate.c>
+pub fn integrate(f: impl Fn(f64) -> f64, params: impl AsRef<IntegrateParams>) -> Integral {
+    let params = params.as_ref();
+    let invalid = Integral {
+        value: 0.,
+        abs_error: 0.,
+        subdivisions: 0,
+        status: IntegrateStatus::InvalidInput,
+    };
+    if params.lower.is_nan() || params.upper.is_nan() || params.subdivisions < 1 {
+        return invalid;
+    }
+    if params.abs_tol <= 0. && params.rel_tol < (50. * f64::EPSILON).max(0.5e-28) {
+        return invalid;
+    }
+    let (lower, upper) = (params.lower, params.upper);
+    if lower.is_finite() && upper.is_finite() {
+        return qagse(|a, b| qk21(&f, a, b), [lower, upper], params);
+    }
+    if lower == upper {
+        return Integral {
+            status: IntegrateStatus::Ok,
+            ..invalid
+        };
+    }
+    // integrate over `(bound, ∞)`, `(-∞, bound)` or `(-∞, ∞)` and flip the sign for reversed limits
+    let (bound, inf, sign) = match (lower.is_finite(), upper.is_finite()) {
+        (true, false) if 0. < upper => (lower, 1, 1.),
+        (true, false) => (lower, -1, -1.),
+        (false, true) if lower < 0. => (upper, -1, 1.),
+        (false, true) => (upper, 1, -1.),
+        _ if lower < upper => (0., 2, 1.),
+        _ => (0., 2, -1.),
+    };
+    let mut out = qagse(|a, b| qk15i(&f, bound, inf, a, b), [0., 1.], params);
+    out.value *= sign;
+    out
+}
+
+/// `(result, abserr, resabs, resasc)`: the Kronrod estimate, its error, and the integrals of
+/// `|f|` and `|f - mean(f)|` over the interval.
+type RuleOutput = (f64, f64, f64, f64);
+
+fn qk_error(resk_minus_resg: f64, resabs: f64, resasc: f64) -> f64 {
+    let mut abserr = resk_minus_resg.abs();
+    if resasc != 0. && abserr != 0. {
+        abserr = resasc * 1_f64.min((200. * abserr / resasc).powf(1.5));
+    }
+    if f64::MIN_POSITIVE / (50. * f64::EPSILON) < resabs {
+        abserr = abserr.max(f64::EPSILON * 50. * resabs);
+    }
+    abserr
+}
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/appl/integrate.c#L1557>
+#[allow(clippy::excessive_precision)]
+fn qk21(f: impl Fn(f64) -> f64, a: f64, b: f64) -> RuleOutput {
+    #[rustfmt::skip]
+    const WG: [f64; 5] = [
+        0.066671344308688137593568809893332, 0.149451349150580593145776339657697,
+        0.219086362515982043995534934228163, 0.269266719309996355091226921569469,
+        0.295524224714752870173892994651338,
+    ];
+    #[rustfmt::skip]
+    const XGK: [f64; 11] = [
+        0.995657163025808080735527280689003, 0.973906528517171720077964012084452,
+        0.930157491355708226001207180059508, 0.865063366688984510732096688423493,
+        0.780817726586416897063717578345042, 0.679409568299024406234327365114874,
+        0.562757134668604683339000099272694, 0.433395394129247190799265943165784,
+        0.294392862701460198131126603103866, 0.148874338981631210884826001129720,
+        0.,
+    ];
+    #[rustfmt::skip]
+    const WGK: [f64; 11] = [
+        0.011694638867371874278064396062192, 0.032558162307964727478818972459390,
+        0.054755896574351996031381300244580, 0.075039674810919952767043140916190,
+        0.093125454583697605535065465083366, 0.109387158802297641899210590325805,
+        0.123491976262065851077557956271247, 0.134709217311473325928054001771707,
+        0.142775938577060080797094273138717, 0.147739104901338491374841515972068,
+        0.149445554002916905664936468389821,
+    ];
+    let centr = 0.5 * (a + b);
+    let hlgth = 0.5 * (b - a);
+    let dhlgth = hlgth.abs();
+    let mut fv1 = [0.; 10];
+    let mut fv2 = [0.; 10];
+    let mut resg = 0.;
+    let fc = f(centr);
+    let mut resk = WGK[10] * fc;
+    let mut resabs = resk.abs();
+    for j in 0..10 {
+        let absc = hlgth * XGK[j];
+        let fval1 = f(centr - absc);
+        let fval2 = f(centr + absc);
+        fv1[j] = fval1;
+        fv2[j] = fval2;
+        let fsum = fval1 + fval2;
+        // the odd Kronrod nodes are the 10-point Gauss nodes
+        if j % 2 == 1 {
+            resg += WG[j / 2] * fsum;
+        }
+        resk += WGK[j] * fsum;
+        resabs += WGK[j] * (fval1.abs() + fval2.abs());
+    }
+    let reskh = resk * 0.5;
+    let mut resasc = WGK[10] * (fc - reskh).abs();
+    for j in 0..10 {
+        resasc += WGK[j] * ((fv1[j] - reskh).abs() + (fv2[j] - reskh).abs());
+    }
+    let result = resk * hlgth;
+    let resabs = resabs * dhlgth;
+    let resasc = resasc * dhlgth;
+    let abserr = qk_error((resk - resg) * hlgth, resabs, resasc);
+    (result, abserr, resabs, resasc)
+}
+
+/// 15-point Gauss-Kronrod on `(a, b) ⊂ (0, 1]` after substituting `x = bound + sign (1 - t) / t`;
+/// `inf` is 1 for `(bound, ∞)`, -1 for `(-∞, bound)` and 2 for `(-∞, ∞)`.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/appl/integrate.c#L1375>
+#[allow(clippy::excessive_precision)]
+fn qk15i(f: impl Fn(f64) -> f64, boun: f64, inf: i32, a: f64, b: f64) -> RuleOutput {
+    #[rustfmt::skip]
+    const WG: [f64; 8] = [
+        0., 0.129484966168869693270611432679082,
+        0., 0.279705391489276667901467771423780,
+        0., 0.381830050505118944950369775488975,
+        0., 0.417959183673469387755102040816327,
+    ];
+    #[rustfmt::skip]
+    const XGK: [f64; 8] = [
+        0.991455371120812639206854697526329, 0.949107912342758524526189684047851,
+        0.864864423359769072789712788640926, 0.741531185599394439863864773280788,
+        0.586087235467691130294144845693013, 0.405845151377397166906606412076961,
+        0.207784955007898467600689403773245, 0.,
+    ];
+    #[rustfmt::skip]
+    const WGK: [f64; 8] = [
+        0.022935322010529224963732008058970, 0.063092092629978553290700663189204,
+        0.104790010322250183839876322541518, 0.140653259715525918745189590510238,
+        0.169004726639267902826583426598550, 0.190350578064785409913256402421014,
+        0.204432940075298892414161999234649, 0.209482141084727828012999174891714,
+    ];
+    let dinf = f64::from(inf.min(1));
+    let transformed = |t: f64| {
+        let x = boun + dinf * (1. - t) / t;
+        let mut y = f(x);
+        if inf == 2 {
+            y += f(-x);
+        }
+        y / t / t
+    };
+    let centr = 0.5 * (a + b);
+    let hlgth = 0.5 * (b - a);
+    let fc = transformed(centr);
+    let mut resg = WG[7] * fc;
+    let mut resk = WGK[7] * fc;
+    let mut resabs = resk.abs();
+    let mut fv1 = [0.; 7];
+    let mut fv2 = [0.; 7];
+    for j in 0..7 {
+        let absc = hlgth * XGK[j];
+        let fval1 = transformed(centr - absc);
+        let fval2 = transformed(centr + absc);
+        fv1[j] = fval1;
+        fv2[j] = fval2;
+        let fsum = fval1 + fval2;
+        resg += WG[j] * fsum;
+        resk += WGK[j] * fsum;
+        resabs += WGK[j] * (fval1.abs() + fval2.abs());
+    }
+    let reskh = resk * 0.5;
+    let mut resasc = WGK[7] * (fc - reskh).abs();
+    for j in 0..7 {
+        resasc += WGK[j] * ((fv1[j] - reskh).abs() + (fv2[j] - reskh).abs());
+    }
+    let result = resk * hlgth;
+    let resasc = resasc * hlgth;
+    let resabs = resabs * hlgth;
+    let abserr = qk_error((resk - resg) * hlgth, resabs, resasc);
+    (result, abserr, resabs, resasc)
+}
+
+/// The globally adaptive driver shared by `dqagse` and `dqagie`; arrays are 1-based as in QUADPACK.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/appl/integrate.c#L545>
+fn qagse(
+    rule: impl Fn(f64, f64) -> RuleOutput,
+    [a, b]: [f64; 2],
+    params: &IntegrateParams,
+) -> Integral {
+    let epmach = f64::EPSILON;
+    let uflow = f64::MIN_POSITIVE;
+    let oflow = f64::MAX;
+    let limit = params.subdivisions;
+    let (epsabs, epsrel) = (params.abs_tol, params.rel_tol);
+    let mut alist = vec![0.; limit + 1];
+    let mut blist = vec![0.; limit + 1];
+    let mut rlist = vec![0.; limit + 1];
+    let mut elist = vec![0.; limit + 1];
+    let mut iord = vec![0; limit + 1];
+    let mut rlist2 = [0.; 53];
+    let mut res3la = [0.; 4];
+    alist[1] = a;
+    blist[1] = b;
+
+    let mut ier = 0;
+    let mut ierro = 0;
+    let (mut result, mut abserr, defabs, resabs) = rule(a, b);
+    let dres = result.abs();
+    let mut errbnd = epsabs.max(epsrel * dres);
+    let mut last = 1;
+    rlist[1] = result;
+    elist[1] = abserr;
+    iord[1] = 1;
+    if abserr <= 100. * epmach * defabs && errbnd < abserr {
+        ier = 2;
+    }
+    if limit == 1 {
+        ier = 1;
+    }
+    let finish = |ier: i32, result: f64, abserr: f64, last: usize| {
+        let ier = if 2 < ier { ier - 1 } else { ier };
+        let status = match ier {
+            0 => IntegrateStatus::Ok,
+            1 => IntegrateStatus::MaxSubdivisions,
+            2 => IntegrateStatus::RoundOff,
+            3 => IntegrateStatus::BadIntegrand,
+            4 => IntegrateStatus::RoundOffInExtrapolation,
+            5 => IntegrateStatus::ProbablyDivergent,
+            _ => IntegrateStatus::InvalidInput,
+        };
+        Integral {
+            value: result,
+            abs_error: abserr,
+            subdivisions: last,
+            status,
+        }
+    };
+    if ier != 0 || (abserr <= errbnd && abserr != resabs) || abserr == 0. {
+        return finish(ier, result, abserr, last);
+    }
+
+    rlist2[1] = result;
+    let mut errmax = abserr;
+    let mut maxerr = 1;
+    let mut area = result;
+    let mut errsum = abserr;
+    abserr = oflow;
+    let mut nrmax = 1;
+    let mut nres = 0;
+    let mut numrl2 = 2;
+    let mut ktmin = 0;
+    let mut extrap = false;
+    let mut noext = false;
+    let (mut iroff1, mut iroff2, mut iroff3) = (0, 0, 0);
+    let ksgn = if (1. - 50. * epmach) * defabs <= dres {
+        1
+    } else {
+        -1
+    };
+    let mut small = 0.;
+    let mut erlarg = 0.;
+    let mut ertest = 0.;
+    let mut correc = 0.;
+
+    enum Exit {
+        /// sum the subinterval results (label 115)
+        Sum,
+        /// check the extrapolated result (label 100)
+        Extrapolated,
+    }
+    let mut exit = Exit::Extrapolated;
+    for l in 2..=limit {
+        last = l;
+        let a1 = alist[maxerr];
+        let b1 = 0.5 * (alist[maxerr] + blist[maxerr]);
+        let a2 = b1;
+        let b2 = blist[maxerr];
+        let erlast = errmax;
+        let (area1, error1, _, defab1) = rule(a1, b1);
+        let (area2, error2, _, defab2) = rule(a2, b2);
+        let area12 = area1 + area2;
+        let erro12 = error1 + error2;
+        errsum = errsum + erro12 - errmax;
+        area = area + area12 - rlist[maxerr];
+        if defab1 != error1 && defab2 != error2 {
+            if (rlist[maxerr] - area12).abs() <= 1e-5 * area12.abs() && 0.99 * errmax <= erro12 {
+                if extrap {
+                    iroff2 += 1;
+                } else {
+                    iroff1 += 1;
+                }
+            }
+            if 10 < last && errmax < erro12 {
+                iroff3 += 1;
+            }
+        }
+        rlist[maxerr] = area1;
+        rlist[last] = area2;
+        errbnd = epsabs.max(epsrel * area.abs());
+        if 10 <= iroff1 + iroff2 || 20 <= iroff3 {
+            ier = 2;
+        }
+        if 5 <= iroff2 {
+            ierro = 3;
+        }
+        if last == limit {
+            ier = 1;
+        }
+        if a1.abs().max(b2.abs()) <= (1. + 100. * epmach) * (a2.abs() + 1000. * uflow) {
+            ier = 4;
+        }
+        if error2 <= error1 {
+            alist[last] = a2;
+            blist[maxerr] = b1;
+            blist[last] = b2;
+            elist[maxerr] = error1;
+            elist[last] = error2;
+        } else {
+            alist[maxerr] = a2;
+            alist[last] = a1;
+            blist[last] = b1;
+            rlist[maxerr] = area2;
+            rlist[last] = area1;
+            elist[maxerr] = error2;
+            elist[last] = error1;
+        }
+        qpsrt(
+            limit,
+            last,
+            &mut maxerr,
+            &mut errmax,
+            &elist,
+            &mut iord,
+            &mut nrmax,
+        );
+        if errsum <= errbnd {
+            exit = Exit::Sum;
+            break;
+        }
+        if ier != 0 {
+            break;
+        }
+        if last == 2 {
+            small = (b - a).abs() * 0.375;
+            erlarg = errsum;
+            ertest = errbnd;
+            rlist2[2] = area;
+            continue;
+        }
+        if noext {
+            continue;
+        }
+        erlarg -= erlast;
+        if small < (b1 - a1).abs() {
+            erlarg += erro12;
+        }
+        if !extrap {
+            if small < (blist[maxerr] - alist[maxerr]).abs() {
+                continue;
+            }
+            extrap = true;
+            nrmax = 2;
+        }
+        if ierro != 3 && ertest < erlarg {
+            // the smallest interval has the largest error: bisect the larger intervals first
+            let id = nrmax;
+            let jupbnd = if 2 + limit / 2 < last {
+                limit + 3 - last
+            } else {
+                last
+            };
+            let mut larger_interval_left = false;
+            for _ in id..=jupbnd {
+                maxerr = iord[nrmax];
+                errmax = elist[maxerr];
+                if small < (blist[maxerr] - alist[maxerr]).abs() {
+                    larger_interval_left = true;
+                    break;
+                }
+                nrmax += 1;
+            }
+            if larger_interval_left {
+                continue;
+            }
+        }
+        numrl2 += 1;
+        rlist2[numrl2] = area;
+        let (reseps, abseps) = qelg(&mut numrl2, &mut rlist2, &mut res3la, &mut nres);
+        ktmin += 1;
+        if 5 < ktmin && abserr < 1e-3 * errsum {
+            ier = 5;
+        }
+        if abseps < abserr {
+            ktmin = 0;
+            abserr = abseps;
+            result = reseps;
+            correc = erlarg;
+            ertest = epsabs.max(epsrel * reseps.abs());
+            if abserr <= ertest {
+                break;
+            }
+        }
+        if numrl2 == 1 {
+            noext = true;
+        }
+        if ier == 5 {
+            break;
+        }
+        maxerr = iord[1];
+        errmax = elist[maxerr];
+        nrmax = 1;
+        extrap = false;
+        small *= 0.5;
+        erlarg = errsum;
+    }
+
+    if let Exit::Extrapolated = exit {
+        if abserr == oflow {
+            exit = Exit::Sum;
+        } else if ier + ierro != 0 {
+            if ierro == 3 {
+                abserr += correc;
+            }
+            if ier == 0 {
+                ier = 3;
+            }
+            if result != 0. && area != 0. {
+                if errsum / area.abs() < abserr / result.abs() {
+                    exit = Exit::Sum;
+                }
+            } else if errsum < abserr {
+                exit = Exit::Sum;
+            } else if area == 0. {
+                return finish(ier, result, abserr, last);
+            }
+        }
+        if let Exit::Extrapolated = exit {
+            // test on divergence
+            if ksgn == -1 && result.abs().max(area.abs()) <= defabs * 0.01 {
+                return finish(ier, result, abserr, last);
+            }
+            let ratio = result / area;
+            if !(0.01..=100.).contains(&ratio) || area.abs() < errsum {
+                ier = 6;
+            }
+            return finish(ier, result, abserr, last);
+        }
+    }
+    let result = rlist[1..=last].iter().sum();
+    finish(ier, result, errsum, last)
+}
+
+/// Maintain `iord` so that `elist[iord[1..=nrmax]]` are the largest errors in decreasing order.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/appl/integrate.c#L2035>
+fn qpsrt(
+    limit: usize,
+    last: usize,
+    maxerr: &mut usize,
+    ermax: &mut f64,
+    elist: &[f64],
+    iord: &mut [usize],
+    nrmax: &mut usize,
+) {
+    if last <= 2 {
+        iord[1] = 1;
+        iord[2] = 2;
+    } else {
+        let errmax = elist[*maxerr];
+        if *nrmax != 1 {
+            for _ in 1..*nrmax {
+                let isucc = iord[*nrmax - 1];
+                if errmax <= elist[isucc] {
+                    break;
+                }
+                iord[*nrmax] = isucc;
+                *nrmax -= 1;
+            }
+        }
+        let jupbn = if limit / 2 + 2 < last {
+            limit + 3 - last
+        } else {
+            last
+        };
+        let errmin = elist[last];
+        let jbnd = jupbn - 1;
+        let ibeg = *nrmax + 1;
+        let mut inserted = false;
+        for i in ibeg..=jbnd {
+            let isucc = iord[i];
+            if elist[isucc] <= errmax {
+                // insert errmax by traversing the list top-down
+                iord[i - 1] = *maxerr;
+                let mut k = jbnd;
+                let mut placed = false;
+                for _ in i..=jbnd {
+                    let isucc = iord[k];
+                    if errmin < elist[isucc] {
+                        iord[k + 1] = last;
+                        placed = true;
+                        break;
+                    }
+                    iord[k + 1] = isucc;
+                    k -= 1;
+                }
+                if !placed {
+                    iord[i] = last;
+                }
+                inserted = true;
+                break;
+            }
+            iord[i - 1] = isucc;
+        }
+        if !inserted {
+            iord[jbnd] = *maxerr;
+            iord[jupbn] = last;
+        }
+    }
+    *maxerr = iord[*nrmax];
+    *ermax = elist[*maxerr];
+}
+
+/// Wynn's epsilon algorithm; `epstab[1..=n]` holds the sequence and returns `(result, abserr)`.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/appl/integrate.c#L1832>
+fn qelg(
+    n: &mut usize,
+    epstab: &mut [f64; 53],
+    res3la: &mut [f64; 4],
+    nres: &mut usize,
+) -> (f64, f64) {
+    let epmach = f64::EPSILON;
+    let oflow = f64::MAX;
+    *nres += 1;
+    let mut abserr = oflow;
+    let mut result = epstab[*n];
+    if *n < 3 {
+        abserr = abserr.max(5. * epmach * result.abs());
+        return (result, abserr);
+    }
+    const LIMEXP: usize = 50;
+    epstab[*n + 2] = epstab[*n];
+    let newelm = (*n - 1) / 2;
+    epstab[*n] = oflow;
+    let num = *n;
+    let mut k1 = *n;
+    let mut converged = false;
+    for i in 1..=newelm {
+        let k2 = k1 - 1;
+        let k3 = k1 - 2;
+        let res = epstab[k1 + 2];
+        let e0 = epstab[k3];
+        let e1 = epstab[k2];
+        let e2 = res;
+        let e1abs = e1.abs();
+        let delta2 = e2 - e1;
+        let err2 = delta2.abs();
+        let tol2 = e2.abs().max(e1abs) * epmach;
+        let delta3 = e1 - e0;
+        let err3 = delta3.abs();
+        let tol3 = e1abs.max(e0.abs()) * epmach;
+        if err2 <= tol2 && err3 <= tol3 {
+            // e0, e1 and e2 are equal to within machine accuracy; convergence is assumed
+            result = res;
+            abserr = err2 + err3;
+            converged = true;
+            break;
+        }
+        let e3 = epstab[k1];
+        epstab[k1] = e1;
+        let delta1 = e1 - e3;
+        let err1 = delta1.abs();
+        let tol1 = e1abs.max(e3.abs()) * epmach;
+        if err1 <= tol1 || err2 <= tol2 || err3 <= tol3 {
+            *n = i + i - 1;
+            break;
+        }
+        let ss = 1. / delta1 + 1. / delta2 - 1. / delta3;
+        let epsinf = (ss * e1).abs();
+        if epsinf <= 1e-4 {
+            *n = i + i - 1;
+            break;
+        }
+        let res = e1 + 1. / ss;
+        epstab[k1] = res;
+        k1 -= 2;
+        let error = err2 + (res - e2).abs() + err3;
+        if error <= abserr {
+            abserr = error;
+            result = res;
+        }
+    }
+    if !converged {
+        if *n == LIMEXP {
+            *n = 2 * (LIMEXP / 2) - 1;
+        }
+        let mut ib = if num.is_multiple_of(2) { 2 } else { 1 };
+        let ie = newelm + 1;
+        for _ in 1..=ie {
+            let ib2 = ib + 2;
+            epstab[ib] = epstab[ib2];
+            ib = ib2;
+        }
+        if num != *n {
+            let shift = num - *n;
+            for i in 1..=*n {
+                epstab[i] = epstab[i + shift];
+            }
+        }
+        if *nres < 4 {
+            res3la[*nres] = result;
+            abserr = oflow;
+        } else {
+            abserr = (result - res3la[3]).abs()
+                + (result - res3la[2]).abs()
+                + (result - res3la[1]).abs();
+            res3la[1] = res3la[2];
+            res3la[2] = res3la[3];
+            res3la[3] = result;
+        }
+    }
+    abserr = abserr.max(5. * epmach * result.abs());
+    (result, abserr)
+}
+
+/// Nodes and weights of an `n`-point Gaussian quadrature rule.
+#[derive(Debug, Clone)]
+pub struct GaussRule {
+    pub nodes: Vec<f64>,
+    pub weights: Vec<f64>,
+}
+impl GaussRule {
+    /// `Σ wᵢ f(xᵢ)`
+    pub fn integrate(&self, f: impl Fn(f64) -> f64) -> f64 {
+        self.nodes
+            .iter()
+            .zip(&self.weights)
+            .map(|(x, w)| w * f(*x))
+            .sum()
+    }
+    /// Map a rule on `[-1, 1]` onto `[lower, upper]`.
+    pub fn rescale(&self, lower: f64, upper: f64) -> GaussRule {
+        let half = (upper - lower) / 2.;
+        let mid = (upper + lower) / 2.;
+        GaussRule {
+            nodes: self.nodes.iter().map(|x| mid + half * x).collect(),
+            weights: self.weights.iter().map(|w| half * w).collect(),
+        }
+    }
+}
+/// Golub-Welsch: the nodes are the eigenvalues of the Jacobi matrix of the orthogonal polynomials
+/// and the weights come from the first components of its eigenvectors.
+///
+/// ref: <https://doi.org/10.1090/S0025-5718-69-99647-1>
+fn golub_welsch(diag: &[f64], off_diag: &[f64], mu0: f64) -> GaussRule {
+    let n = diag.len();
+    assert!(0 < n);
+    let jacobi = nalgebra::DMatrix::from_fn(n, n, |r, c| {
+        if r == c {
+            diag[r]
+        } else if r + 1 == c {
+            off_diag[r]
+        } else if c + 1 == r {
+            off_diag[c]
+        } else {
+            0.
+        }
+    });
+    let eigen = nalgebra::SymmetricEigen::new(jacobi);
+    let mut pairs: Vec<(f64, f64)> = (0..n)
+        .map(|i| {
+            (
+                eigen.eigenvalues[i],
+                mu0 * eigen.eigenvectors[(0, i)].powi(2),
+            )
+        })
+        .collect();
+    pairs.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    GaussRule {
+        nodes: pairs.iter().map(|p| p.0).collect(),
+        weights: pairs.iter().map(|p| p.1).collect(),
+    }
+}
+/// Weight `1` on `[-1, 1]`.
+pub fn gauss_legendre(n: usize) -> GaussRule {
+    let off_diag: Vec<f64> = (1..n)
+        .map(|i| {
+            let i = i as f64;
+            i / (4. * i * i - 1.).sqrt()
+        })
+        .collect();
+    golub_welsch(&vec![0.; n], &off_diag, 2.)
+}
+/// Weight `x^alpha e^-x` on `[0, ∞)`.
+pub fn gauss_laguerre(n: usize, alpha: f64) -> GaussRule {
+    assert!(-1. < alpha);
+    let diag: Vec<f64> = (0..n).map(|i| 2. * i as f64 + alpha + 1.).collect();
+    let off_diag: Vec<f64> = (1..n)
+        .map(|i| {
+            let i = i as f64;
+            (i * (i + alpha)).sqrt()
+        })
+        .collect();
+    let mu0 = crate::special::lgamma(alpha + 1.).exp();
+    golub_welsch(&diag, &off_diag, mu0)
+}
+/// Weight `e^-x²` on `(-∞, ∞)`.
+pub fn gauss_hermite(n: usize) -> GaussRule {
+    let off_diag: Vec<f64> = (1..n).map(|i| (i as f64 / 2.).sqrt()).collect();
+    golub_welsch(&vec![0.; n], &off_diag, std::f64::consts::PI.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn test_sampled() {
+        let x = vector::seq(vector::SeqParams {
+            start: 0.,
+            end: 1. + 1e-9,
+            step: 0.1,
+        });
+        let y = vector::mul(&x, &x);
+        assert!((trapz(&x, &y) - 0.335).abs() < 1e-12);
+        assert!((simpson(&x, &y) - 1. / 3.).abs() < 1e-12);
+        // odd number of intervals and uneven spacing are exact for quadratics too
+        let x = [0., 0.1, 0.3, 0.35, 0.7, 1.];
+        let y = vector::mul(x, x);
+        assert!((simpson(x, &y) - 1. / 3.).abs() < 1e-12);
+        #[rustfmt::skip]
+        let s = simpson_rule(f64::sin, RuleParams { lower: 0., upper: PI, n: 20 });
+        assert!((s - 2.).abs() < 1e-5);
+        #[rustfmt::skip]
+        let t = trapezoid_rule(f64::sin, RuleParams { lower: 0., upper: PI, n: 20 });
+        assert!((t - 2.).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_integrate() {
+        // R: integrate(dnorm, 0, Inf) -> 0.5 with absolute error < 4.7e-05
+        let dnorm = |x: f64| (-x * x / 2.).exp() / (2. * PI).sqrt();
+        let r = integrate(dnorm, IntegrateParams::new(0., f64::INFINITY));
+        assert_eq!(r.status, IntegrateStatus::Ok);
+        assert!((r.value - 0.5).abs() < 1e-9);
+        assert!(r.abs_error < 4.7e-5);
+        let r = integrate(
+            dnorm,
+            IntegrateParams::new(f64::NEG_INFINITY, f64::INFINITY),
+        );
+        assert!((r.value - 1.).abs() < 1e-9);
+        let r = integrate(dnorm, IntegrateParams::new(f64::NEG_INFINITY, 1.96));
+        assert!((r.value - 0.9750021048517795).abs() < 1e-9);
+        let r = integrate(dnorm, IntegrateParams::new(f64::INFINITY, 0.));
+        assert!((r.value + 0.5).abs() < 1e-9);
+        // R: integrate(function(x) 1/((x+1)*sqrt(x)), 0, Inf) -> 3.141593 with absolute error < 2.7e-05
+        let r = integrate(
+            |x| 1. / ((x + 1.) * x.sqrt()),
+            IntegrateParams::new(0., f64::INFINITY),
+        );
+        assert!((r.value - PI).abs() < 1e-5);
+        // R: integrate(function(x) log(x), 0, 1) needs extrapolation for the endpoint singularity
+        let r = integrate(f64::ln, IntegrateParams::new(0., 1.));
+        assert_eq!(r.status, IntegrateStatus::Ok);
+        assert!((r.value + 1.).abs() < 1e-12);
+        assert!(1 < r.subdivisions);
+        let r = integrate(|x| 1. / x, IntegrateParams::new(0., 1.));
+        assert_eq!(r.status, IntegrateStatus::MaxSubdivisions);
+        let r = integrate(f64::exp, IntegrateParams::new(1., 0.));
+        assert!((r.value + 1_f64.exp() - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gauss() {
+        let rule = gauss_legendre(5);
+        assert!((rule.integrate(|x| x.powi(8)) - 2. / 9.).abs() < 1e-13);
+        assert!((rule.rescale(0., PI).integrate(f64::sin) - 2.).abs() < 1e-5);
+        let rule = gauss_laguerre(6, 0.);
+        assert!((rule.integrate(|x| x.powi(5)) - 120.).abs() < 1e-9);
+        let rule = gauss_hermite(4);
+        assert!((rule.integrate(|x| x * x) - PI.sqrt() / 2.).abs() < 1e-13);
+        assert!((rule.nodes[3] - 1.650680123885785).abs() < 1e-12);
+    }
+}
@@ -1,4 +1,21 @@
+pub mod autodiff;
+pub mod boot;
+pub mod htest;
+pub mod integrate;
+pub mod lm;
+pub mod markov;
 pub mod matrix;
+pub mod montecarlo;
+pub mod numderiv;
+pub mod ode;
+pub mod optim;
 pub mod plot;
+pub mod polynomial;
 pub mod property;
+pub mod roots;
+pub mod simulation;
+pub mod special;
+pub mod stochastic;
+pub mod symbolic;
 pub mod vector;
+pub mod warning;
@@ -0,0 +1,171 @@
+use std::fmt;
+
+use statrs::distribution::{ContinuousCDF, FisherSnedecor};
+
+use super::{
+    Lm,
+    print::{CoefColumn, write_coefmat},
+};
+use crate::warning::warning;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnovaRow {
+    pub term: String,
+    pub df: usize,
+    pub sum_sq: f64,
+    pub mean_sq: f64,
+    /// `None` for the residuals
+    pub f_value: Option<f64>,
+    pub p_value: Option<f64>,
+}
+
+/// An analysis of variance table, like R's `anova` object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anova {
+    pub response: String,
+    /// a row for each term, then one for the residuals
+    pub rows: Vec<AnovaRow>,
+}
+
+/// `anova(fit)`: the sequential sums of squares of the terms, each adjusted for the terms before
+/// it, with F tests against the residual mean square.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/lm.R>
+pub fn anova(fit: &Lm) -> Anova {
+    let rank = fit.rank;
+    let rdf = fit.df_residual;
+    let ssr: f64 = fit.residuals.iter().map(|r| r * r).sum();
+    let mss: f64 = fit.fitted_values.iter().map(|f| f * f).sum();
+    if ssr < 1e-10 * mss {
+        warning("ANOVA F-tests on an essentially perfect fit are unreliable");
+    }
+    let msr = ssr / rdf as f64;
+    // the term of each estimable column, in the pivoted order of the effects
+    let assign: Vec<usize> = fit.qr.pivot[..rank]
+        .iter()
+        .map(|&j| fit.design.assign[j])
+        .collect();
+    let mut terms = assign.clone();
+    terms.sort_unstable();
+    terms.dedup();
+    let mut rows: Vec<AnovaRow> = terms
+        .into_iter()
+        .filter(|&term| !(term == 0 && fit.design.intercept))
+        .map(|term| {
+            let effects = assign.iter().zip(&fit.effects).filter(|(a, _)| **a == term);
+            let sum_sq: f64 = effects.clone().map(|(_, e)| e * e).sum();
+            let df = effects.count();
+            let mean_sq = sum_sq / df as f64;
+            let f_value = mean_sq / msr;
+            let p_value = match 0 < rdf {
+                true => FisherSnedecor::new(df as f64, rdf as f64)
+                    .unwrap()
+                    .sf(f_value),
+                false => f64::NAN,
+            };
+            AnovaRow {
+                term: match term {
+                    0 => "(Intercept)".to_string(),
+                    _ => fit.design.term_labels[term - 1].clone(),
+                },
+                df,
+                sum_sq,
+                mean_sq,
+                f_value: Some(f_value),
+                p_value: Some(p_value),
+            }
+        })
+        .collect();
+    rows.push(AnovaRow {
+        term: "Residuals".to_string(),
+        df: rdf,
+        sum_sq: ssr,
+        mean_sq: msr,
+        f_value: None,
+        p_value: None,
+    });
+    Anova {
+        response: match &fit.formula {
+            Some(formula) => formula.response.clone(),
+            None => "y".to_string(),
+        },
+        rows,
+    }
+}
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/anova.R>
+impl fmt::Display for Anova {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Analysis of Variance Table")?;
+        writeln!(f)?;
+        writeln!(f, "Response: {}", self.response)?;
+        let names: Vec<String> = self.rows.iter().map(|row| row.term.clone()).collect();
+        let column = |value: fn(&AnovaRow) -> f64| self.rows.iter().map(value).collect();
+        let columns = [
+            (
+                "Df",
+                CoefColumn::Other { zap: false },
+                column(|r| r.df as f64),
+            ),
+            (
+                "Sum Sq",
+                CoefColumn::Other { zap: true },
+                column(|r| r.sum_sq),
+            ),
+            (
+                "Mean Sq",
+                CoefColumn::Other { zap: true },
+                column(|r| r.mean_sq),
+            ),
+            (
+                "F value",
+                CoefColumn::Test,
+                column(|r| r.f_value.unwrap_or(f64::NAN)),
+            ),
+            (
+                "Pr(>F)",
+                CoefColumn::PValue,
+                column(|r| r.p_value.unwrap_or(f64::NAN)),
+            ),
+        ];
+        write_coefmat(f, &names, &columns, 5, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lm::{formula, lm, tests::mtcars};
+
+    #[test]
+    fn test_anova() {
+        let table = anova(&lm(&formula("mpg ~ wt"), &mtcars()));
+        assert_eq!(table.rows.len(), 2);
+        assert!((table.rows[0].sum_sq - 847.7252).abs() < 1e-4);
+        assert!((table.rows[1].sum_sq - 278.3219).abs() < 1e-4);
+        let expected = [
+            "Analysis of Variance Table",
+            "",
+            "Response: mpg",
+            "          Df Sum Sq Mean Sq F value    Pr(>F)    ",
+            "wt         1 847.73  847.73  91.375 1.294e-10 ***",
+            "Residuals 30 278.32    9.28                      ",
+            "---",
+            "Signif. codes:  0 ‘***’ 0.001 ‘**’ 0.01 ‘*’ 0.05 ‘.’ 0.1 ‘ ’ 1",
+            "",
+        ];
+        assert_eq!(table.to_string(), expected.join("\n"));
+
+        // the sequential sums of squares add up to the total about the mean
+        let table = anova(&lm(&formula("mpg ~ wt * cyl"), &mtcars()));
+        let total: f64 = table.rows.iter().map(|row| row.sum_sq).sum();
+        let mean = crate::vector::mean(crate::lm::tests::MPG);
+        let expected: f64 = crate::lm::tests::MPG
+            .iter()
+            .map(|y| (y - mean).powi(2))
+            .sum();
+        assert!((total - expected).abs() < 1e-9);
+        let df: Vec<usize> = table.rows.iter().map(|row| row.df).collect();
+        assert_eq!(df, [1, 2, 2, 26]);
+    }
+}
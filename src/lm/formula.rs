@@ -0,0 +1,437 @@
+use std::fmt;
+
+use crate::matrix::{FillOrdering, MatrixBuf, matrix};
+
+/// A model formula `response ~ terms`, each term the interaction of one or more variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Formula {
+    pub response: String,
+    pub intercept: bool,
+    /// the variables of each term, lower order terms first
+    pub terms: Vec<Vec<String>>,
+}
+impl Formula {
+    /// `attr(terms(formula), "term.labels")`
+    pub fn term_labels(&self) -> Vec<String> {
+        self.terms.iter().map(|term| term.join(":")).collect()
+    }
+}
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ~ ", self.response)?;
+        match (self.terms.is_empty(), self.intercept) {
+            (true, true) => write!(f, "1"),
+            (true, false) => write!(f, "0"),
+            (false, true) => write!(f, "{}", self.term_labels().join(" + ")),
+            (false, false) => write!(f, "{} - 1", self.term_labels().join(" + ")),
+        }
+    }
+}
+
+/// Parse a model formula in R syntax: `y ~ a + b`, with `a:b` for an interaction, `a * b` for
+/// `a + b + a:b`, parentheses, `- term` to drop a term, and `0`, `1` or `- 1` for the intercept.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/src/model.c>
+pub fn formula(text: &str) -> Formula {
+    let (response, rhs) = text
+        .split_once('~')
+        .unwrap_or_else(|| panic!("invalid formula: no '~'"));
+    let response = response.trim();
+    assert!(
+        response.starts_with(|c: char| c.is_alphabetic() || c == '.')
+            && response.chars().all(is_name_char),
+        "invalid formula: response '{response}'"
+    );
+    let mut parser = Parser {
+        chars: rhs.chars().collect(),
+        pos: 0,
+        variables: vec![],
+        intercept: true,
+    };
+    let mut terms = parser.sum(true);
+    parser.skip_space();
+    if let Some(c) = parser.peek() {
+        panic!("unexpected '{c}'");
+    }
+    terms.sort_by_key(|term| term.len());
+    let terms = terms
+        .into_iter()
+        .map(|term| term.iter().map(|&i| parser.variables[i].clone()).collect())
+        .collect();
+    Formula {
+        response: response.to_string(),
+        intercept: parser.intercept,
+        terms,
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+/// A term as the sorted indices of its variables in order of first appearance.
+type Term = Vec<usize>;
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    variables: Vec<String>,
+    intercept: bool,
+}
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+    fn skip_space(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+    /// Consume `c` if it is the next non-space character.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_space();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+    fn expect(&mut self, c: char) {
+        if !self.eat(c) {
+            match self.peek() {
+                Some(found) => panic!("unexpected '{found}'"),
+                None => panic!("unexpected end of input"),
+            }
+        }
+    }
+
+    /// Terms added with `+` and dropped with `-`; `top` allows the intercept markers.
+    fn sum(&mut self, top: bool) -> Vec<Term> {
+        let mut terms: Vec<Term> = vec![];
+        let mut first = true;
+        loop {
+            let add = if self.eat('+') {
+                true
+            } else if self.eat('-') {
+                false
+            } else if first {
+                true
+            } else {
+                return terms;
+            };
+            first = false;
+            self.skip_space();
+            if let Some(c @ ('0' | '1')) = self.peek() {
+                assert!(top, "invalid model formula");
+                self.pos += 1;
+                // `+ 1` and `- 0` keep the intercept, `+ 0` and `- 1` drop it
+                self.intercept = add == (c == '1');
+                continue;
+            }
+            let product = self.product();
+            if add {
+                union(&mut terms, product);
+            } else {
+                terms.retain(|term| !product.contains(term));
+            }
+        }
+    }
+    /// `a * b`: `a + b + a:b`
+    fn product(&mut self) -> Vec<Term> {
+        let mut lhs = self.interaction();
+        while self.eat('*') {
+            let rhs = self.interaction();
+            let cross = interact(&lhs, &rhs);
+            union(&mut lhs, rhs);
+            union(&mut lhs, cross);
+        }
+        lhs
+    }
+    fn interaction(&mut self) -> Vec<Term> {
+        let mut lhs = self.atom();
+        while self.eat(':') {
+            let rhs = self.atom();
+            lhs = interact(&lhs, &rhs);
+        }
+        lhs
+    }
+    fn atom(&mut self) -> Vec<Term> {
+        if self.eat('(') {
+            let terms = self.sum(false);
+            self.expect(')');
+            return terms;
+        }
+        let start = self.pos;
+        if !self.peek().is_some_and(|c| c.is_alphabetic() || c == '.') {
+            match self.peek() {
+                Some(found) => panic!("unexpected '{found}'"),
+                None => panic!("unexpected end of input"),
+            }
+        }
+        while self.peek().is_some_and(is_name_char) {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+        let index = match self.variables.iter().position(|v| *v == name) {
+            Some(index) => index,
+            None => {
+                self.variables.push(name);
+                self.variables.len() - 1
+            }
+        };
+        vec![vec![index]]
+    }
+}
+fn union(terms: &mut Vec<Term>, more: Vec<Term>) {
+    for term in more {
+        if !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+}
+fn interact(lhs: &[Term], rhs: &[Term]) -> Vec<Term> {
+    let mut out = vec![];
+    for b in rhs {
+        for a in lhs {
+            let mut term: Term = a.iter().chain(b).copied().collect();
+            term.sort_unstable();
+            term.dedup();
+            union(&mut out, vec![term]);
+        }
+    }
+    out
+}
+
+/// A variable of the data for `model_matrix` and `lm`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Numeric(Vec<f64>),
+    /// categorical, with its levels in sorted order as `factor` gives them
+    Factor(Vec<String>),
+}
+impl Column {
+    pub fn len(&self) -> usize {
+        match self {
+            Column::Numeric(x) => x.len(),
+            Column::Factor(x) => x.len(),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The design matrix of a model, with what `lm` needs to label its columns and group them into
+/// terms.
+#[derive(Debug, Clone)]
+pub struct ModelMatrix {
+    pub x: MatrixBuf<f64>,
+    /// column names, like `"(Intercept)"`, `"x"`, `"fB"` or `"x:fB"`
+    pub names: Vec<String>,
+    /// the term of each column: 0 for the intercept and `i` for `term_labels[i - 1]`
+    pub assign: Vec<usize>,
+    pub term_labels: Vec<String>,
+    pub intercept: bool,
+    /// the levels of each factor, to code new data the same way
+    pub levels: Vec<(String, Vec<String>)>,
+}
+
+/// `model.matrix(formula, data)`: the intercept, numeric variables as they are, and factors
+/// coded by treatment contrasts against their first level, or by indicators of every level in a
+/// term whose margin is not in the model.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/src/model.c>
+pub fn model_matrix(formula: &Formula, data: &[(&str, Column)]) -> ModelMatrix {
+    build(formula, data, None)
+}
+/// The design matrix of `formula` for `data`, coding factors with `levels` when given.
+pub(super) fn build(
+    formula: &Formula,
+    data: &[(&str, Column)],
+    levels: Option<&[(String, Vec<String>)]>,
+) -> ModelMatrix {
+    let n = data
+        .first()
+        .unwrap_or_else(|| panic!("'data' has no variables"))
+        .1
+        .len();
+    let variable = |name: &str| {
+        let (_, column) = data
+            .iter()
+            .find(|(v, _)| *v == name)
+            .unwrap_or_else(|| panic!("object '{name}' not found"));
+        assert_eq!(
+            column.len(),
+            n,
+            "variable lengths differ (found for '{name}')"
+        );
+        column
+    };
+    let mut factor_levels: Vec<(String, Vec<String>)> = vec![];
+    for name in formula.terms.iter().flatten() {
+        if let Column::Factor(values) = variable(name) {
+            if factor_levels.iter().any(|(v, _)| v == name) {
+                continue;
+            }
+            let known = levels.and_then(|levels| levels.iter().find(|(v, _)| v == name));
+            let levels = match known {
+                Some((_, levels)) => {
+                    if let Some(new) = values.iter().find(|v| !levels.contains(v)) {
+                        panic!("factor {name} has new level {new}");
+                    }
+                    levels.clone()
+                }
+                None => {
+                    let mut levels = values.clone();
+                    levels.sort();
+                    levels.dedup();
+                    levels
+                }
+            };
+            factor_levels.push((name.clone(), levels));
+        }
+    }
+    // without an intercept, the first factor of the first term with one is coded by indicators
+    let full_factor = match formula.intercept {
+        true => None,
+        false => formula.terms.iter().enumerate().find_map(|(t, term)| {
+            let name = term
+                .iter()
+                .find(|v| matches!(variable(v), Column::Factor(_)))?;
+            Some((t, name.clone()))
+        }),
+    };
+    let mut names = vec![];
+    let mut columns: Vec<Vec<f64>> = vec![];
+    let mut assign = vec![];
+    if formula.intercept {
+        names.push("(Intercept)".to_string());
+        columns.push(vec![1.; n]);
+        assign.push(0);
+    }
+    for (t, term) in formula.terms.iter().enumerate() {
+        let mut term_columns = vec![(String::new(), vec![1.; n])];
+        for name in term {
+            let variable_columns = match variable(name) {
+                Column::Numeric(x) => vec![(name.clone(), x.clone())],
+                Column::Factor(values) => {
+                    let (_, levels) = factor_levels.iter().find(|(v, _)| v == name).unwrap();
+                    let full = full_factor.as_ref() == Some(&(t, name.clone()))
+                        || !has_margin(&formula.terms, t, name);
+                    let skip = match full {
+                        true => 0,
+                        false => {
+                            assert!(
+                                2 <= levels.len(),
+                                "contrasts can be applied only to factors with 2 or more levels"
+                            );
+                            1
+                        }
+                    };
+                    levels[skip..]
+                        .iter()
+                        .map(|level| {
+                            let indicator = values.iter().map(|v| f64::from(v == level)).collect();
+                            (format!("{name}{level}"), indicator)
+                        })
+                        .collect()
+                }
+            };
+            // the earlier variables vary fastest
+            let mut next = vec![];
+            for (variable_name, x) in &variable_columns {
+                for (term_name, column) in &term_columns {
+                    let name = match term_name.is_empty() {
+                        true => variable_name.clone(),
+                        false => format!("{term_name}:{variable_name}"),
+                    };
+                    next.push((name, column.iter().zip(x).map(|(a, b)| a * b).collect()));
+                }
+            }
+            term_columns = next;
+        }
+        for (name, column) in term_columns {
+            names.push(name);
+            columns.push(column);
+            assign.push(t + 1);
+        }
+    }
+    assert!(!columns.is_empty(), "the model has no coefficients");
+    let data: Vec<f64> = (0..n)
+        .flat_map(|i| columns.iter().map(move |column| column[i]))
+        .collect();
+    ModelMatrix {
+        x: matrix(data, [columns.len(), n], FillOrdering::RowByRow),
+        names,
+        assign,
+        term_labels: formula.term_labels(),
+        intercept: formula.intercept,
+        levels: factor_levels,
+    }
+}
+/// Whether the term `t` without `name` is the empty term or within an earlier term, in which
+/// case the factor `name` is coded by contrasts.
+fn has_margin(terms: &[Vec<String>], t: usize, name: &str) -> bool {
+    let margin: Vec<&String> = terms[t].iter().filter(|v| *v != name).collect();
+    margin.is_empty()
+        || terms[..t]
+            .iter()
+            .any(|earlier| margin.iter().all(|v| earlier.contains(v)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::column;
+
+    #[test]
+    fn test_formula() {
+        let f = formula("y ~ a * b");
+        assert_eq!(f.response, "y");
+        assert!(f.intercept);
+        assert_eq!(f.term_labels(), ["a", "b", "a:b"]);
+        assert_eq!(
+            formula("y~a*b*c - a:b:c").to_string(),
+            "y ~ a + b + c + a:b + a:c + b:c"
+        );
+        assert_eq!(formula("y ~ b:a + a").to_string(), "y ~ a + b:a");
+        assert_eq!(
+            formula("y ~ (a + b):c - 1").to_string(),
+            "y ~ a:c + b:c - 1"
+        );
+        assert_eq!(formula("y ~ 0 + x").to_string(), "y ~ x - 1");
+        assert_eq!(formula("y ~ 1").to_string(), "y ~ 1");
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected '^'")]
+    fn test_formula_error() {
+        formula("y ~ x^2");
+    }
+
+    #[test]
+    fn test_model_matrix() {
+        let f = |x: &[&str]| Column::Factor(x.iter().map(|x| x.to_string()).collect());
+        let data = [
+            ("x", Column::Numeric(vec![1., 2., 3., 4.])),
+            ("g", f(&["b", "a", "b", "c"])),
+        ];
+        let m = model_matrix(&formula("y ~ x * g"), &data);
+        assert_eq!(m.names, ["(Intercept)", "x", "gb", "gc", "x:gb", "x:gc"]);
+        assert_eq!(m.assign, [0, 1, 2, 2, 3, 3]);
+        assert_eq!(
+            m.levels,
+            [("g".to_string(), vec!["a".into(), "b".into(), "c".into()])]
+        );
+        assert_eq!(column(&m.x, 4), [1., 0., 3., 0.]);
+
+        // indicators of every level without the margin `x`, or without the intercept
+        let m = model_matrix(&formula("y ~ x + x:g"), &data);
+        assert_eq!(m.names, ["(Intercept)", "x", "x:gb", "x:gc"]);
+        let m = model_matrix(&formula("y ~ x:g"), &data);
+        assert_eq!(m.names, ["(Intercept)", "x:ga", "x:gb", "x:gc"]);
+        let m = model_matrix(&formula("y ~ g - 1"), &data);
+        assert_eq!(m.names, ["ga", "gb", "gc"]);
+        assert!(!m.intercept);
+    }
+}
@@ -0,0 +1,229 @@
+mod anova;
+mod formula;
+mod predict;
+mod print;
+mod qr;
+mod summary;
+use std::fmt;
+
+pub use anova::*;
+pub use formula::*;
+pub use predict::*;
+pub use summary::*;
+
+use self::{
+    print::{format_na, write_named},
+    qr::{Qr, qr},
+};
+use crate::matrix::{MatrixBuf, column, matrix_buf_to_malgebra};
+
+/// A linear model fitted by least squares, like R's `lm` object.
+#[derive(Debug, Clone)]
+pub struct Lm {
+    /// one for each column of the design, `NaN` (R's `NA`) where a column depends linearly on the
+    /// ones before it
+    pub coefficients: Vec<f64>,
+    pub residuals: Vec<f64>,
+    pub fitted_values: Vec<f64>,
+    /// `Q'y`, the orthogonal single degree of freedom effects, in the pivoted column order
+    pub effects: Vec<f64>,
+    pub rank: usize,
+    pub df_residual: usize,
+    pub design: ModelMatrix,
+    /// `None` for a fit by `lm_fit`
+    pub formula: Option<Formula>,
+    qr: Qr,
+}
+
+/// `lm(formula, data)`: fit `formula` to the variables of `data` by least squares, through the
+/// QR decomposition of its design matrix.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/lm.R>
+pub fn lm(formula: &Formula, data: &[(&str, Column)]) -> Lm {
+    let design = model_matrix(formula, data);
+    let y = match data.iter().find(|(name, _)| *name == formula.response) {
+        Some((_, Column::Numeric(y))) => y,
+        Some(_) => panic!("the response '{}' must be numeric", formula.response),
+        None => panic!("object '{}' not found", formula.response),
+    };
+    fit(y, design, Some(formula.clone()))
+}
+/// `lm.fit(x, y)`: fit `y` to the columns of the design matrix `x`. A column of ones is taken as
+/// the intercept, and every other column, named `x1`, `x2`, ... by position, as its own term.
+pub fn lm_fit(y: impl AsRef<[f64]>, x: impl AsRef<MatrixBuf<f64>>) -> Lm {
+    let x = x.as_ref();
+    let intercept = (0..x.dim()[0]).find(|&j| column(x, j).iter().all(|&v| v == 1.));
+    let names: Vec<String> = (0..x.dim()[0])
+        .map(|j| match Some(j) == intercept {
+            true => "(Intercept)".to_string(),
+            false => format!("x{}", j + 1),
+        })
+        .collect();
+    let mut term = 0;
+    let assign = (0..x.dim()[0])
+        .map(|j| match Some(j) == intercept {
+            true => 0,
+            false => {
+                term += 1;
+                term
+            }
+        })
+        .collect();
+    let design = ModelMatrix {
+        x: x.clone(),
+        term_labels: names
+            .iter()
+            .filter(|name| *name != "(Intercept)")
+            .cloned()
+            .collect(),
+        names,
+        assign,
+        intercept: intercept.is_some(),
+        levels: vec![],
+    };
+    fit(y.as_ref(), design, None)
+}
+
+fn fit(y: &[f64], design: ModelMatrix, formula: Option<Formula>) -> Lm {
+    let [p, n] = design.x.dim();
+    assert_eq!(y.len(), n, "incompatible dimensions");
+    assert!(y.iter().all(|y| y.is_finite()), "NA/NaN/Inf in 'y'");
+    let qr = qr(matrix_buf_to_malgebra(&design.x), 1e-7);
+    let effects = qr.qty(y);
+    let mut coefficients = vec![f64::NAN; p];
+    for (&j, b) in qr.pivot.iter().zip(qr.coef(&effects)) {
+        coefficients[j] = b;
+    }
+    let mut residual_effects = effects.clone();
+    residual_effects[..qr.rank].fill(0.);
+    let residuals = qr.qy(&residual_effects);
+    let fitted_values = y.iter().zip(&residuals).map(|(y, r)| y - r).collect();
+    Lm {
+        coefficients,
+        residuals,
+        fitted_values,
+        effects,
+        rank: qr.rank,
+        df_residual: n - qr.rank,
+        design,
+        formula,
+        qr,
+    }
+}
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/lm.R>
+impl fmt::Display for Lm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f)?;
+        if let Some(formula) = &self.formula {
+            writeln!(f, "Call:")?;
+            writeln!(f, "lm(formula = {formula})")?;
+            writeln!(f)?;
+        }
+        writeln!(f, "Coefficients:")?;
+        let coefficients = format_na(&self.coefficients, 4);
+        write_named(f, &self.design.names, &coefficients, 2)?;
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::{FillOrdering, matrix};
+
+    pub(super) const MPG: [f64; 32] = [
+        21.0, 21.0, 22.8, 21.4, 18.7, 18.1, 14.3, 24.4, 22.8, 19.2, 17.8, 16.4, 17.3, 15.2, 10.4,
+        10.4, 14.7, 32.4, 30.4, 33.9, 21.5, 15.5, 15.2, 13.3, 19.2, 27.3, 26.0, 30.4, 15.8, 19.7,
+        15.0, 21.4,
+    ];
+    pub(super) const WT: [f64; 32] = [
+        2.620, 2.875, 2.320, 3.215, 3.440, 3.460, 3.570, 3.190, 3.150, 3.440, 3.440, 4.070, 3.730,
+        3.780, 5.250, 5.424, 5.345, 2.200, 1.615, 1.835, 2.465, 3.520, 3.435, 3.840, 3.845, 1.935,
+        2.140, 1.513, 3.170, 2.770, 3.570, 2.780,
+    ];
+    pub(super) const CYL: [u8; 32] = [
+        6, 6, 4, 6, 8, 6, 8, 4, 4, 6, 6, 8, 8, 8, 8, 8, 8, 4, 4, 4, 4, 8, 8, 8, 8, 4, 4, 4, 8, 6,
+        8, 4,
+    ];
+
+    /// `mtcars[, c("mpg", "wt", "cyl")]` with `cyl` as a factor.
+    pub(super) fn mtcars() -> Vec<(&'static str, Column)> {
+        vec![
+            ("mpg", Column::Numeric(MPG.to_vec())),
+            ("wt", Column::Numeric(WT.to_vec())),
+            (
+                "cyl",
+                Column::Factor(CYL.iter().map(|c| c.to_string()).collect()),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_lm() {
+        let fit = lm(&formula("mpg ~ wt"), &mtcars());
+        assert!((fit.coefficients[0] - 37.285126).abs() < 1e-6);
+        assert!((fit.coefficients[1] - -5.344472).abs() < 1e-6);
+        assert_eq!(fit.df_residual, 30);
+        let fitted: f64 = fit.fitted_values.iter().sum();
+        assert!((fitted - MPG.iter().sum::<f64>()).abs() < 1e-10);
+        let expected = [
+            "",
+            "Call:",
+            "lm(formula = mpg ~ wt)",
+            "",
+            "Coefficients:",
+            "(Intercept)           wt  ",
+            "     37.285       -5.344  ",
+            "",
+            "",
+        ];
+        assert_eq!(fit.to_string(), expected.join("\n"));
+
+        // the group means of a factor
+        let fit = lm(&formula("mpg ~ cyl"), &mtcars());
+        assert_eq!(fit.design.names, ["(Intercept)", "cyl6", "cyl8"]);
+        let means = [26.663636, 19.742857, 15.1];
+        assert!((fit.coefficients[0] - means[0]).abs() < 1e-6);
+        assert!((fit.coefficients[1] - (means[1] - means[0])).abs() < 1e-6);
+        assert!((fit.coefficients[2] - (means[2] - means[0])).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_interaction() {
+        // separate lines for each number of cylinders
+        let fit = lm(&formula("mpg ~ wt * cyl"), &mtcars());
+        assert_eq!(fit.design.term_labels, ["wt", "cyl", "wt:cyl"]);
+        for (k, cyl) in [4, 6, 8].into_iter().enumerate() {
+            let rows: Vec<usize> = (0..32).filter(|&i| CYL[i] == cyl).collect();
+            let x: Vec<f64> = rows.iter().flat_map(|&i| [1., WT[i]]).collect();
+            let x = matrix(x, [2, rows.len()], FillOrdering::RowByRow);
+            let group = lm_fit(rows.iter().map(|&i| MPG[i]).collect::<Vec<f64>>(), x);
+            let [mut a, mut b] = [fit.coefficients[0], fit.coefficients[1]];
+            if 0 < k {
+                a += fit.coefficients[1 + k];
+                b += fit.coefficients[3 + k];
+            }
+            assert!((a - group.coefficients[0]).abs() < 1e-8);
+            assert!((b - group.coefficients[1]).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_rank_deficient() {
+        let x = matrix(
+            [1., 1., 2., 1., 2., 4., 1., 3., 6., 1., 5., 10.],
+            [3, 4],
+            FillOrdering::RowByRow,
+        );
+        let fit = lm_fit([1., 3., 2., 5.], &x);
+        assert_eq!(fit.design.names, ["(Intercept)", "x2", "x3"]);
+        assert_eq!(fit.rank, 2);
+        assert!((fit.coefficients[1] - 31. / 35.).abs() < 1e-12);
+        assert!(fit.coefficients[2].is_nan());
+        assert!(
+            fit.to_string()
+                .contains("     0.3143       0.8857           NA  ")
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use statrs::distribution::{ContinuousCDF, StudentsT};
+
+use super::{Column, Lm, formula::build};
+use crate::{
+    htest::ConfInt,
+    matrix::{MatrixBuf, matrix_buf_to_malgebra},
+    warning::warning,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interval {
+    None,
+    /// for the mean response
+    Confidence,
+    /// for a future observation
+    Prediction,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PredictParams {
+    pub interval: Interval,
+    pub level: f64,
+}
+impl Default for PredictParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl PredictParams {
+    pub fn new() -> Self {
+        Self {
+            interval: Interval::None,
+            level: 0.95,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<PredictParams> for PredictParams { fn as_ref(&self) -> &PredictParams { self } }
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prediction {
+    pub fit: Vec<f64>,
+    pub se_fit: Vec<f64>,
+    /// `None` for `Interval::None`
+    pub interval: Option<Vec<ConfInt>>,
+    pub df: usize,
+    pub residual_scale: f64,
+}
+
+/// `predict(fit, newdata, se.fit = TRUE, interval, level)` at each row of the design matrix `x`,
+/// which has the fitted model's columns; `new_model_matrix` builds it from new data.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/lm.R>
+pub fn predict(
+    fit: &Lm,
+    x: impl AsRef<MatrixBuf<f64>>,
+    params: impl AsRef<PredictParams>,
+) -> Prediction {
+    let params = params.as_ref();
+    let x = matrix_buf_to_malgebra(x);
+    assert_eq!(
+        x.ncols(),
+        fit.coefficients.len(),
+        "'x' must have a column for each coefficient"
+    );
+    if fit.rank < fit.coefficients.len() {
+        warning("prediction from a rank-deficient fit may be misleading");
+    }
+    let pivot = &fit.qr.pivot[..fit.rank];
+    let x = x.select_columns(pivot);
+    let beta =
+        nalgebra::DVector::from_iterator(fit.rank, pivot.iter().map(|&j| fit.coefficients[j]));
+    let predictor = &x * beta;
+    let xr_inverse = &x * fit.qr.r_inverse();
+    let df = fit.df_residual;
+    let res_var = fit.residuals.iter().map(|r| r * r).sum::<f64>() / df as f64;
+    // the variance of each fitted mean
+    let ip: Vec<f64> = xr_inverse
+        .row_iter()
+        .map(|row| row.norm_squared() * res_var)
+        .collect();
+    let interval = match params.interval {
+        Interval::None => None,
+        interval => {
+            let tfrac = match 0 < df {
+                true => StudentsT::new(0., 1., df as f64)
+                    .unwrap()
+                    .inverse_cdf((1. + params.level) / 2.),
+                false => f64::NAN,
+            };
+            let pred_var = match interval {
+                Interval::Prediction => res_var,
+                _ => 0.,
+            };
+            let bounds = predictor
+                .iter()
+                .zip(&ip)
+                .map(|(fit, ip)| {
+                    let hwid = tfrac * (ip + pred_var).sqrt();
+                    ConfInt {
+                        lower: fit - hwid,
+                        upper: fit + hwid,
+                        level: params.level,
+                    }
+                })
+                .collect();
+            Some(bounds)
+        }
+    };
+    Prediction {
+        fit: predictor.iter().copied().collect(),
+        se_fit: ip.iter().map(|ip| ip.sqrt()).collect(),
+        interval,
+        df,
+        residual_scale: res_var.sqrt(),
+    }
+}
+
+/// The design matrix of `data` for a model fitted by `lm`, with its factors coded by the levels
+/// of the fit, for `predict`.
+pub fn new_model_matrix(fit: &Lm, data: &[(&str, Column)]) -> MatrixBuf<f64> {
+    let formula = fit
+        .formula
+        .as_ref()
+        .unwrap_or_else(|| panic!("the model has no formula"));
+    build(formula, data, Some(&fit.design.levels)).x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lm::{
+        formula, lm, lm_fit,
+        tests::{WT, mtcars},
+    };
+    use crate::matrix::{FillOrdering, matrix};
+
+    #[test]
+    fn test_predict() {
+        let fit = lm(&formula("mpg ~ wt"), &mtcars());
+        let wt = [1.5, 3., 3.21725];
+        let x = new_model_matrix(&fit, &[("wt", Column::Numeric(wt.to_vec()))]);
+        let mut params = PredictParams::new();
+        params.interval = Interval::Confidence;
+        let confidence = predict(&fit, &x, params);
+        params.interval = Interval::Prediction;
+        let prediction = predict(&fit, &x, params);
+        assert!((confidence.fit[1] - (37.285126 - 3. * 5.344472)).abs() < 1e-5);
+        // se.fit^2 = sigma^2 (1 / n + (x - mean)^2 / Sxx)
+        let sigma = confidence.residual_scale;
+        let mean_wt = 3.21725;
+        let sxx: f64 = WT.iter().map(|w| (w - mean_wt).powi(2)).sum();
+        for (i, w) in wt.iter().enumerate() {
+            let se = sigma * (1. / 32. + (w - mean_wt).powi(2) / sxx).sqrt();
+            assert!((confidence.se_fit[i] - se).abs() < 1e-10);
+            let [c, p] = [&confidence, &prediction].map(|p| p.interval.as_ref().unwrap()[i]);
+            assert!(p.lower < c.lower && c.upper < p.upper);
+            let ratio = (p.upper - p.lower) / (c.upper - c.lower);
+            assert!((ratio - (1. + (sigma / se).powi(2)).sqrt()).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_saturated() {
+        let x = matrix([1., 1., 1., 2.], [2, 2], FillOrdering::RowByRow);
+        let fit = lm_fit([1., 3.], &x);
+        let mut params = PredictParams::new();
+        params.interval = Interval::Prediction;
+        let prediction = predict(&fit, &x, params);
+        assert!((prediction.fit[1] - 3.).abs() < 1e-12);
+        let interval = prediction.interval.unwrap();
+        assert!(
+            interval
+                .iter()
+                .all(|ci| ci.lower.is_nan() && ci.upper.is_nan())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "factor cyl has new level 5")]
+    fn test_new_level() {
+        let fit = lm(&formula("mpg ~ cyl"), &mtcars());
+        new_model_matrix(&fit, &[("cyl", Column::Factor(vec!["5".to_string()]))]);
+    }
+}
@@ -0,0 +1,237 @@
+//! Printing after R's `print.default` of named vectors and `printCoefmat`.
+
+use std::fmt;
+
+use crate::htest::format::{format_number, format_numbers, format_pvals};
+
+/// `print` of a named vector: each name over its value, right-justified to a common width and
+/// followed by `gap` spaces, as many to a line as fit in 80 columns.
+pub(super) fn write_named(
+    f: &mut fmt::Formatter<'_>,
+    names: &[String],
+    values: &[String],
+    gap: usize,
+) -> fmt::Result {
+    let width = names
+        .iter()
+        .chain(values)
+        .map(|s| s.len())
+        .max()
+        .unwrap_or(0);
+    let per_line = (80 / (width + gap)).max(1);
+    for (names, values) in names.chunks(per_line).zip(values.chunks(per_line)) {
+        for name in names {
+            write!(f, "{name:>width$}{:gap$}", "")?;
+        }
+        writeln!(f)?;
+        for value in values {
+            write!(f, "{value:>width$}{:gap$}", "")?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+/// `format(x, digits = digits)` with `NaN` shown as R's `NA`.
+pub(super) fn format_na(x: &[f64], digits: usize) -> Vec<String> {
+    let finite: Vec<f64> = x.iter().copied().filter(|x| !x.is_nan()).collect();
+    let mut formatted = format_numbers(finite, digits).into_iter();
+    let out: Vec<String> = x
+        .iter()
+        .map(|x| match x.is_nan() {
+            true => "NA".to_string(),
+            false => formatted.next().unwrap(),
+        })
+        .collect();
+    let width = out.iter().map(|s| s.len()).max().unwrap_or(0);
+    out.into_iter().map(|s| format!("{s:>width$}")).collect()
+}
+
+/// `zapsmall(x, digits)`: round to `digits` significant digits of the largest magnitude.
+pub(super) fn zapsmall(x: &[f64], digits: usize) -> Vec<f64> {
+    let max = x
+        .iter()
+        .filter(|x| x.is_finite())
+        .fold(0., |max: f64, x| max.max(x.abs()));
+    let decimals = match 0. < max {
+        true => (digits as f64 - max.log10().ceil()).max(0.),
+        false => digits as f64,
+    };
+    x.iter().map(|&x| round(x, decimals as i32)).collect()
+}
+fn round(x: f64, decimals: i32) -> f64 {
+    let scale = 10f64.powi(decimals);
+    (x * scale).round() / scale
+}
+
+/// How `write_coefmat` formats a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CoefColumn {
+    /// estimates and standard errors, rounded together to the decimals of the smallest
+    Coef,
+    /// test statistics
+    Test,
+    /// other values, first zapped toward zero when `zap`
+    Other { zap: bool },
+    /// p-values, with significance stars
+    PValue,
+}
+
+/// `printCoefmat(x, digits, na.print)`: a table of named rows, `NaN` cells printed as
+/// `na_print`.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/lm.R>
+pub(super) fn write_coefmat(
+    f: &mut fmt::Formatter<'_>,
+    row_names: &[String],
+    columns: &[(&str, CoefColumn, Vec<f64>)],
+    digits: usize,
+    na_print: &str,
+) -> fmt::Result {
+    let dig_tst = (digits - 1).clamp(1, 5);
+    let columns: Vec<(&str, CoefColumn, Vec<f64>)> = columns
+        .iter()
+        .map(|(header, kind, x)| match kind {
+            CoefColumn::Other { zap: true } => (*header, *kind, zapsmall(x, digits)),
+            _ => (*header, *kind, x.clone()),
+        })
+        .collect();
+    let mut cells: Vec<Vec<String>> =
+        vec![vec![na_print.to_string(); row_names.len()]; columns.len()];
+    // format the finite values of the columns of `kind` together
+    let mut format_together = |kind: CoefColumn, round_to: Option<i32>, digits: usize| {
+        let values: Vec<f64> = columns
+            .iter()
+            .filter(|(_, k, _)| *k == kind)
+            .flat_map(|(_, _, x)| x.iter().copied().filter(|x| !x.is_nan()))
+            .map(|x| round_to.map_or(x, |d| round(x, d)))
+            .collect();
+        let mut formatted = format_numbers(values, digits).into_iter();
+        for (j, (_, k, x)) in columns.iter().enumerate() {
+            if *k != kind {
+                continue;
+            }
+            for (i, x) in x.iter().enumerate() {
+                if !x.is_nan() {
+                    cells[j][i] = formatted.next().unwrap();
+                }
+            }
+        }
+    };
+    let coef_magnitudes = columns
+        .iter()
+        .filter(|(_, k, _)| *k == CoefColumn::Coef)
+        .flat_map(|(_, _, x)| x.iter().map(|x| x.abs()))
+        .filter(|x| x.is_finite() && *x != 0.);
+    let smallest = coef_magnitudes.fold(f64::INFINITY, f64::min);
+    let digmin = match smallest.is_finite() {
+        true => 1 + smallest.log10().floor() as i32,
+        false => 1,
+    };
+    format_together(
+        CoefColumn::Coef,
+        Some((digits as i32 - digmin).max(1)),
+        digits,
+    );
+    format_together(CoefColumn::Test, Some(dig_tst as i32), digits);
+    let mut stars = None;
+    for (j, (_, kind, x)) in columns.iter().enumerate() {
+        match kind {
+            CoefColumn::Other { .. } => {
+                cells[j] = format_na(x, digits);
+                for (cell, x) in cells[j].iter_mut().zip(x) {
+                    if x.is_nan() {
+                        *cell = na_print.to_string();
+                    }
+                }
+            }
+            CoefColumn::PValue => {
+                let finite: Vec<f64> = x.iter().copied().filter(|x| !x.is_nan()).collect();
+                let mut formatted = format_pvals(&finite, dig_tst).into_iter();
+                for (cell, x) in cells[j].iter_mut().zip(x) {
+                    if !x.is_nan() {
+                        *cell = formatted.next().unwrap();
+                    }
+                }
+                if finite.iter().any(|&p| p < 0.1) {
+                    stars = Some(x.iter().map(|&p| significance(p)).collect::<Vec<&str>>());
+                }
+            }
+            CoefColumn::Coef | CoefColumn::Test => {}
+        }
+        // a value that rounds to zero, or a zero that does not look like one
+        if !matches!(kind, CoefColumn::Test | CoefColumn::PValue) {
+            for (cell, x) in cells[j].iter_mut().zip(x) {
+                let shown: Option<f64> = cell.trim().parse().ok();
+                if shown.is_some_and(|shown| (*x == 0.) != (shown == 0.)) {
+                    *cell = format_number(*x, (digits - 1).max(1));
+                }
+            }
+        }
+    }
+    let name_width = row_names.iter().map(|s| s.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = columns
+        .iter()
+        .zip(&cells)
+        .map(|((header, _, _), cells)| cells.iter().map(|s| s.len()).fold(header.len(), usize::max))
+        .collect();
+    let star_width = stars
+        .as_ref()
+        .map(|stars| stars.iter().map(|s| s.len()).max().unwrap_or(0));
+    write!(f, "{:name_width$}", "")?;
+    for ((header, _, _), width) in columns.iter().zip(&widths) {
+        write!(f, " {header:>width$}")?;
+    }
+    if let Some(width) = star_width {
+        write!(f, " {:width$}", "")?;
+    }
+    writeln!(f)?;
+    for (i, name) in row_names.iter().enumerate() {
+        write!(f, "{name:name_width$}")?;
+        for (cells, width) in cells.iter().zip(&widths) {
+            write!(f, " {:>width$}", cells[i])?;
+        }
+        if let (Some(stars), Some(width)) = (&stars, star_width) {
+            write!(f, " {:width$}", stars[i])?;
+        }
+        writeln!(f)?;
+    }
+    if stars.is_some() {
+        writeln!(f, "---")?;
+        writeln!(
+            f,
+            "Signif. codes:  0 ‘***’ 0.001 ‘**’ 0.01 ‘*’ 0.05 ‘.’ 0.1 ‘ ’ 1"
+        )?;
+    }
+    Ok(())
+}
+/// `symnum` of a p-value on the cutpoints 0.001, 0.01, 0.05 and 0.1.
+fn significance(p: f64) -> &'static str {
+    match p {
+        p if p.is_nan() => "",
+        p if p <= 0.001 => "***",
+        p if p <= 0.01 => "**",
+        p if p <= 0.05 => "*",
+        p if p <= 0.1 => ".",
+        _ => " ",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zapsmall() {
+        assert_eq!(zapsmall(&[847.7252, 9.277398], 5), [847.73, 9.28]);
+        assert_eq!(zapsmall(&[1e-20, 1.], 5), [0., 1.]);
+    }
+
+    #[test]
+    fn test_format_na() {
+        assert_eq!(
+            format_na(&[37.285126, f64::NAN, -5.344472], 4),
+            ["37.285", "    NA", "-5.344"]
+        );
+    }
+}
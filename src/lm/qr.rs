@@ -0,0 +1,165 @@
+use nalgebra::DMatrix;
+
+/// Householder QR decomposition with LINPACK `dqrdc2`'s limited pivoting: a column whose norm
+/// falls below `tol` times its original norm is moved to the end, beyond the rank.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/appl/dqrdc2.f>
+#[derive(Debug, Clone)]
+pub(super) struct Qr {
+    /// `R` in the upper triangle and the Householder vectors below it
+    pub qr: DMatrix<f64>,
+    pub qraux: Vec<f64>,
+    pub rank: usize,
+    /// the original index of each column
+    pub pivot: Vec<usize>,
+}
+
+pub(super) fn qr(mut x: DMatrix<f64>, tol: f64) -> Qr {
+    let (n, p) = x.shape();
+    let norm_below =
+        |x: &DMatrix<f64>, j: usize, from: usize| x.view((from, j), (n - from, 1)).norm();
+    let mut qraux: Vec<f64> = (0..p).map(|j| norm_below(&x, j, 0)).collect();
+    let mut original: Vec<f64> = qraux
+        .iter()
+        .map(|&norm| if norm == 0. { 1. } else { norm })
+        .collect();
+    let mut pivot: Vec<usize> = (0..p).collect();
+    // columns not moved to the end
+    let mut k = p;
+    for l in 0..n.min(p) {
+        while l < k && qraux[l] < original[l] * tol {
+            for j in l..p - 1 {
+                x.swap_columns(j, j + 1);
+            }
+            pivot[l..].rotate_left(1);
+            qraux[l..].rotate_left(1);
+            original[l..].rotate_left(1);
+            k -= 1;
+        }
+        if l == n - 1 {
+            break;
+        }
+        let mut nrmxl = norm_below(&x, l, l);
+        if nrmxl == 0. {
+            continue;
+        }
+        if x[(l, l)] != 0. {
+            nrmxl = nrmxl.copysign(x[(l, l)]);
+        }
+        for i in l..n {
+            x[(i, l)] /= nrmxl;
+        }
+        x[(l, l)] += 1.;
+        for j in l + 1..p {
+            let t = -(l..n).map(|i| x[(i, l)] * x[(i, j)]).sum::<f64>() / x[(l, l)];
+            for i in l..n {
+                x[(i, j)] += t * x[(i, l)];
+            }
+            if qraux[j] != 0. {
+                let tt = (1. - (x[(l, j)].abs() / qraux[j]).powi(2)).max(0.);
+                // recompute rather than downdate a norm that has nearly vanished
+                qraux[j] = match tt < 1e-6 {
+                    true => norm_below(&x, j, l + 1),
+                    false => qraux[j] * tt.sqrt(),
+                };
+            }
+        }
+        qraux[l] = x[(l, l)];
+        x[(l, l)] = -nrmxl;
+    }
+    Qr {
+        qr: x,
+        qraux,
+        rank: k.min(n),
+        pivot,
+    }
+}
+
+impl Qr {
+    /// `qr.qty(qr, y)`: `Q'y`.
+    pub fn qty(&self, y: &[f64]) -> Vec<f64> {
+        let mut y = y.to_vec();
+        for j in 0..self.transformations() {
+            self.reflect(j, &mut y);
+        }
+        y
+    }
+    /// `qr.qy(qr, y)`: `Qy`.
+    pub fn qy(&self, y: &[f64]) -> Vec<f64> {
+        let mut y = y.to_vec();
+        for j in (0..self.transformations()).rev() {
+            self.reflect(j, &mut y);
+        }
+        y
+    }
+    fn transformations(&self) -> usize {
+        self.rank.min(self.qr.nrows() - 1)
+    }
+    /// Apply the `j`th Householder reflection to `y`.
+    fn reflect(&self, j: usize, y: &mut [f64]) {
+        let diag = self.qraux[j];
+        if diag == 0. {
+            return;
+        }
+        let below = self.qr.view((j + 1, j), (y.len() - j - 1, 1));
+        let dot = diag * y[j]
+            + below
+                .iter()
+                .zip(&y[j + 1..])
+                .map(|(a, b)| a * b)
+                .sum::<f64>();
+        let t = -dot / diag;
+        y[j] += t * diag;
+        for (y, a) in y[j + 1..].iter_mut().zip(below.iter()) {
+            *y += t * a;
+        }
+    }
+    /// The coefficients of the first `rank` pivoted columns from `Q'y`, by back substitution.
+    pub fn coef(&self, qty: &[f64]) -> Vec<f64> {
+        let r = self.r();
+        let b = nalgebra::DVector::from_column_slice(&qty[..self.rank]);
+        r.solve_upper_triangular(&b)
+            .unwrap()
+            .iter()
+            .copied()
+            .collect()
+    }
+    /// The upper triangular `R` of the first `rank` pivoted columns.
+    pub fn r(&self) -> DMatrix<f64> {
+        self.qr
+            .view((0, 0), (self.rank, self.rank))
+            .upper_triangle()
+    }
+    /// `R^-1`, so that `(X'X)^-1` of the first `rank` pivoted columns is `R^-1 R^-T`.
+    pub fn r_inverse(&self) -> DMatrix<f64> {
+        self.r()
+            .solve_upper_triangular(&DMatrix::identity(self.rank, self.rank))
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qr() {
+        // the third column is the sum of the first two
+        let x = DMatrix::from_row_slice(4, 3, &[1., 1., 2., 1., 2., 3., 1., 3., 4., 1., 5., 6.]);
+        let y = [1., 3., 2., 5.];
+        let qr = qr(x.clone(), 1e-7);
+        assert_eq!(qr.rank, 2);
+        assert_eq!(qr.pivot, [0, 1, 2]);
+        let qty = qr.qty(&y);
+        let back = qr.qy(&qty);
+        assert!(back.iter().zip(&y).all(|(a, b)| (a - b).abs() < 1e-12));
+        // least squares line through (1, 1), (2, 3), (3, 2), (5, 5)
+        let coef = qr.coef(&qty);
+        assert!((coef[0] - 11. / 35.).abs() < 1e-12 && (coef[1] - 31. / 35.).abs() < 1e-12);
+
+        // a dependent column in the middle moves to the end
+        let x = DMatrix::from_row_slice(3, 3, &[1., 2., 0., 1., 2., 1., 1., 2., 3.]);
+        let qr = super::qr(x, 1e-7);
+        assert_eq!((qr.rank, qr.pivot), (2, vec![0, 2, 1]));
+    }
+}
@@ -0,0 +1,323 @@
+use std::fmt;
+
+use statrs::distribution::{ContinuousCDF, FisherSnedecor, StudentsT};
+
+use super::{
+    Formula, Lm,
+    print::{CoefColumn, format_na, write_coefmat, write_named, zapsmall},
+};
+use crate::{
+    htest::{
+        ConfInt,
+        format::{format_c, format_number, format_pval},
+    },
+    matrix::{FillOrdering, MatrixBuf, matrix},
+    vector::{mean, quantile, signif},
+    warning::warning,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coefficient {
+    pub name: String,
+    pub estimate: f64,
+    pub std_error: f64,
+    pub t_value: f64,
+    /// two-sided, from the t distribution on the residual degrees of freedom
+    pub p_value: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FStatistic {
+    pub value: f64,
+    pub numdf: f64,
+    pub dendf: f64,
+    pub p_value: f64,
+}
+
+/// `summary(fit)` of a linear model.
+#[derive(Debug, Clone)]
+pub struct LmSummary {
+    /// the coefficients that are not aliased
+    pub coefficients: Vec<Coefficient>,
+    /// each column of the design, and whether it is aliased
+    pub aliased: Vec<(String, bool)>,
+    pub residuals: Vec<f64>,
+    /// the residual standard error
+    pub sigma: f64,
+    /// the rank, the residual degrees of freedom and the number of columns
+    pub df: [usize; 3],
+    pub r_squared: f64,
+    pub adj_r_squared: f64,
+    /// `None` for a model with the intercept alone
+    pub f_statistic: Option<FStatistic>,
+    /// `(X'X)^-1` of the coefficients that are not aliased
+    pub cov_unscaled: MatrixBuf<f64>,
+    pub formula: Option<Formula>,
+}
+
+/// `summary(fit)`: standard errors and t tests of the coefficients, the residual standard
+/// error, R² and the F test of the model against the intercept alone.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/lm.R>
+pub fn summary(fit: &Lm) -> LmSummary {
+    let p = fit.rank;
+    let rdf = fit.df_residual;
+    let n = fit.residuals.len();
+    let rss: f64 = fit.residuals.iter().map(|r| r * r).sum();
+    let resvar = rss / rdf as f64;
+    let f = &fit.fitted_values;
+    let mss: f64 = match fit.design.intercept {
+        true => {
+            let m = mean(f);
+            f.iter().map(|f| (f - m).powi(2)).sum()
+        }
+        false => f.iter().map(|f| f * f).sum(),
+    };
+    let mean_f = mean(f);
+    let var_f = f.iter().map(|f| (f - mean_f).powi(2)).sum::<f64>() / (n - 1) as f64;
+    let scale = mean_f.powi(2) + var_f;
+    if resvar.is_finite() && resvar < scale * 1e-30 {
+        warning("essentially perfect fit: summary may be unreliable");
+    }
+    // the estimable columns in their original order
+    let mut order: Vec<usize> = (0..p).collect();
+    order.sort_by_key(|&i| fit.qr.pivot[i]);
+    let r_inverse = fit.qr.r_inverse();
+    let cov = &r_inverse * r_inverse.transpose();
+    let t = (0 < rdf).then(|| StudentsT::new(0., 1., rdf as f64).unwrap());
+    let coefficients = order
+        .iter()
+        .map(|&i| {
+            let j = fit.qr.pivot[i];
+            let estimate = fit.coefficients[j];
+            let std_error = (cov[(i, i)] * resvar).sqrt();
+            let t_value = estimate / std_error;
+            Coefficient {
+                name: fit.design.names[j].clone(),
+                estimate,
+                std_error,
+                t_value,
+                p_value: t.map_or(f64::NAN, |t| 2. * t.cdf(-t_value.abs())),
+            }
+        })
+        .collect();
+    let cov: Vec<f64> = order
+        .iter()
+        .flat_map(|&i| order.iter().map(move |&k| (i, k)))
+        .map(|(i, k)| cov[(i, k)])
+        .collect();
+    let df_int = usize::from(fit.design.intercept);
+    let (r_squared, adj_r_squared, f_statistic) = match p == df_int {
+        true => (0., 0., None),
+        false => {
+            let r_squared = mss / (mss + rss);
+            let adj = 1. - (1. - r_squared) * ((n - df_int) as f64 / rdf as f64);
+            let (numdf, dendf) = ((p - df_int) as f64, rdf as f64);
+            let value = (mss / numdf) / resvar;
+            let p_value = match 0 < rdf {
+                true => FisherSnedecor::new(numdf, dendf).unwrap().sf(value),
+                false => f64::NAN,
+            };
+            let f = FStatistic {
+                value,
+                numdf,
+                dendf,
+                p_value,
+            };
+            (r_squared, adj, Some(f))
+        }
+    };
+    let ncol = fit.coefficients.len();
+    LmSummary {
+        coefficients,
+        aliased: fit
+            .design
+            .names
+            .iter()
+            .zip(&fit.coefficients)
+            .map(|(name, b)| (name.clone(), b.is_nan()))
+            .collect(),
+        residuals: fit.residuals.clone(),
+        sigma: resvar.sqrt(),
+        df: [p, rdf, ncol],
+        r_squared,
+        adj_r_squared,
+        f_statistic,
+        cov_unscaled: match p {
+            0 => matrix([0.], [0, 0], FillOrdering::RowByRow),
+            _ => matrix(cov, [p, p], FillOrdering::RowByRow),
+        },
+        formula: fit.formula.clone(),
+    }
+}
+
+/// `confint(fit, level = level)`: confidence intervals for the coefficients from the t
+/// distribution, with `NaN` bounds for the aliased ones.
+pub fn confint(fit: &Lm, level: f64) -> Vec<ConfInt> {
+    assert!(0. < level && level < 1., "'level' must be in (0, 1)");
+    let summary = summary(fit);
+    let rdf = fit.df_residual;
+    let q = match 0 < rdf {
+        true => StudentsT::new(0., 1., rdf as f64)
+            .unwrap()
+            .inverse_cdf((1. + level) / 2.),
+        false => f64::NAN,
+    };
+    let mut coefficients = summary.coefficients.iter();
+    summary
+        .aliased
+        .iter()
+        .map(|&(_, aliased)| {
+            let (lower, upper) = match aliased {
+                true => (f64::NAN, f64::NAN),
+                false => {
+                    let b = coefficients.next().unwrap();
+                    (b.estimate - q * b.std_error, b.estimate + q * b.std_error)
+                }
+            };
+            ConfInt {
+                lower,
+                upper,
+                level,
+            }
+        })
+        .collect()
+}
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/lm.R>
+impl fmt::Display for LmSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = 4;
+        let [rank, rdf, ncol] = self.df;
+        writeln!(f)?;
+        if let Some(formula) = &self.formula {
+            writeln!(f, "Call:")?;
+            writeln!(f, "lm(formula = {formula})")?;
+            writeln!(f)?;
+        }
+        writeln!(f, "Residuals:")?;
+        if 5 < rdf {
+            let names = ["Min", "1Q", "Median", "3Q", "Max"].map(String::from);
+            let q = quantile(&self.residuals, [0., 0.25, 0.5, 0.75, 1.]);
+            let q = format_na(&zapsmall(&q, digits + 1), digits);
+            write_named(f, &names, &q, 1)?;
+        } else if 0 < rdf {
+            let names: Vec<String> = (1..=self.residuals.len()).map(|i| i.to_string()).collect();
+            write_named(f, &names, &format_na(&self.residuals, digits), 1)?;
+        } else {
+            writeln!(
+                f,
+                "ALL {rank} residuals are 0: no residual degrees of freedom!"
+            )?;
+        }
+        writeln!(f)?;
+        match ncol - rank {
+            0 => writeln!(f, "Coefficients:")?,
+            singular => writeln!(
+                f,
+                "Coefficients: ({singular} not defined because of singularities)"
+            )?,
+        }
+        // every column, with the aliased ones as NA
+        let mut names = vec![];
+        let mut columns = [vec![], vec![], vec![], vec![]];
+        let mut coefficients = self.coefficients.iter();
+        for (name, aliased) in &self.aliased {
+            let values = match *aliased {
+                true => [f64::NAN; 4],
+                false => {
+                    let b = coefficients.next().unwrap();
+                    [b.estimate, b.std_error, b.t_value, b.p_value]
+                }
+            };
+            names.push(name.clone());
+            for (column, value) in columns.iter_mut().zip(values) {
+                column.push(value);
+            }
+        }
+        let [estimate, std_error, t_value, p_value] = columns;
+        let columns = [
+            ("Estimate", CoefColumn::Coef, estimate),
+            ("Std. Error", CoefColumn::Coef, std_error),
+            ("t value", CoefColumn::Test, t_value),
+            ("Pr(>|t|)", CoefColumn::PValue, p_value),
+        ];
+        write_coefmat(f, &names, &columns, digits, "NA")?;
+        writeln!(f)?;
+        let sigma = format_number(signif([self.sigma], digits as i32)[0], 7);
+        writeln!(
+            f,
+            "Residual standard error: {sigma} on {rdf} degrees of freedom"
+        )?;
+        if let Some(fstat) = &self.f_statistic {
+            let p_value = format_pval(fstat.p_value, digits);
+            writeln!(
+                f,
+                "Multiple R-squared:  {},\tAdjusted R-squared:  {} ",
+                format_c(self.r_squared, digits),
+                format_c(self.adj_r_squared, digits)
+            )?;
+            writeln!(
+                f,
+                "F-statistic: {} on {} and {} DF,  p-value: {p_value}",
+                format_c(fstat.value, digits),
+                fstat.numdf,
+                fstat.dendf
+            )?;
+        }
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lm::{formula, lm, lm_fit, tests::mtcars};
+
+    #[test]
+    fn test_summary() {
+        let fit = lm(&formula("mpg ~ wt"), &mtcars());
+        let s = summary(&fit);
+        assert!((s.coefficients[1].std_error - 0.5591010).abs() < 1e-6);
+        assert!((s.sigma - 3.045882).abs() < 1e-6);
+        assert!((s.r_squared - 0.7528328).abs() < 1e-7);
+        let expected = [
+            "",
+            "Call:",
+            "lm(formula = mpg ~ wt)",
+            "",
+            "Residuals:",
+            "    Min      1Q  Median      3Q     Max ",
+            "-4.5432 -2.3647 -0.1252  1.4096  6.8727 ",
+            "",
+            "Coefficients:",
+            "            Estimate Std. Error t value Pr(>|t|)    ",
+            "(Intercept)  37.2851     1.8776  19.858  < 2e-16 ***",
+            "wt           -5.3445     0.5591  -9.559 1.29e-10 ***",
+            "---",
+            "Signif. codes:  0 ‘***’ 0.001 ‘**’ 0.01 ‘*’ 0.05 ‘.’ 0.1 ‘ ’ 1",
+            "",
+            "Residual standard error: 3.046 on 30 degrees of freedom",
+            "Multiple R-squared:  0.7528,\tAdjusted R-squared:  0.7446 ",
+            "F-statistic: 91.38 on 1 and 30 DF,  p-value: 1.294e-10",
+            "",
+            "",
+        ];
+        assert_eq!(s.to_string(), expected.join("\n"));
+    }
+
+    #[test]
+    fn test_confint() {
+        let fit = lm(&formula("mpg ~ wt"), &mtcars());
+        let ci = confint(&fit, 0.95);
+        assert!((ci[0].lower - 33.450500).abs() < 1e-5);
+        assert!((ci[0].upper - 41.119753).abs() < 1e-5);
+        assert!((ci[1].lower - -6.486308).abs() < 1e-5);
+        assert!((ci[1].upper - -4.202635).abs() < 1e-5);
+
+        // a saturated fit has no residual degrees of freedom
+        let x = matrix([1., 1., 1., 2.], [2, 2], FillOrdering::RowByRow);
+        let ci = confint(&lm_fit([1., 3.], x), 0.95);
+        assert!(ci.iter().all(|ci| ci.lower.is_nan() && ci.upper.is_nan()));
+    }
+}
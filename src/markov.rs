@@ -0,0 +1,388 @@
+use nalgebra::{DMatrix, DVector};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::matrix::{MatrixBuf, malgebra_to_matrix_buf, matrix_buf_to_malgebra};
+
+/// Tolerance on row sums of transition and generator matrices.
+const ROW_SUM_TOL: f64 = 1e-8;
+
+/// Square, with entries in `[0, 1]` and rows summing to 1.
+pub fn is_transition_matrix(p: impl AsRef<MatrixBuf<f64>>) -> bool {
+    let p = matrix_buf_to_malgebra(p);
+    p.is_square()
+        && p.iter().all(|&x| (0. ..=1.).contains(&x))
+        && p.row_iter()
+            .all(|row| (row.sum() - 1.).abs() <= ROW_SUM_TOL)
+}
+/// Square, with non-negative off-diagonal rates and rows summing to 0.
+pub fn is_generator_matrix(q: impl AsRef<MatrixBuf<f64>>) -> bool {
+    let q = matrix_buf_to_malgebra(q);
+    let scale = q.iter().fold(1_f64, |m, x| m.max(x.abs()));
+    q.is_square()
+        && q.iter().all(|x| x.is_finite())
+        && (0..q.nrows()).all(|i| (0..q.ncols()).all(|j| i == j || 0. <= q[(i, j)]))
+        && q.row_iter()
+            .all(|row| row.sum().abs() <= ROW_SUM_TOL * scale)
+}
+fn transition(p: impl AsRef<MatrixBuf<f64>>) -> DMatrix<f64> {
+    let p = p.as_ref();
+    assert!(
+        is_transition_matrix(p),
+        "not a transition matrix: entries must be in [0, 1] and rows must sum to 1"
+    );
+    matrix_buf_to_malgebra(p)
+}
+
+/// `n`-step transition probabilities `P^n`, by repeated squaring.
+pub fn transition_power(p: impl AsRef<MatrixBuf<f64>>, n: u32) -> MatrixBuf<f64> {
+    let p = transition(p);
+    malgebra_to_matrix_buf(p.pow(n))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StationaryMethod {
+    /// left eigenvector for the eigenvalue 1, by inverse iteration
+    Eigen,
+    /// `π (P - I) = 0` with one equation replaced by `Σ π = 1`
+    Solve,
+}
+/// The stationary distribution `π = π P`; panics when it is not unique.
+pub fn stationary(p: impl AsRef<MatrixBuf<f64>>, method: StationaryMethod) -> Vec<f64> {
+    let p = transition(p);
+    let n = p.nrows();
+    assert!(0 < n, "the transition matrix has no states");
+    let pt = p.transpose();
+    // unique exactly when the eigenvalue 1 is simple, so that `P - I` has rank `n - 1`
+    assert!(
+        (&pt - DMatrix::identity(n, n)).rank(1e-9) == n - 1,
+        "stationary distribution is not unique"
+    );
+    let pi = match method {
+        StationaryMethod::Eigen => {
+            // shifted just past the eigenvalue 1, so each solve amplifies its eigenvector
+            let lu = (pt - DMatrix::identity(n, n) * (1. + 1e-10)).lu();
+            let mut x = DVector::from_element(n, 1. / n as f64);
+            for _ in 0..50 {
+                let next = lu.solve(&x).expect("stationary distribution is not unique");
+                let next = &next / next.sum();
+                let change = (&next - &x).amax();
+                x = next;
+                if change < 1e-15 {
+                    break;
+                }
+            }
+            x
+        }
+        StationaryMethod::Solve => {
+            let mut a = pt - DMatrix::identity(n, n);
+            a.row_mut(n - 1).fill(1.);
+            let mut b = DVector::zeros(n);
+            b[n - 1] = 1.;
+            a.lu()
+                .solve(&b)
+                .expect("stationary distribution is not unique")
+        }
+    };
+    assert!(
+        pi.iter().all(|&x| -1e-10 < x),
+        "stationary distribution is not unique"
+    );
+    pi.iter().map(|x| x.max(0.)).collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct Absorption {
+    pub transient: Vec<usize>,
+    pub absorbing: Vec<usize>,
+    /// `B = (I - Q)⁻¹ R`: row `i` gives the probabilities of ending in each absorbing state when
+    /// starting from `transient[i]`
+    pub probabilities: MatrixBuf<f64>,
+    /// expected steps before absorption from each transient state
+    pub expected_steps: Vec<f64>,
+    /// fundamental matrix `N = (I - Q)⁻¹`: expected visits to `transient[j]` from `transient[i]`
+    pub fundamental: MatrixBuf<f64>,
+}
+/// Absorption probabilities and times of an absorbing chain, where every state that is not
+/// absorbing (`P[i, i] = 1`) is transient.
+pub fn absorption(p: impl AsRef<MatrixBuf<f64>>) -> Absorption {
+    let p = transition(p);
+    let n = p.nrows();
+    let (absorbing, transient): (Vec<usize>, Vec<usize>) = (0..n).partition(|&i| p[(i, i)] == 1.);
+    assert!(!absorbing.is_empty(), "the chain has no absorbing state");
+    let q = p.select_rows(&transient).select_columns(&transient);
+    let r = p.select_rows(&transient).select_columns(&absorbing);
+    let fundamental = (DMatrix::identity(transient.len(), transient.len()) - q)
+        .try_inverse()
+        .expect("some non-absorbing states never reach an absorbing state");
+    let probabilities = &fundamental * r;
+    let expected_steps = fundamental.row_iter().map(|row| row.sum()).collect();
+    Absorption {
+        transient,
+        absorbing,
+        probabilities: malgebra_to_matrix_buf(probabilities),
+        expected_steps,
+        fundamental: malgebra_to_matrix_buf(fundamental),
+    }
+}
+
+/// Expected steps to first reach any of `target` from each state; `0` on the targets and `∞`
+/// from states that cannot reach them.
+pub fn hitting_times(p: impl AsRef<MatrixBuf<f64>>, target: impl AsRef<[usize]>) -> Vec<f64> {
+    let p = transition(p);
+    let n = p.nrows();
+    let target = target.as_ref();
+    assert!(target.iter().all(|&i| i < n), "target state out of range");
+    // states that reach the target, by search backwards along the transitions
+    let mut reaches = vec![false; n];
+    let mut stack = target.to_vec();
+    while let Some(j) = stack.pop() {
+        if !reaches[j] {
+            reaches[j] = true;
+            stack.extend((0..n).filter(|&i| !reaches[i] && 0. < p[(i, j)]));
+        }
+    }
+    let mut h = vec![f64::INFINITY; n];
+    for &i in target {
+        h[i] = 0.;
+    }
+    // once reachable, the target is reached with probability 1 unless mass leaks to states that
+    // cannot reach it, which makes the expectation infinite
+    let rest: Vec<usize> = (0..n)
+        .filter(|&i| reaches[i] && !target.contains(&i))
+        .collect();
+    let leaks = |i: usize| (0..n).any(|j| !reaches[j] && 0. < p[(i, j)]);
+    let mut infinite = vec![false; n];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &i in &rest {
+            if !infinite[i] && (leaks(i) || rest.iter().any(|&j| infinite[j] && 0. < p[(i, j)])) {
+                infinite[i] = true;
+                changed = true;
+            }
+        }
+    }
+    let rest: Vec<usize> = rest.into_iter().filter(|&i| !infinite[i]).collect();
+    if rest.is_empty() {
+        return h;
+    }
+    let a = DMatrix::identity(rest.len(), rest.len()) - p.select_rows(&rest).select_columns(&rest);
+    let solution = a
+        .lu()
+        .solve(&DVector::from_element(rest.len(), 1.))
+        .expect("singular hitting time system");
+    for (&i, &x) in rest.iter().zip(solution.iter()) {
+        h[i] = x;
+    }
+    h
+}
+
+/// A path `start, X_1, …, X_steps` of the discrete-time chain.
+pub fn simulate_chain(
+    p: impl AsRef<MatrixBuf<f64>>,
+    start: usize,
+    steps: usize,
+    seed: u64,
+) -> Vec<usize> {
+    let p = transition(p);
+    assert!(start < p.nrows(), "start state out of range");
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut path = Vec::with_capacity(steps + 1);
+    path.push(start);
+    let mut state = start;
+    for _ in 0..steps {
+        state = draw_index(&mut rng, p.row(state).iter().copied());
+        path.push(state);
+    }
+    path
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CtmcPath {
+    /// jump times, starting with 0
+    pub times: Vec<f64>,
+    /// state entered at each of `times`
+    pub states: Vec<usize>,
+}
+/// A path of the continuous-time chain with generator `q` on `[0, horizon]`: exponential holding
+/// times with rate `-q[i, i]`, then a jump to `j` with probability `q[i, j] / -q[i, i]`.
+pub fn simulate_ctmc(
+    q: impl AsRef<MatrixBuf<f64>>,
+    start: usize,
+    horizon: f64,
+    seed: u64,
+) -> CtmcPath {
+    let q = q.as_ref();
+    assert!(
+        is_generator_matrix(q),
+        "not a generator matrix: off-diagonal rates must be non-negative and rows must sum to 0"
+    );
+    let q = matrix_buf_to_malgebra(q);
+    assert!(start < q.nrows(), "start state out of range");
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut path = CtmcPath {
+        times: vec![0.],
+        states: vec![start],
+    };
+    let (mut t, mut state) = (0., start);
+    loop {
+        let rate = -q[(state, state)];
+        if rate <= 0. {
+            // absorbing
+            break;
+        }
+        t += -(1. - rng.r#gen::<f64>()).ln() / rate;
+        if horizon < t {
+            break;
+        }
+        let jumps = (0..q.ncols()).map(|j| if j == state { 0. } else { q[(state, j)] });
+        state = draw_index(&mut rng, jumps);
+        path.times.push(t);
+        path.states.push(state);
+    }
+    path
+}
+/// An index drawn with probability proportional to `weights`.
+fn draw_index(rng: &mut StdRng, weights: impl Iterator<Item = f64> + Clone) -> usize {
+    let total: f64 = weights.clone().sum();
+    let u = rng.r#gen::<f64>() * total;
+    let mut cumulative = 0.;
+    let mut last = 0;
+    for (i, w) in weights.enumerate() {
+        if 0. < w {
+            cumulative += w;
+            last = i;
+            if u < cumulative {
+                return i;
+            }
+        }
+    }
+    // rounding left `u` past the last cumulative sum
+    last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::{FillOrdering, entry, matrix};
+
+    fn weather() -> MatrixBuf<f64> {
+        #[rustfmt::skip]
+        let p = [
+            0.5, 0.4, 0.1,
+            0.3, 0.4, 0.3,
+            0.2, 0.3, 0.5,
+        ];
+        matrix(p, [3, 3], FillOrdering::RowByRow)
+    }
+
+    #[test]
+    fn test_stationary() {
+        let p = weather();
+        assert!(is_transition_matrix(&p));
+        assert!(!is_transition_matrix(matrix(
+            [0.5, 0.6, 0., 1.],
+            [2, 2],
+            FillOrdering::RowByRow
+        )));
+        // π = (21, 23, 18) / 62
+        let expected = [21. / 62., 23. / 62., 18. / 62.];
+        for method in [StationaryMethod::Eigen, StationaryMethod::Solve] {
+            let pi = stationary(&p, method);
+            for (a, b) in pi.iter().zip(expected) {
+                assert!((a - b).abs() < 1e-12, "{method:?} {pi:?}");
+            }
+        }
+        let p50 = transition_power(&p, 50);
+        for i in 0..3 {
+            for (j, e) in expected.iter().enumerate() {
+                assert!((entry(&p50, [j, i]) - e).abs() < 1e-12);
+            }
+        }
+        let p2 = transition_power(&p, 2);
+        assert!((entry(&p2, [0, 0]) - (0.25 + 0.12 + 0.02)).abs() < 1e-15);
+
+        let path = simulate_chain(&p, 0, 100_000, 1);
+        assert_eq!(path.len(), 100_001);
+        assert_eq!(path, simulate_chain(&p, 0, 100_000, 1));
+        for (state, e) in expected.iter().enumerate() {
+            let share = path.iter().filter(|&&s| s == state).count() as f64 / path.len() as f64;
+            assert!((share - e).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "stationary distribution is not unique")]
+    fn test_stationary_identity() {
+        let p = matrix([1., 0., 0., 1.], [2, 2], FillOrdering::RowByRow);
+        stationary(p, StationaryMethod::Eigen);
+    }
+
+    #[test]
+    #[should_panic(expected = "stationary distribution is not unique")]
+    fn test_stationary_reducible() {
+        #[rustfmt::skip]
+        let p = [
+            0.5, 0.5, 0., 0.,
+            0.5, 0.5, 0., 0.,
+            0., 0., 0.4, 0.6,
+            0., 0., 0.3, 0.7,
+        ];
+        stationary(
+            matrix(p, [4, 4], FillOrdering::RowByRow),
+            StationaryMethod::Eigen,
+        );
+    }
+
+    #[test]
+    fn test_absorption() {
+        // gambler's ruin on 0..=4 with a fair coin
+        let mut p = vec![0.; 25];
+        p[0] = 1.;
+        p[24] = 1.;
+        for i in 1..4 {
+            p[i * 5 + i - 1] = 0.5;
+            p[i * 5 + i + 1] = 0.5;
+        }
+        let p = matrix(p, [5, 5], FillOrdering::RowByRow);
+        let a = absorption(&p);
+        assert_eq!(a.transient, [1, 2, 3]);
+        assert_eq!(a.absorbing, [0, 4]);
+        for (i, k) in (1..4).enumerate() {
+            let ruin = 1. - k as f64 / 4.;
+            assert!((entry(&a.probabilities, [0, i]) - ruin).abs() < 1e-12);
+            // k (4 - k) expected rounds
+            assert!((a.expected_steps[i] - (k * (4 - k)) as f64).abs() < 1e-12);
+        }
+        let h = hitting_times(&p, [4]);
+        assert_eq!(h[4], 0.);
+        assert!(h[0..4].iter().all(|h| h.is_infinite()));
+        let h = hitting_times(weather(), [2]);
+        assert_eq!(h[2], 0.);
+        // h0 = 1 + 0.5 h0 + 0.4 h1, h1 = 1 + 0.3 h0 + 0.4 h1
+        assert!((h[0] - 50. / 9.).abs() < 1e-12 && (h[1] - 40. / 9.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ctmc() {
+        #[rustfmt::skip]
+        let q = matrix([
+            -1., 1.,
+            2., -2.,
+        ], [2, 2], FillOrdering::RowByRow);
+        assert!(is_generator_matrix(&q));
+        let horizon = 10_000.;
+        let path = simulate_ctmc(&q, 0, horizon, 3);
+        assert_eq!(path.times.len(), path.states.len());
+        assert!(path.states.windows(2).all(|w| w[0] != w[1]));
+        // long-run share of time in state 0 is 2 / 3
+        let mut in_zero = 0.;
+        for (k, &t) in path.times.iter().enumerate() {
+            let end = path.times.get(k + 1).copied().unwrap_or(horizon);
+            if path.states[k] == 0 {
+                in_zero += end - t;
+            }
+        }
+        assert!((in_zero / horizon - 2. / 3.).abs() < 0.02);
+    }
+}
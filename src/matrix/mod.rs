@@ -1,9 +1,11 @@
 mod constructor;
 mod ops;
+mod reshape;
 use std::marker::PhantomData;
 
 pub use constructor::*;
 pub use ops::*;
+pub use reshape::*;
 
 /// ref: <https://github.com/wch/r-source/blob/67e3ab91b0489f56520142ce9352d68aa9a49ab0/src/main/array.c#L232>
 #[derive(Debug, Clone)]
@@ -40,6 +42,16 @@ pub fn entry_mut<T>(matrix: &mut MatrixBuf<T>, index: [usize; 2]) -> &mut T {
     let dim = matrix.dim();
     &mut matrix.data_mut()[entry_index(&EntryIndexParams { index, dim })]
 }
+/// `matrix[, col]`
+pub fn column<T>(matrix: impl AsRef<MatrixBuf<T>>, col: usize) -> Vec<T>
+where
+    T: Copy,
+{
+    let matrix = matrix.as_ref();
+    (0..matrix.dim()[1])
+        .map(|row| entry(matrix, [col, row]))
+        .collect()
+}
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct EntryIndexParams {
     pub index: [usize; 2],
@@ -47,11 +47,74 @@ mod single_vector_in_single_vector_out {
         elem_op1(matrix, |x| vector::floor(x)) }
     pub fn ceil<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
         elem_op1(matrix, |x| vector::ceil(x)) }
-    pub fn round<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
-        elem_op1(matrix, |x| vector::round(x)) }
+    pub fn round<T>(matrix: impl AsRef<MatrixBuf<T>>, digits: i32) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::round(x, digits)) }
+    pub fn signif<T>(matrix: impl AsRef<MatrixBuf<T>>, digits: i32) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::signif(x, digits)) }
+    pub fn trunc<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::trunc(x)) }
+    pub fn sign<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Signed + PartialOrd + Clone {
+        elem_op1(matrix, |x| vector::sign(x)) }
+    pub fn ln<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::ln(x)) }
+    pub fn log<T>(matrix: impl AsRef<MatrixBuf<T>>, base: T) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::log(x, base)) }
+    pub fn log2<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::log2(x)) }
+    pub fn log10<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::log10(x)) }
+    pub fn log1p<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::log1p(x)) }
+    pub fn expm1<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::expm1(x)) }
+    pub fn asin<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::asin(x)) }
+    pub fn acos<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::acos(x)) }
+    pub fn atan<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::atan(x)) }
+    pub fn sinh<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::sinh(x)) }
+    pub fn cosh<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::cosh(x)) }
+    pub fn tanh<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::tanh(x)) }
+    pub fn asinh<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::asinh(x)) }
+    pub fn acosh<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::acosh(x)) }
+    pub fn atanh<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::atanh(x)) }
+    pub fn sinpi<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float + num_traits::FloatConst {
+        elem_op1(matrix, |x| vector::sinpi(x)) }
+    pub fn cospi<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float + num_traits::FloatConst {
+        elem_op1(matrix, |x| vector::cospi(x)) }
+    pub fn tanpi<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float + num_traits::FloatConst {
+        elem_op1(matrix, |x| vector::tanpi(x)) }
     pub fn factorial<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: CalcFactorial {
         elem_op1(matrix, |x| vector::factorial(x)) }
 }
+pub use special_functions::*;
+#[rustfmt::skip]
+mod special_functions {
+    use super::*;
+    pub fn lgamma<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::lgamma(x)) }
+    pub fn lfactorial<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::lfactorial(x)) }
+    pub fn digamma<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::digamma(x)) }
+    pub fn trigamma<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::trigamma(x)) }
+    pub fn psigamma<T>(matrix: impl AsRef<MatrixBuf<T>>, deriv: u32) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op1(matrix, |x| vector::psigamma(x, deriv)) }
+    pub fn lchoose<T>(a: impl AsRef<MatrixBuf<T>>, b: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op2(a, b, |a, b| vector::lchoose(a, b)) }
+    pub fn beta<T>(a: impl AsRef<MatrixBuf<T>>, b: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op2(a, b, |a, b| vector::beta(a, b)) }
+    pub fn lbeta<T>(a: impl AsRef<MatrixBuf<T>>, b: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op2(a, b, |a, b| vector::lbeta(a, b)) }
+}
 fn elem_op1<A, B>(
     input: impl AsRef<MatrixBuf<A>>,
     vec_op: impl Fn(&[A]) -> Vec<B>,
@@ -97,6 +160,8 @@ mod two_vectors_in_single_vector_out {
         elem_op2(a, b, |a, b| vector::xor(a, b)) }
     pub fn choose<T>(a: impl AsRef<MatrixBuf<T>>, b: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: CalcChoose + Clone {
         elem_op2(a, b, |a, b| vector::choose(a, b)) }
+    pub fn atan2<T>(y: impl AsRef<MatrixBuf<T>>, x: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T> where T: num_traits::Float {
+        elem_op2(y, x, |y, x| vector::atan2(y, x)) }
 }
 fn elem_op2<A, B, C>(
     a: impl AsRef<MatrixBuf<A>>,
@@ -185,14 +250,14 @@ where
     m
 }
 
-fn matrix_buf_to_malgebra<T>(m: impl AsRef<MatrixBuf<T>>) -> nalgebra::DMatrix<T>
+pub(crate) fn matrix_buf_to_malgebra<T>(m: impl AsRef<MatrixBuf<T>>) -> nalgebra::DMatrix<T>
 where
     T: nalgebra::Scalar + Copy,
 {
     let m = m.as_ref();
     nalgebra::DMatrix::from_fn(m.dim()[1], m.dim()[0], |r, c| entry(m, [c, r]))
 }
-fn malgebra_to_matrix_buf<T, R, C, S>(m: nalgebra::Matrix<T, R, C, S>) -> MatrixBuf<T>
+pub(crate) fn malgebra_to_matrix_buf<T, R, C, S>(m: nalgebra::Matrix<T, R, C, S>) -> MatrixBuf<T>
 where
     T: Copy,
     R: nalgebra::Dim,
@@ -224,6 +289,13 @@ mod tests {
         assert_eq!(b.data(), [1, 4, 2, 5, 3, 6]);
     }
 
+    #[test]
+    fn test_round() {
+        let a = matrix([0.5, 1.5, 2.675, -2.5], [2, 2], FillOrdering::RowByRow);
+        assert_eq!(round(&a, 0).data(), [0., 2., 3., -2.]);
+        assert_eq!(round(&a, 2).data(), [0.5, 1.5, 2.67, -2.5]);
+    }
+
     #[test]
     fn test_det() {
         let a = matrix([1, -2, 3, 2, 0, 3, 1, 5, 4], [3, 3], FillOrdering::RowByRow);
@@ -0,0 +1,96 @@
+use crate::{matrix::MatrixBuf, vector};
+
+fn rows<T>(matrix: &MatrixBuf<T>) -> std::slice::Chunks<'_, T> {
+    matrix.data().chunks(matrix.dim()[0].max(1))
+}
+fn from_rows<'a, T>(n_cols: usize, rows: impl IntoIterator<Item = &'a [T]>) -> MatrixBuf<T>
+where
+    T: Copy + 'a,
+{
+    let mut data = vec![];
+    let mut n_rows = 0;
+    for row in rows {
+        data.extend_from_slice(row);
+        n_rows += 1;
+    }
+    MatrixBuf {
+        dim: [n_cols, n_rows],
+        data,
+    }
+}
+
+/// A negative `n` keeps all but the last `-n` rows.
+pub fn head<T>(matrix: impl AsRef<MatrixBuf<T>>, n: isize) -> MatrixBuf<T>
+where
+    T: Copy,
+{
+    let matrix = matrix.as_ref();
+    let len = vector::head_len(matrix.dim()[1], n);
+    from_rows(matrix.dim()[0], rows(matrix).take(len))
+}
+/// A negative `n` keeps all but the first `-n` rows.
+pub fn tail<T>(matrix: impl AsRef<MatrixBuf<T>>, n: isize) -> MatrixBuf<T>
+where
+    T: Copy,
+{
+    let matrix = matrix.as_ref();
+    let len = vector::head_len(matrix.dim()[1], n);
+    from_rows(matrix.dim()[0], rows(matrix).skip(matrix.dim()[1] - len))
+}
+/// Insert the rows of `values` after the first `after` rows of `matrix`.
+pub fn append<T>(
+    matrix: impl AsRef<MatrixBuf<T>>,
+    values: impl AsRef<MatrixBuf<T>>,
+    after: usize,
+) -> MatrixBuf<T>
+where
+    T: Copy,
+{
+    let matrix = matrix.as_ref();
+    let values = values.as_ref();
+    assert_eq!(matrix.dim()[0], values.dim()[0]);
+    let after = after.min(matrix.dim()[1]);
+    let rows = rows(matrix)
+        .take(after)
+        .chain(self::rows(values))
+        .chain(self::rows(matrix).skip(after));
+    from_rows(matrix.dim()[0], rows)
+}
+pub fn rev<T>(matrix: impl AsRef<MatrixBuf<T>>) -> MatrixBuf<T>
+where
+    T: Copy,
+{
+    let matrix = matrix.as_ref();
+    from_rows(matrix.dim()[0], rows(matrix).rev())
+}
+/// Shift rows `n` places towards the top, wrapping around; a negative `n` shifts towards the bottom.
+pub fn rotate<T>(matrix: impl AsRef<MatrixBuf<T>>, n: isize) -> MatrixBuf<T>
+where
+    T: Copy,
+{
+    let matrix = matrix.as_ref();
+    let rows: Vec<&[T]> = rows(matrix).collect();
+    let rows = vector::rotate(rows, n);
+    from_rows(matrix.dim()[0], rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matrix::{FillOrdering, matrix};
+
+    use super::*;
+
+    #[test]
+    fn test_rows() {
+        let a = matrix([1, 2, 3, 4, 5, 6], [2, 3], FillOrdering::RowByRow);
+        assert_eq!(head(&a, 1).data(), [1, 2]);
+        assert_eq!(head(&a, -1).dim(), [2, 2]);
+        assert_eq!(tail(&a, 1).data(), [5, 6]);
+        assert_eq!(rev(&a).data(), [5, 6, 3, 4, 1, 2]);
+        assert_eq!(rotate(&a, 1).data(), [3, 4, 5, 6, 1, 2]);
+        let b = matrix([0], [2, 1], FillOrdering::RowByRow);
+        let c = append(&a, &b, 1);
+        assert_eq!(c.dim(), [2, 4]);
+        assert_eq!(c.data(), [1, 2, 0, 0, 3, 4, 5, 6]);
+    }
+}
@@ -0,0 +1,419 @@
+use std::ops::Range;
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+#[derive(Debug, Clone, Copy)]
+pub struct McParams {
+    /// total number of draws, split across the batches
+    pub n: usize,
+    /// confidence level of the interval
+    pub level: f64,
+    pub seed: u64,
+    /// batches run on their own threads, each with an independent stream seeded from `seed`;
+    /// the estimate does not depend on how the threads are scheduled
+    pub batches: usize,
+}
+impl McParams {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            level: 0.95,
+            seed: 0,
+            batches: 1,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<McParams> for McParams { fn as_ref(&self) -> &McParams { self } }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct McEstimate {
+    pub estimate: f64,
+    pub std_error: f64,
+    /// normal-theory interval at `level`
+    pub conf_int: [f64; 2],
+    /// draws of the integrand
+    pub n: usize,
+}
+impl McEstimate {
+    fn new(estimate: f64, std_error: f64, n: usize, level: f64) -> Self {
+        let z = Normal::new(0., 1.).unwrap().inverse_cdf((1. + level) / 2.);
+        Self {
+            estimate,
+            std_error,
+            conf_int: [estimate - z * std_error, estimate + z * std_error],
+            n,
+        }
+    }
+}
+
+/// `E[X]` from draws `draw(rng)` of `X`.
+pub fn expectation(
+    draw: impl Fn(&mut StdRng) -> f64 + Sync,
+    params: impl AsRef<McParams>,
+) -> McEstimate {
+    let params = params.as_ref();
+    let m = run_batches(params, |rng, draws| {
+        let mut m = Moments::default();
+        for _ in draws {
+            m.push(draw(rng));
+        }
+        m
+    });
+    McEstimate::new(m.mean, m.std_error(), m.n, params.level)
+}
+
+/// `∫ f` over the box `[lower, upper]` from uniform draws.
+pub fn crude(
+    f: impl Fn(&[f64]) -> f64 + Sync,
+    lower: impl AsRef<[f64]>,
+    upper: impl AsRef<[f64]>,
+    params: impl AsRef<McParams>,
+) -> McEstimate {
+    let cube = Cube::new(lower.as_ref(), upper.as_ref());
+    let params = params.as_ref();
+    let m = run_batches(params, |rng, draws| {
+        let mut m = Moments::default();
+        let mut x = vec![0.; cube.dim()];
+        for _ in draws {
+            cube.sample(rng, &mut x);
+            m.push(cube.volume * f(&x));
+        }
+        m
+    });
+    McEstimate::new(m.mean, m.std_error(), m.n, params.level)
+}
+
+/// Crude Monte Carlo averaging each draw `x` with its reflection `lower + upper - x`; `n` counts
+/// the pairs.
+pub fn antithetic(
+    f: impl Fn(&[f64]) -> f64 + Sync,
+    lower: impl AsRef<[f64]>,
+    upper: impl AsRef<[f64]>,
+    params: impl AsRef<McParams>,
+) -> McEstimate {
+    let cube = Cube::new(lower.as_ref(), upper.as_ref());
+    let params = params.as_ref();
+    let m = run_batches(params, |rng, draws| {
+        let mut m = Moments::default();
+        let mut x = vec![0.; cube.dim()];
+        for _ in draws {
+            cube.sample(rng, &mut x);
+            let y = f(&x);
+            for (x, (a, b)) in x.iter_mut().zip(cube.lower.iter().zip(cube.upper)) {
+                *x = a + b - *x;
+            }
+            m.push(cube.volume * (y + f(&x)) / 2.);
+        }
+        m
+    });
+    McEstimate::new(m.mean, m.std_error(), 2 * m.n, params.level)
+}
+
+/// Crude Monte Carlo corrected by a control variate `g` whose integral over the box is
+/// `g_integral`, with the regression coefficient estimated from the same draws.
+pub fn control_variate(
+    f: impl Fn(&[f64]) -> f64 + Sync,
+    g: impl Fn(&[f64]) -> f64 + Sync,
+    g_integral: f64,
+    lower: impl AsRef<[f64]>,
+    upper: impl AsRef<[f64]>,
+    params: impl AsRef<McParams>,
+) -> McEstimate {
+    let cube = Cube::new(lower.as_ref(), upper.as_ref());
+    let params = params.as_ref();
+    let m = run_batches(params, |rng, draws| {
+        let mut m = CoMoments::default();
+        let mut x = vec![0.; cube.dim()];
+        for _ in draws {
+            cube.sample(rng, &mut x);
+            m.push(cube.volume * f(&x), cube.volume * g(&x));
+        }
+        m
+    });
+    assert!(2 < m.n, "need at least three draws");
+    let beta = if 0. < m.m2_y { m.c / m.m2_y } else { 0. };
+    let estimate = m.mean_x - beta * (m.mean_y - g_integral);
+    // residual variance of the regression of f on g
+    let residual = (m.m2_x - beta * m.c).max(0.) / (m.n - 2) as f64;
+    let std_error = (residual / m.n as f64).sqrt();
+    McEstimate::new(estimate, std_error, m.n, params.level)
+}
+
+/// `∫ f` as `E[f(X) / density(X)]` with `X` drawn by `sample` from `density`.
+pub fn importance(
+    f: impl Fn(&[f64]) -> f64 + Sync,
+    density: impl Fn(&[f64]) -> f64 + Sync,
+    sample: impl Fn(&mut StdRng) -> Vec<f64> + Sync,
+    params: impl AsRef<McParams>,
+) -> McEstimate {
+    let params = params.as_ref();
+    let m = run_batches(params, |rng, draws| {
+        let mut m = Moments::default();
+        for _ in draws {
+            let x = sample(rng);
+            let q = density(&x);
+            m.push(if q == 0. { 0. } else { f(&x) / q });
+        }
+        m
+    });
+    McEstimate::new(m.mean, m.std_error(), m.n, params.level)
+}
+
+/// Crude Monte Carlo within each of the `strata^d` equal cells of the box, with the draws spread
+/// evenly over the cells.
+pub fn stratified(
+    f: impl Fn(&[f64]) -> f64 + Sync,
+    lower: impl AsRef<[f64]>,
+    upper: impl AsRef<[f64]>,
+    strata: usize,
+    params: impl AsRef<McParams>,
+) -> McEstimate {
+    let cube = Cube::new(lower.as_ref(), upper.as_ref());
+    let params = params.as_ref();
+    assert!(0 < strata, "'strata' must be positive");
+    let cells = u32::try_from(cube.dim())
+        .ok()
+        .and_then(|d| strata.checked_pow(d))
+        .expect("too many strata");
+    assert!(2 * cells <= params.n, "need at least two draws per stratum");
+    let cell_volume = cube.volume / cells as f64;
+    let m = run_batches(params, |rng, draws| {
+        let mut m = vec![Moments::default(); cells];
+        let mut x = vec![0.; cube.dim()];
+        for i in draws {
+            // visit the cells in turn across all batches, so every cell gets its share
+            let mut cell = i % cells;
+            for (j, x) in x.iter_mut().enumerate() {
+                let k = cell % strata;
+                cell /= strata;
+                let width = (cube.upper[j] - cube.lower[j]) / strata as f64;
+                *x = cube.lower[j] + width * (k as f64 + rng.r#gen::<f64>());
+            }
+            m[i % cells].push(cell_volume * f(&x));
+        }
+        m
+    });
+    let estimate = m.iter().map(|m| m.mean).sum();
+    let variance: f64 = m.iter().map(|m| m.std_error().powi(2)).sum();
+    let n = m.iter().map(|m| m.n).sum();
+    McEstimate::new(estimate, variance.sqrt(), n, params.level)
+}
+
+struct Cube<'a> {
+    lower: &'a [f64],
+    upper: &'a [f64],
+    volume: f64,
+}
+impl<'a> Cube<'a> {
+    fn new(lower: &'a [f64], upper: &'a [f64]) -> Self {
+        assert_eq!(lower.len(), upper.len());
+        assert!(
+            lower
+                .iter()
+                .zip(upper)
+                .all(|(a, b)| a.is_finite() && b.is_finite()),
+            "non-finite bounds"
+        );
+        let volume = lower.iter().zip(upper).map(|(a, b)| b - a).product();
+        Self {
+            lower,
+            upper,
+            volume,
+        }
+    }
+    fn dim(&self) -> usize {
+        self.lower.len()
+    }
+    fn sample(&self, rng: &mut StdRng, x: &mut [f64]) {
+        for (x, (a, b)) in x.iter_mut().zip(self.lower.iter().zip(self.upper)) {
+            *x = a + (b - a) * rng.r#gen::<f64>();
+        }
+    }
+}
+
+/// Statistics that can be accumulated per batch and combined afterwards.
+trait Merge {
+    fn merge(self, other: Self) -> Self;
+}
+/// Split the `n` draws into consecutive ranges, one per batch, and run `work(rng, draws)` for
+/// each on its own thread.
+fn run_batches<A>(params: &McParams, work: impl Fn(&mut StdRng, Range<usize>) -> A + Sync) -> A
+where
+    A: Merge + Send,
+{
+    assert!(0 < params.n, "'n' must be positive");
+    assert!(0 < params.batches, "'batches' must be positive");
+    assert!(
+        0. < params.level && params.level < 1.,
+        "'level' must be a single number between 0 and 1"
+    );
+    let mut master = StdRng::seed_from_u64(params.seed);
+    let seeds: Vec<u64> = (0..params.batches).map(|_| master.r#gen()).collect();
+    let start = |b: usize| b * params.n / params.batches;
+    let run = |b: usize| work(&mut StdRng::seed_from_u64(seeds[b]), start(b)..start(b + 1));
+    if params.batches == 1 {
+        return run(0);
+    }
+    let results: Vec<A> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..params.batches)
+            .map(|b| scope.spawn(move || run(b)))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    results.into_iter().reduce(A::merge).unwrap()
+}
+
+/// Welford's running mean and sum of squared deviations.
+#[derive(Debug, Clone, Copy, Default)]
+struct Moments {
+    n: usize,
+    mean: f64,
+    m2: f64,
+}
+impl Moments {
+    fn push(&mut self, x: f64) {
+        self.n += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.n as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+    fn std_error(&self) -> f64 {
+        if self.n < 2 {
+            return f64::NAN;
+        }
+        (self.m2 / (self.n - 1) as f64 / self.n as f64).sqrt()
+    }
+}
+/// ref: <https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance#Parallel_algorithm>
+impl Merge for Moments {
+    fn merge(self, other: Self) -> Self {
+        if other.n == 0 {
+            return self;
+        }
+        let n = self.n + other.n;
+        let delta = other.mean - self.mean;
+        let w = other.n as f64 / n as f64;
+        Self {
+            n,
+            mean: self.mean + delta * w,
+            m2: self.m2 + other.m2 + delta * delta * self.n as f64 * w,
+        }
+    }
+}
+impl Merge for Vec<Moments> {
+    fn merge(self, other: Self) -> Self {
+        self.into_iter()
+            .zip(other)
+            .map(|(a, b)| a.merge(b))
+            .collect()
+    }
+}
+/// Running means, sums of squared deviations and co-deviation of pairs `(x, y)`.
+#[derive(Debug, Clone, Copy, Default)]
+struct CoMoments {
+    n: usize,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    c: f64,
+}
+impl CoMoments {
+    fn push(&mut self, x: f64, y: f64) {
+        self.n += 1;
+        let dx = x - self.mean_x;
+        self.mean_x += dx / self.n as f64;
+        let dy = y - self.mean_y;
+        self.mean_y += dy / self.n as f64;
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.c += dx * (y - self.mean_y);
+    }
+}
+impl Merge for CoMoments {
+    fn merge(self, other: Self) -> Self {
+        if other.n == 0 {
+            return self;
+        }
+        let n = self.n + other.n;
+        let w = other.n as f64 / n as f64;
+        let dx = other.mean_x - self.mean_x;
+        let dy = other.mean_y - self.mean_y;
+        let scale = self.n as f64 * w;
+        Self {
+            n,
+            mean_x: self.mean_x + dx * w,
+            mean_y: self.mean_y + dy * w,
+            m2_x: self.m2_x + other.m2_x + dx * dx * scale,
+            m2_y: self.m2_y + other.m2_y + dy * dy * scale,
+            c: self.c + other.c + dx * dy * scale,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::distributions::Distribution;
+
+    use super::*;
+
+    fn covers(e: &McEstimate, truth: f64) -> bool {
+        e.conf_int[0] <= truth && truth <= e.conf_int[1]
+    }
+
+    #[test]
+    fn test_variance_reduction() {
+        // ∫_0^1 e^x dx = e - 1
+        let truth = 1_f64.exp() - 1.;
+        let f = |x: &[f64]| x[0].exp();
+        let params = McParams::new(10_000);
+        let crude = crude(f, [0.], [1.], params);
+        let antithetic = antithetic(f, [0.], [1.], McParams::new(5_000));
+        let control = control_variate(f, |x| 1. + x[0], 1.5, [0.], [1.], params);
+        let stratified = stratified(f, [0.], [1.], 100, params);
+        // the density 2 (1 + x) / 3 roughly follows e^x
+        let importance = importance(
+            f,
+            |x| 2. * (1. + x[0]) / 3.,
+            |rng| vec![(1. + 3. * rng.r#gen::<f64>()).sqrt() - 1.],
+            params,
+        );
+        for e in [&crude, &antithetic, &control, &stratified, &importance] {
+            assert_eq!(e.n, 10_000);
+            assert!(covers(e, truth), "{e:?}");
+        }
+        // crude variance per draw is about 0.242
+        assert!((crude.std_error - (0.242_f64 / 10_000.).sqrt()).abs() < 1e-4);
+        for e in [&antithetic, &control, &stratified] {
+            assert!(e.std_error < crude.std_error / 5., "{e:?}");
+        }
+        assert!(importance.std_error < crude.std_error / 2.);
+    }
+
+    #[test]
+    fn test_batches() {
+        let normal = Normal::new(0., 1.).unwrap();
+        let draw = |rng: &mut StdRng| normal.sample(rng).powi(2);
+        let mut params = McParams::new(20_001);
+        params.seed = 1;
+        params.batches = 4;
+        let a = expectation(draw, params);
+        assert_eq!(a, expectation(draw, params));
+        assert_eq!(a.n, 20_001);
+        assert!(covers(&a, 1.));
+        params.batches = 1;
+        let b = expectation(draw, params);
+        assert_ne!(a.estimate, b.estimate);
+        assert!((a.std_error - b.std_error).abs() < 1e-3);
+
+        // 2-d stratification: ∫∫_[0,1]² x y = 1/4
+        let f = |x: &[f64]| x[0] * x[1];
+        params.batches = 3;
+        let e = stratified(f, [0., 0.], [1., 1.], 10, params);
+        assert!(covers(&e, 0.25));
+        let c = crude(f, [0., 0.], [1., 1.], params);
+        assert!(e.std_error < c.std_error / 5.);
+    }
+}
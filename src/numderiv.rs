@@ -0,0 +1,267 @@
+use crate::matrix::{FillOrdering, MatrixBuf, matrix};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiffMethod {
+    /// one central difference with a step balancing truncation against rounding error
+    Central,
+    /// central differences at shrinking steps combined by Richardson extrapolation
+    Richardson,
+}
+/// ref: <https://cran.r-project.org/web/packages/numDeriv/numDeriv.pdf>
+#[derive(Debug, Clone, Copy)]
+pub struct DiffParams {
+    pub method: DiffMethod,
+    /// Richardson: initial step relative to `|x|`
+    pub d: f64,
+    /// Richardson: initial step where `|x| < zero_tol`
+    pub eps: f64,
+    pub zero_tol: f64,
+    /// Richardson: number of steps
+    pub r: usize,
+    /// Richardson: step reduction factor
+    pub v: f64,
+}
+impl Default for DiffParams {
+    /// numDeriv's defaults: `d = 1e-4`, `eps = 1e-4`, `zero.tol = sqrt(.Machine$double.eps / 7e-7)`,
+    /// `r = 4`, `v = 2`.
+    fn default() -> Self {
+        Self {
+            method: DiffMethod::Richardson,
+            d: 1e-4,
+            eps: 1e-4,
+            zero_tol: (f64::EPSILON / 7e-7).sqrt(),
+            r: 4,
+            v: 2.,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<DiffParams> for DiffParams { fn as_ref(&self) -> &DiffParams { self } }
+
+#[derive(Debug, Clone)]
+pub struct Derivative<V> {
+    pub value: V,
+    /// estimated absolute error of `value`
+    pub error: V,
+}
+
+pub fn derivative(
+    f: impl Fn(f64) -> f64,
+    x: f64,
+    params: impl AsRef<DiffParams>,
+) -> Derivative<f64> {
+    let d = grad(|x| f(x[0]), [x], params);
+    Derivative {
+        value: d.value[0],
+        error: d.error[0],
+    }
+}
+pub fn grad(
+    f: impl Fn(&[f64]) -> f64,
+    x: impl AsRef<[f64]>,
+    params: impl AsRef<DiffParams>,
+) -> Derivative<Vec<f64>> {
+    let x = x.as_ref();
+    let jacobian = jacobian(|x| vec![f(x)], x, params);
+    Derivative {
+        value: jacobian.value.data().to_vec(),
+        error: jacobian.error.data().to_vec(),
+    }
+}
+/// One row per output of `f` and one column per input.
+pub fn jacobian(
+    f: impl Fn(&[f64]) -> Vec<f64>,
+    x: impl AsRef<[f64]>,
+    params: impl AsRef<DiffParams>,
+) -> Derivative<MatrixBuf<f64>> {
+    let params = params.as_ref();
+    let x = x.as_ref();
+    let n = x.len();
+    let mut m = 0;
+    let (value, error) = extrapolate(params, x, 1, |h| {
+        let mut columns = Vec::with_capacity(n);
+        let mut x_h = x.to_vec();
+        for j in 0..n {
+            x_h[j] = x[j] + h[j];
+            let up = f(&x_h);
+            x_h[j] = x[j] - h[j];
+            let down = f(&x_h);
+            x_h[j] = x[j];
+            m = up.len();
+            columns.push(
+                up.iter()
+                    .zip(&down)
+                    .map(|(u, d)| (u - d) / (2. * h[j]))
+                    .collect::<Vec<f64>>(),
+            );
+        }
+        (0..m * n).map(|k| columns[k % n][k / n]).collect()
+    });
+    Derivative {
+        value: matrix(value, [n, m], FillOrdering::RowByRow),
+        error: matrix(error, [n, m], FillOrdering::RowByRow),
+    }
+}
+pub fn hessian(
+    f: impl Fn(&[f64]) -> f64,
+    x: impl AsRef<[f64]>,
+    params: impl AsRef<DiffParams>,
+) -> Derivative<MatrixBuf<f64>> {
+    let params = params.as_ref();
+    let x = x.as_ref();
+    let n = x.len();
+    let f0 = f(x);
+    let (value, error) = extrapolate(params, x, 2, |h| {
+        let mut out = vec![0.; n * n];
+        let mut x_h = x.to_vec();
+        for i in 0..n {
+            x_h[i] = x[i] + h[i];
+            let up = f(&x_h);
+            x_h[i] = x[i] - h[i];
+            let down = f(&x_h);
+            out[i * n + i] = (up - 2. * f0 + down) / (h[i] * h[i]);
+            for j in 0..i {
+                let mut corner = |si: f64, sj: f64| {
+                    x_h[i] = x[i] + si * h[i];
+                    x_h[j] = x[j] + sj * h[j];
+                    f(&x_h)
+                };
+                let d = corner(1., 1.) - corner(1., -1.) - corner(-1., 1.) + corner(-1., -1.);
+                x_h[j] = x[j];
+                out[i * n + j] = d / (4. * h[i] * h[j]);
+                out[j * n + i] = out[i * n + j];
+            }
+            x_h[i] = x[i];
+        }
+        out
+    });
+    Derivative {
+        value: matrix(value, [n, n], FillOrdering::RowByRow),
+        error: matrix(error, [n, n], FillOrdering::RowByRow),
+    }
+}
+
+/// Evaluate the difference quotients `quotient(h)`, whose truncation error is `O(h²)`, and refine
+/// them into `(value, error)`.
+///
+/// ref: <https://github.com/cran/numDeriv/blob/master/R/numDeriv.R>
+fn extrapolate(
+    params: &DiffParams,
+    x: &[f64],
+    order: i32,
+    mut quotient: impl FnMut(&[f64]) -> Vec<f64>,
+) -> (Vec<f64>, Vec<f64>) {
+    match params.method {
+        DiffMethod::Central => {
+            // balance the `O(h²)` truncation error against the `O(ε / hᵒʳᵈᵉʳ)` rounding error
+            let scale = f64::EPSILON.powf(1. / (2. + order as f64));
+            let h: Vec<f64> = x.iter().map(|x| scale * x.abs().max(1.)).collect();
+            let a = quotient(&h);
+            let h2: Vec<f64> = h.iter().map(|h| 2. * h).collect();
+            let a2 = quotient(&h2);
+            let error = a
+                .iter()
+                .zip(&a2)
+                .map(|(a, a2)| (a - a2).abs() / 3.)
+                .collect();
+            (a, error)
+        }
+        DiffMethod::Richardson => {
+            assert!(1 < params.r && 1. < params.v);
+            let mut h: Vec<f64> = x
+                .iter()
+                .map(|x| {
+                    let eps = if x.abs() < params.zero_tol {
+                        params.eps
+                    } else {
+                        0.
+                    };
+                    (params.d * x).abs() + eps
+                })
+                .collect();
+            let mut table = Vec::with_capacity(params.r);
+            for _ in 0..params.r {
+                table.push(quotient(&h));
+                h.iter_mut().for_each(|h| *h /= params.v);
+            }
+            // `table[k]` becomes the `m`-th extrapolation from steps `k..=k + m`
+            let mut previous = table[params.r - 2].clone();
+            for m in 1..params.r {
+                let factor = params.v.powi(2 * m as i32);
+                for k in 0..params.r - m {
+                    let refined = table[k + 1]
+                        .iter()
+                        .zip(&table[k])
+                        .map(|(a1, a0)| (factor * a1 - a0) / (factor - 1.))
+                        .collect();
+                    table[k] = refined;
+                }
+                if m == params.r - 2 {
+                    previous = table[0].clone();
+                }
+            }
+            let value = table.swap_remove(0);
+            let error = value
+                .iter()
+                .zip(&previous)
+                .map(|(a, b)| (a - b).abs())
+                .collect();
+            (value, error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matrix::entry;
+
+    use super::*;
+
+    #[test]
+    fn test_derivative() {
+        let d = derivative(f64::sin, 1., DiffParams::default());
+        assert!((d.value - 1_f64.cos()).abs() < 1e-10);
+        assert!(d.error < 1e-8);
+        let params = DiffParams {
+            method: DiffMethod::Central,
+            ..Default::default()
+        };
+        let d = derivative(f64::exp, 0., params);
+        assert!((d.value - 1.).abs() < 1e-9);
+        assert!((d.value - 1.).abs() <= 10. * d.error);
+        // R: numDeriv::grad(function(x) sum(sin(x)), 0:3)
+        let g = grad(
+            |x| x.iter().map(|x| x.sin()).sum(),
+            [0., 1., 2., 3.],
+            DiffParams::default(),
+        );
+        for (i, g) in g.value.iter().enumerate() {
+            assert!((g - (i as f64).cos()).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_jacobian_hessian() {
+        let f = |x: &[f64]| vec![x[0] * x[1], x[0].sin() + x[1] * x[1], x[1].exp()];
+        let j = jacobian(f, [1., 2.], DiffParams::default());
+        assert_eq!(j.value.dim(), [2, 3]);
+        let exact = [[2., 1.], [1_f64.cos(), 4.], [0., 2_f64.exp()]];
+        for (row, exact) in exact.iter().enumerate() {
+            for (col, exact) in exact.iter().enumerate() {
+                assert!((entry(&j.value, [col, row]) - exact).abs() < 1e-9);
+            }
+        }
+        let rosenbrock = |x: &[f64]| 100. * (x[1] - x[0] * x[0]).powi(2) + (1. - x[0]).powi(2);
+        let h = hessian(rosenbrock, [1., 1.], DiffParams::default());
+        assert!((entry(&h.value, [0, 0]) - 802.).abs() < 1e-6);
+        assert!((entry(&h.value, [1, 0]) + 400.).abs() < 1e-6);
+        assert_eq!(entry(&h.value, [1, 0]), entry(&h.value, [0, 1]));
+        assert!((entry(&h.value, [1, 1]) - 200.).abs() < 1e-6);
+        let params = DiffParams {
+            method: DiffMethod::Central,
+            ..Default::default()
+        };
+        let h = hessian(rosenbrock, [1., 1.], params);
+        assert!((entry(&h.value, [0, 0]) - 802.).abs() < 1e-2);
+    }
+}
@@ -0,0 +1,621 @@
+mod steppers;
+
+use std::cell::Cell;
+
+use crate::{
+    matrix::{FillOrdering, MatrixBuf, matrix},
+    warning::warning,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OdeMethod {
+    /// fixed-step explicit Euler
+    Euler,
+    /// fixed-step classical Runge-Kutta
+    Rk4,
+    /// adaptive Dormand-Prince 5(4) with dense output
+    Dopri5,
+    /// adaptive Kaps-Rentrop Rosenbrock 4(3) for stiff problems
+    Rosenbrock,
+    /// `Dopri5`, switching to and from `Rosenbrock` as the problem turns stiff, like `lsoda`
+    Lsoda,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventDirection {
+    Both,
+    /// the root function crosses zero from below
+    Rising,
+    /// the root function crosses zero from above
+    Falling,
+}
+pub type EventRoot = dyn Fn(f64, &[f64]) -> f64;
+pub type EventAction = dyn Fn(f64, &[f64]) -> Vec<f64>;
+/// Fires where `root(t, y)` crosses zero; deSolve's `rootfunc` with its `events`.
+pub struct Event {
+    pub root: Box<EventRoot>,
+    pub direction: EventDirection,
+    /// stop the integration at the event
+    pub terminal: bool,
+    /// replace the state at the event and restart from there
+    pub action: Option<Box<EventAction>>,
+}
+impl Event {
+    pub fn new(root: impl Fn(f64, &[f64]) -> f64 + 'static) -> Self {
+        Self {
+            root: Box::new(root),
+            direction: EventDirection::Both,
+            terminal: false,
+            action: None,
+        }
+    }
+}
+impl std::fmt::Debug for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Event")
+            .field("direction", &self.direction)
+            .field("terminal", &self.terminal)
+            .field("action", &self.action.is_some())
+            .finish()
+    }
+}
+
+/// ref: <https://cran.r-project.org/web/packages/deSolve/deSolve.pdf>
+#[derive(Debug)]
+pub struct OdeParams {
+    pub method: OdeMethod,
+    /// increasing output times; the first is the initial time
+    pub times: Vec<f64>,
+    pub rtol: f64,
+    pub atol: f64,
+    /// step of `Euler` and `Rk4`, which otherwise step from one output time to the next; first
+    /// trial step of the adaptive methods, which otherwise pick it
+    pub h_init: Option<f64>,
+    pub h_min: f64,
+    pub h_max: f64,
+    /// accepted steps over the whole integration
+    pub max_steps: usize,
+    pub events: Vec<Event>,
+}
+impl OdeParams {
+    /// deSolve's defaults: `rtol = 1e-6`, `atol = 1e-6`, `hmin = 0`, `hmax = Inf`.
+    pub fn new(method: OdeMethod, times: impl AsRef<[f64]>) -> Self {
+        Self {
+            method,
+            times: times.as_ref().to_vec(),
+            rtol: 1e-6,
+            atol: 1e-6,
+            h_init: None,
+            h_min: 0.,
+            h_max: f64::INFINITY,
+            max_steps: 100_000,
+            events: vec![],
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<OdeParams> for OdeParams { fn as_ref(&self) -> &OdeParams { self } }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OdeStatus {
+    Success,
+    /// stopped by a terminal event
+    Terminated,
+    MaxSteps,
+    StepSizeTooSmall,
+}
+#[derive(Debug, Clone)]
+pub struct EventHit {
+    /// index into [`OdeParams::events`]
+    pub event: usize,
+    pub time: f64,
+    /// state before any action
+    pub state: Vec<f64>,
+}
+#[derive(Debug, Clone)]
+pub struct OdeSolution {
+    /// one row per output time, holding the time followed by the state; a terminal event adds a
+    /// last row at the event
+    pub out: MatrixBuf<f64>,
+    pub events: Vec<EventHit>,
+    pub status: OdeStatus,
+    /// accepted steps
+    pub steps: usize,
+    pub rejected: usize,
+    pub fn_evals: usize,
+    /// accepted steps taken by the stiff method
+    pub stiff_steps: usize,
+}
+
+type Rhs<'a> = dyn Fn(f64, &[f64]) -> Vec<f64> + 'a;
+
+/// Continuous extension of a step.
+enum Dense {
+    /// cubic Hermite through the end points and their slopes
+    Hermite { f0: Vec<f64>, f1: Vec<f64> },
+    /// Dormand-Prince coefficients `rcont1..=rcont5`
+    Dopri([Vec<f64>; 5]),
+}
+struct Step {
+    t0: f64,
+    t1: f64,
+    y1: Vec<f64>,
+    f1: Vec<f64>,
+    dense: Dense,
+}
+impl Step {
+    fn at(&self, t: f64, y0: &[f64]) -> Vec<f64> {
+        if t == self.t1 {
+            return self.y1.clone();
+        }
+        let h = self.t1 - self.t0;
+        let s = (t - self.t0) / h;
+        let s1 = 1. - s;
+        match &self.dense {
+            Dense::Hermite { f0, f1 } => (0..y0.len())
+                .map(|i| {
+                    let dy = self.y1[i] - y0[i];
+                    s1 * y0[i]
+                        + s * self.y1[i]
+                        + s * (s - 1.) * ((1. - 2. * s) * dy + (s - 1.) * h * f0[i] + s * h * f1[i])
+                })
+                .collect(),
+            Dense::Dopri([r1, r2, r3, r4, r5]) => (0..y0.len())
+                .map(|i| r1[i] + s * (r2[i] + s1 * (r3[i] + s * (r4[i] + s1 * r5[i]))))
+                .collect(),
+        }
+    }
+}
+
+/// Solve `dy/dt = f(t, y)` from `y(times[0]) = y0`, reporting the state at each of `times`.
+///
+/// ref: <https://www.unige.ch/~hairer/books.html>
+pub fn ode(
+    f: impl Fn(f64, &[f64]) -> Vec<f64>,
+    y0: impl AsRef<[f64]>,
+    params: impl AsRef<OdeParams>,
+) -> OdeSolution {
+    let params = params.as_ref();
+    let times = &params.times;
+    assert!(!times.is_empty(), "'times' must not be empty");
+    assert!(
+        times.windows(2).all(|w| w[0] < w[1]),
+        "'times' must be increasing"
+    );
+    let fn_evals = Cell::new(0);
+    let f = |t: f64, y: &[f64]| {
+        fn_evals.set(fn_evals.get() + 1);
+        let dy = f(t, y);
+        assert_eq!(
+            dy.len(),
+            y.len(),
+            "the number of derivatives must equal the length of the initial conditions vector"
+        );
+        dy
+    };
+    let method = params.method;
+    let adaptive = !matches!(method, OdeMethod::Euler | OdeMethod::Rk4);
+    let events = &params.events;
+    let t_end = *times.last().unwrap();
+
+    let mut t = times[0];
+    let mut y = y0.as_ref().to_vec();
+    let mut f0 = f(t, &y);
+    let mut data = vec![t];
+    data.extend(&y);
+    let mut next_out = 1;
+    let mut g: Vec<f64> = events.iter().map(|e| (e.root)(t, &y)).collect();
+    let mut hits = vec![];
+    let mut status = OdeStatus::Success;
+    let (mut steps, mut rejected, mut stiff_steps) = (0, 0, 0);
+    let mut stiff = method == OdeMethod::Rosenbrock;
+    // consecutive accepted steps that look stiff, or non-stiff, to the current method
+    let (mut stiff_run, mut nonstiff_run) = (0, 0);
+    let mut h = match (adaptive, params.h_init) {
+        (true, Some(h)) => h,
+        (true, None) => {
+            let order = if stiff { 4 } else { 5 };
+            initial_step(&f, t, &y, &f0, order, params)
+        }
+        (false, _) => 0.,
+    };
+
+    'integrate: while next_out < times.len() {
+        if params.max_steps <= steps {
+            warning(format!(
+                "an excessive amount of work (> maxsteps = {}) was done, but integration was not successful - increase maxsteps",
+                params.max_steps
+            ));
+            status = OdeStatus::MaxSteps;
+            break;
+        }
+        let step_stiff = stiff;
+        let step = if !adaptive {
+            let target = times[next_out];
+            let mut h = params.h_init.unwrap_or(target - t).min(target - t);
+            // land on the output time rather than leave a sliver for the next step
+            if target - (t + h) <= 1e-10 * h {
+                h = target - t;
+            }
+            let t1 = if h == target - t { target } else { t + h };
+            let y1 = match method {
+                OdeMethod::Euler => steppers::euler(&y, &f0, h),
+                _ => steppers::rk4(&f, t, &y, &f0, h),
+            };
+            let f1 = f(t1, &y1);
+            Step {
+                t0: t,
+                t1,
+                y1,
+                dense: Dense::Hermite {
+                    f0: f0.clone(),
+                    f1: f1.clone(),
+                },
+                f1,
+            }
+        } else {
+            let mut jacobian = None;
+            let mut retried = false;
+            loop {
+                h = h.min(params.h_max).min(t_end - t);
+                if h < params.h_min.max(16. * f64::EPSILON * t.abs()) {
+                    warning(format!("step size too small at t = {t}"));
+                    status = OdeStatus::StepSizeTooSmall;
+                    break 'integrate;
+                }
+                if stiff {
+                    let jacobian =
+                        jacobian.get_or_insert_with(|| steppers::jacobian(&f, t, &y, &f0));
+                    let attempt =
+                        steppers::rosenbrock(&f, t, &y, &f0, h, jacobian, params.rtol, params.atol);
+                    let error = attempt.as_ref().map_or(f64::INFINITY, |a| a.error);
+                    let Some(attempt) = attempt.filter(|_| error <= 1.) else {
+                        rejected += 1;
+                        retried = true;
+                        h *= (0.9 * error.powf(-1. / 3.)).clamp(0.1, 0.5);
+                        continue;
+                    };
+                    let t1 = t + h;
+                    let f1 = f(t1, &attempt.y1);
+                    if method == OdeMethod::Lsoda {
+                        let rho = jacobian
+                            .0
+                            .row_iter()
+                            .map(|row| row.iter().map(|x| x.abs()).sum::<f64>())
+                            .fold(0., f64::max);
+                        if h * rho <= 3.25 {
+                            nonstiff_run += 1;
+                            if nonstiff_run == 15 {
+                                (stiff, nonstiff_run, stiff_run) = (false, 0, 0);
+                            }
+                        } else {
+                            nonstiff_run = 0;
+                        }
+                    }
+                    let grow = if retried { 1. } else { 5. };
+                    let h_next = h * (0.9 * attempt.error.powf(-0.25)).min(grow);
+                    let step = Step {
+                        t0: t,
+                        t1,
+                        dense: Dense::Hermite {
+                            f0: f0.clone(),
+                            f1: f1.clone(),
+                        },
+                        y1: attempt.y1,
+                        f1,
+                    };
+                    h = h_next;
+                    break step;
+                }
+                let attempt = steppers::dopri5(&f, t, &y, &f0, h, params.rtol, params.atol);
+                if attempt.error.is_nan() || 1. < attempt.error {
+                    rejected += 1;
+                    retried = true;
+                    let shrink = 0.9 * attempt.error.powf(-0.2);
+                    h *= if shrink.is_nan() {
+                        0.2
+                    } else {
+                        shrink.max(0.2)
+                    };
+                    continue;
+                }
+                if method == OdeMethod::Lsoda {
+                    // Hairer's stiffness detection in dopri5
+                    if 3.25 < attempt.h_lambda {
+                        nonstiff_run = 0;
+                        stiff_run += 1;
+                        if stiff_run == 15 {
+                            (stiff, nonstiff_run, stiff_run) = (true, 0, 0);
+                        }
+                    } else {
+                        nonstiff_run += 1;
+                        if nonstiff_run == 6 {
+                            stiff_run = 0;
+                        }
+                    }
+                }
+                let grow = if retried { 1. } else { 10. };
+                let h_next = h * (0.9 * attempt.error.powf(-0.2)).clamp(0.2, grow);
+                let step = Step {
+                    t0: t,
+                    t1: t + h,
+                    y1: attempt.y1,
+                    f1: attempt.f1,
+                    dense: attempt.dense,
+                };
+                h = h_next;
+                break step;
+            }
+        };
+        steps += 1;
+        if step_stiff {
+            stiff_steps += 1;
+        }
+
+        // zero crossings of the root functions, earliest first
+        let g1: Vec<f64> = events.iter().map(|e| (e.root)(step.t1, &step.y1)).collect();
+        let mut crossings: Vec<(usize, f64)> = vec![];
+        for (k, event) in events.iter().enumerate() {
+            let root = |t: f64| (event.root)(t, &step.at(t, &y));
+            // sample inside the step too, so a long step cannot hide a crossing and back, and a
+            // root that starts at zero after an action takes the sign it leaves with
+            let (mut a, mut g_a) = (step.t0, g[k]);
+            for j in 1..=EVENT_SAMPLES {
+                let b = if j == EVENT_SAMPLES {
+                    step.t1
+                } else {
+                    step.t0 + (step.t1 - step.t0) * j as f64 / EVENT_SAMPLES as f64
+                };
+                let g_b = if j == EVENT_SAMPLES { g1[k] } else { root(b) };
+                if g_a != 0. && crosses(event.direction, g_a, g_b) {
+                    crossings.push((k, locate(root, a, b, g_a)));
+                    break;
+                }
+                (a, g_a) = (b, g_b);
+            }
+        }
+        crossings.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let mut stop = None;
+        for (k, time) in crossings {
+            let state = step.at(time, &y);
+            hits.push(EventHit {
+                event: k,
+                time,
+                state: state.clone(),
+            });
+            if events[k].terminal || events[k].action.is_some() {
+                stop = Some((k, time, state));
+                break;
+            }
+        }
+
+        let t_stop = stop.as_ref().map_or(step.t1, |(_, time, _)| *time);
+        while next_out < times.len()
+            && (times[next_out] < t_stop || stop.is_none() && times[next_out] == t_stop)
+        {
+            data.push(times[next_out]);
+            data.extend(step.at(times[next_out], &y));
+            next_out += 1;
+        }
+        if let Some((k, time, state)) = stop {
+            if events[k].terminal {
+                data.push(time);
+                data.extend(&state);
+                status = OdeStatus::Terminated;
+                break;
+            }
+            let action = events[k].action.as_ref().unwrap();
+            t = time;
+            y = action(time, &state);
+            f0 = f(t, &y);
+            g = events.iter().map(|e| (e.root)(t, &y)).collect();
+            // outputs at the event itself show the state after the action
+            while next_out < times.len() && times[next_out] == t {
+                data.push(t);
+                data.extend(&y);
+                next_out += 1;
+            }
+            continue;
+        }
+        t = step.t1;
+        y = step.y1;
+        f0 = step.f1;
+        g = g1;
+    }
+
+    let n_rows = data.len() / (y.len() + 1);
+    OdeSolution {
+        out: matrix(data, [y.len() + 1, n_rows], FillOrdering::RowByRow),
+        events: hits,
+        status,
+        steps,
+        rejected,
+        fn_evals: fn_evals.get(),
+        stiff_steps,
+    }
+}
+
+/// points per step at which the root functions are checked for a sign change
+const EVENT_SAMPLES: usize = 4;
+fn crosses(direction: EventDirection, g0: f64, g1: f64) -> bool {
+    let rising = g0 < 0. && 0. <= g1;
+    let falling = 0. < g0 && g1 <= 0.;
+    match direction {
+        EventDirection::Both => rising || falling,
+        EventDirection::Rising => rising,
+        EventDirection::Falling => falling,
+    }
+}
+/// Bisect for the crossing of `g` in `(a, b]`, returning a time just past it.
+fn locate(g: impl Fn(f64) -> f64, mut a: f64, mut b: f64, g_a: f64) -> f64 {
+    let negative = g_a < 0.;
+    for _ in 0..100 {
+        if b - a <= 4. * f64::EPSILON * a.abs().max(b.abs()) {
+            break;
+        }
+        let m = a + (b - a) / 2.;
+        let g_m = g(m);
+        if (g_m < 0.) != negative || g_m == 0. {
+            b = m;
+        } else {
+            a = m;
+        }
+    }
+    b
+}
+/// Hairer's starting step: a rough `h` for which an explicit Euler step stays accurate.
+fn initial_step(f: &Rhs, t: f64, y: &[f64], f0: &[f64], order: i32, params: &OdeParams) -> f64 {
+    let scale: Vec<f64> = y
+        .iter()
+        .map(|y| params.atol + params.rtol * y.abs())
+        .collect();
+    let norm = |v: &[f64]| {
+        let sum: f64 = v.iter().zip(&scale).map(|(v, s)| (v / s).powi(2)).sum();
+        (sum / v.len().max(1) as f64).sqrt()
+    };
+    let (d0, d1) = (norm(y), norm(f0));
+    let h0 = if d0 < 1e-5 || d1 < 1e-5 {
+        1e-6
+    } else {
+        0.01 * d0 / d1
+    };
+    let h0 = h0.min(params.h_max);
+    let y1: Vec<f64> = y.iter().zip(f0).map(|(y, f)| y + h0 * f).collect();
+    let f1 = f(t + h0, &y1);
+    let df: Vec<f64> = f1.iter().zip(f0).map(|(a, b)| a - b).collect();
+    let d2 = norm(&df) / h0;
+    let h1 = if d1.max(d2) <= 1e-15 {
+        (h0 * 1e-3).max(1e-6)
+    } else {
+        (0.01 / d1.max(d2)).powf(1. / (order as f64 + 1.))
+    };
+    (100. * h0).min(h1).min(params.h_max)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matrix::{column, entry};
+
+    use super::*;
+
+    fn times(end: f64, n: usize) -> Vec<f64> {
+        (0..=n).map(|i| end * i as f64 / n as f64).collect()
+    }
+
+    #[test]
+    fn test_fixed_step() {
+        let decay = |_: f64, y: &[f64]| vec![-y[0]];
+        let mut params = OdeParams::new(OdeMethod::Euler, times(1., 10));
+        params.h_init = Some(1e-4);
+        let s = ode(decay, [1.], &params);
+        assert_eq!(s.out.dim(), [2, 11]);
+        assert_eq!(column(&s.out, 0), times(1., 10));
+        assert!((entry(&s.out, [1, 10]) - (-1_f64).exp()).abs() < 1e-4);
+        params.method = OdeMethod::Rk4;
+        params.h_init = None;
+        let s = ode(decay, [1.], &params);
+        assert_eq!(s.steps, 10);
+        for (t, y) in column(&s.out, 0).iter().zip(column(&s.out, 1)) {
+            assert!((y - (-t).exp()).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_dopri5() {
+        let oscillator = |_: f64, y: &[f64]| vec![y[1], -y[0]];
+        let mut params = OdeParams::new(OdeMethod::Dopri5, times(10., 100));
+        params.rtol = 1e-9;
+        params.atol = 1e-9;
+        let s = ode(oscillator, [1., 0.], &params);
+        assert_eq!(s.status, OdeStatus::Success);
+        assert_eq!(s.out.dim(), [3, 101]);
+        // outputs come from dense output, so they do not shorten the steps
+        params.times = vec![0., 10.];
+        assert_eq!(ode(oscillator, [1., 0.], &params).steps, s.steps);
+        for row in 0..=100 {
+            let t = entry(&s.out, [0, row]);
+            assert!((entry(&s.out, [1, row]) - t.cos()).abs() < 1e-7);
+            assert!((entry(&s.out, [2, row]) + t.sin()).abs() < 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_stiff() {
+        // van der Pol with mu = 100
+        let vdp = |_: f64, y: &[f64]| vec![y[1], 100. * (1. - y[0] * y[0]) * y[1] - y[0]];
+        let run = |method| ode(vdp, [2., 0.], OdeParams::new(method, [0., 200.]));
+        let explicit = run(OdeMethod::Dopri5);
+        let rosenbrock = run(OdeMethod::Rosenbrock);
+        let lsoda = run(OdeMethod::Lsoda);
+        assert_eq!(explicit.stiff_steps, 0);
+        assert!(rosenbrock.steps < explicit.steps / 10);
+        assert_eq!(rosenbrock.stiff_steps, rosenbrock.steps);
+        assert!(0 < lsoda.stiff_steps && lsoda.stiff_steps < lsoda.steps);
+        assert!(lsoda.steps < explicit.steps / 10);
+        for s in [&rosenbrock, &lsoda] {
+            assert!((entry(&s.out, [1, 1]) - entry(&explicit.out, [1, 1])).abs() < 1e-4);
+        }
+        // Robertson's chemical kinetics
+        let robertson = |_: f64, y: &[f64]| {
+            vec![
+                -0.04 * y[0] + 1e4 * y[1] * y[2],
+                0.04 * y[0] - 1e4 * y[1] * y[2] - 3e7 * y[1] * y[1],
+                3e7 * y[1] * y[1],
+            ]
+        };
+        let mut params = OdeParams::new(OdeMethod::Lsoda, [0., 40.]);
+        params.atol = 1e-10;
+        let s = ode(robertson, [1., 0., 0.], &params);
+        assert_eq!(s.status, OdeStatus::Success);
+        // Hairer & Wanner, Solving ODEs II, reference values at t = 40
+        assert!((entry(&s.out, [1, 1]) - 0.7158).abs() < 1e-3);
+        assert!((entry(&s.out, [3, 1]) - 0.2842).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_events() {
+        // a ball dropped from 10 m
+        let fall = |_: f64, y: &[f64]| vec![y[1], -9.81];
+        let mut params = OdeParams::new(OdeMethod::Dopri5, times(5., 50));
+        params.events.push(Event {
+            direction: EventDirection::Falling,
+            terminal: true,
+            ..Event::new(|_, y| y[0])
+        });
+        let s = ode(fall, [10., 0.], &params);
+        let landing = (2. * 10. / 9.81_f64).sqrt();
+        assert_eq!(s.status, OdeStatus::Terminated);
+        assert_eq!(s.events.len(), 1);
+        assert!((s.events[0].time - landing).abs() < 1e-9);
+        let rows = s.out.dim()[1];
+        assert_eq!(rows, 16);
+        assert_eq!(entry(&s.out, [0, rows - 1]), s.events[0].time);
+        assert!(entry(&s.out, [1, rows - 1]).abs() < 1e-8);
+
+        // bouncing with a restitution of 0.9
+        params.events[0].terminal = false;
+        params.events[0].action = Some(Box::new(|_, y| vec![0., -0.9 * y[1]]));
+        let s = ode(fall, [10., 0.], &params);
+        assert_eq!(s.status, OdeStatus::Success);
+        assert_eq!(s.out.dim()[1], 51);
+        // flight times shrink geometrically: landing * (1 + 2 * 0.9 + 2 * 0.9^2 + ...)
+        let mut expected = landing;
+        for (i, hit) in s.events.iter().enumerate() {
+            assert!((hit.time - expected).abs() < 1e-8);
+            expected += 2. * landing * 0.9_f64.powi(i as i32 + 1);
+        }
+        assert_eq!(s.events.len(), 2);
+        assert!(column(&s.out, 1).iter().all(|&h| -1e-8 < h));
+
+        // an action at the last output time still finishes the output
+        let mut params = OdeParams::new(OdeMethod::Dopri5, [0., 0.5, 1.]);
+        params.events.push(Event {
+            action: Some(Box::new(|_, y| y.to_vec())),
+            ..Event::new(|_, y| y[0] - 1.)
+        });
+        let s = ode(|_, _| vec![1.], [0.], &params);
+        assert_eq!(s.status, OdeStatus::Success);
+        assert_eq!(s.out.dim(), [2, 3]);
+        assert_eq!(column(&s.out, 0), [0., 0.5, 1.]);
+        assert!((entry(&s.out, [1, 2]) - 1.).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,270 @@
+use nalgebra::{DMatrix, DVector};
+
+use super::{Dense, Rhs};
+
+/// `y + h Σ cᵢ kᵢ`
+fn combine(y: &[f64], h: f64, terms: &[(f64, &[f64])]) -> Vec<f64> {
+    let mut out = y.to_vec();
+    for &(c, k) in terms {
+        if c != 0. {
+            for (o, k) in out.iter_mut().zip(k) {
+                *o += h * c * k;
+            }
+        }
+    }
+    out
+}
+/// Hairer's scaled RMS norm of `err` against `atol + rtol * max(|y0|, |y1|)`.
+pub(super) fn error_norm(err: &[f64], y0: &[f64], y1: &[f64], rtol: f64, atol: f64) -> f64 {
+    if err.is_empty() {
+        return 0.;
+    }
+    let sum: f64 = err
+        .iter()
+        .zip(y0.iter().zip(y1))
+        .map(|(e, (a, b))| {
+            let sc = atol + rtol * a.abs().max(b.abs());
+            (e / sc).powi(2)
+        })
+        .sum();
+    (sum / err.len() as f64).sqrt()
+}
+
+pub(super) fn euler(y: &[f64], f0: &[f64], h: f64) -> Vec<f64> {
+    combine(y, h, &[(1., f0)])
+}
+pub(super) fn rk4(f: &Rhs, t: f64, y: &[f64], f0: &[f64], h: f64) -> Vec<f64> {
+    let k2 = f(t + h / 2., &combine(y, h, &[(0.5, f0)]));
+    let k3 = f(t + h / 2., &combine(y, h, &[(0.5, &k2)]));
+    let k4 = f(t + h, &combine(y, h, &[(1., &k3)]));
+    combine(
+        y,
+        h,
+        &[
+            (1. / 6., f0),
+            (1. / 3., &k2),
+            (1. / 3., &k3),
+            (1. / 6., &k4),
+        ],
+    )
+}
+
+pub(super) struct DopriStep {
+    pub y1: Vec<f64>,
+    pub f1: Vec<f64>,
+    pub error: f64,
+    /// estimate of `h λ` for the dominant eigenvalue `λ`
+    pub h_lambda: f64,
+    pub dense: Dense,
+}
+/// One Dormand-Prince 5(4) step with its continuous extension; `f0` is reused first-same-as-last.
+///
+/// ref: <http://www.unige.ch/~hairer/prog/nonstiff/dopri5.f>
+#[allow(clippy::excessive_precision)]
+pub(super) fn dopri5(
+    f: &Rhs,
+    t: f64,
+    y: &[f64],
+    f0: &[f64],
+    h: f64,
+    rtol: f64,
+    atol: f64,
+) -> DopriStep {
+    const C2: f64 = 0.2;
+    const C3: f64 = 0.3;
+    const C4: f64 = 0.8;
+    const C5: f64 = 8. / 9.;
+    const A21: f64 = 0.2;
+    const A31: f64 = 3. / 40.;
+    const A32: f64 = 9. / 40.;
+    const A41: f64 = 44. / 45.;
+    const A42: f64 = -56. / 15.;
+    const A43: f64 = 32. / 9.;
+    const A51: f64 = 19372. / 6561.;
+    const A52: f64 = -25360. / 2187.;
+    const A53: f64 = 64448. / 6561.;
+    const A54: f64 = -212. / 729.;
+    const A61: f64 = 9017. / 3168.;
+    const A62: f64 = -355. / 33.;
+    const A63: f64 = 46732. / 5247.;
+    const A64: f64 = 49. / 176.;
+    const A65: f64 = -5103. / 18656.;
+    const A71: f64 = 35. / 384.;
+    const A73: f64 = 500. / 1113.;
+    const A74: f64 = 125. / 192.;
+    const A75: f64 = -2187. / 6784.;
+    const A76: f64 = 11. / 84.;
+    const E1: f64 = 71. / 57600.;
+    const E3: f64 = -71. / 16695.;
+    const E4: f64 = 71. / 1920.;
+    const E5: f64 = -17253. / 339200.;
+    const E6: f64 = 22. / 525.;
+    const E7: f64 = -1. / 40.;
+    const D1: f64 = -12715105075. / 11282082432.;
+    const D3: f64 = 87487479700. / 32700410799.;
+    const D4: f64 = -10690763975. / 1880347072.;
+    const D5: f64 = 701980252875. / 199316789632.;
+    const D6: f64 = -1453857185. / 822651844.;
+    const D7: f64 = 69997945. / 29380423.;
+
+    let k1 = f0;
+    let k2 = f(t + C2 * h, &combine(y, h, &[(A21, k1)]));
+    let k3 = f(t + C3 * h, &combine(y, h, &[(A31, k1), (A32, &k2)]));
+    let k4 = f(
+        t + C4 * h,
+        &combine(y, h, &[(A41, k1), (A42, &k2), (A43, &k3)]),
+    );
+    let k5 = f(
+        t + C5 * h,
+        &combine(y, h, &[(A51, k1), (A52, &k2), (A53, &k3), (A54, &k4)]),
+    );
+    let y_stiff = combine(
+        y,
+        h,
+        &[(A61, k1), (A62, &k2), (A63, &k3), (A64, &k4), (A65, &k5)],
+    );
+    let k6 = f(t + h, &y_stiff);
+    let y1 = combine(
+        y,
+        h,
+        &[(A71, k1), (A73, &k3), (A74, &k4), (A75, &k5), (A76, &k6)],
+    );
+    let k7 = f(t + h, &y1);
+    let err = combine(
+        &vec![0.; y.len()],
+        h,
+        &[
+            (E1, k1),
+            (E3, &k3),
+            (E4, &k4),
+            (E5, &k5),
+            (E6, &k6),
+            (E7, &k7),
+        ],
+    );
+    let error = error_norm(&err, y, &y1, rtol, atol);
+
+    let numerator: f64 = k7.iter().zip(&k6).map(|(a, b)| (a - b).powi(2)).sum();
+    let denominator: f64 = y1.iter().zip(&y_stiff).map(|(a, b)| (a - b).powi(2)).sum();
+    let h_lambda = if 0. < denominator {
+        h.abs() * (numerator / denominator).sqrt()
+    } else {
+        0.
+    };
+
+    let ydiff: Vec<f64> = y1.iter().zip(y).map(|(a, b)| a - b).collect();
+    let bspl: Vec<f64> = (0..y.len()).map(|i| h * k1[i] - ydiff[i]).collect();
+    let r4: Vec<f64> = (0..y.len())
+        .map(|i| ydiff[i] - h * k7[i] - bspl[i])
+        .collect();
+    let r5 = combine(
+        &vec![0.; y.len()],
+        h,
+        &[
+            (D1, k1),
+            (D3, &k3),
+            (D4, &k4),
+            (D5, &k5),
+            (D6, &k6),
+            (D7, &k7),
+        ],
+    );
+    DopriStep {
+        dense: Dense::Dopri([y.to_vec(), ydiff, bspl, r4, r5]),
+        y1,
+        f1: k7,
+        error,
+        h_lambda,
+    }
+}
+
+/// `∂f/∂y` and `∂f/∂t` by forward differences.
+pub(super) fn jacobian(f: &Rhs, t: f64, y: &[f64], f0: &[f64]) -> (DMatrix<f64>, DVector<f64>) {
+    let n = y.len();
+    let delta = f64::EPSILON.sqrt();
+    let mut jac = DMatrix::zeros(n, n);
+    let mut y_h = y.to_vec();
+    for j in 0..n {
+        let h = delta * y[j].abs().max(1.);
+        y_h[j] = y[j] + h;
+        let f_h = f(t, &y_h);
+        y_h[j] = y[j];
+        for i in 0..n {
+            jac[(i, j)] = (f_h[i] - f0[i]) / h;
+        }
+    }
+    let h = delta * t.abs().max(1.);
+    let f_t = f(t + h, y);
+    let dt = DVector::from_iterator(n, f_t.iter().zip(f0).map(|(a, b)| (a - b) / h));
+    (jac, dt)
+}
+
+pub(super) struct RosenbrockStep {
+    pub y1: Vec<f64>,
+    pub error: f64,
+}
+/// One step of the Kaps-Rentrop 4(3) Rosenbrock pair; `None` when
+/// `I / (γ h) - J` is singular.
+///
+/// ref: Press et al., Numerical Recipes in C, 2nd ed., §16.6
+#[allow(clippy::too_many_arguments)]
+pub(super) fn rosenbrock(
+    f: &Rhs,
+    t: f64,
+    y: &[f64],
+    f0: &[f64],
+    h: f64,
+    (jac, dt): &(DMatrix<f64>, DVector<f64>),
+    rtol: f64,
+    atol: f64,
+) -> Option<RosenbrockStep> {
+    const GAM: f64 = 0.231;
+    const A21: f64 = 2.;
+    const A31: f64 = 4.52470820736;
+    const A32: f64 = 4.16352878860;
+    const C21: f64 = -5.07167533877;
+    const C31: f64 = 6.02015272865;
+    const C32: f64 = 0.159750684673;
+    const C41: f64 = -1.856343618677;
+    const C42: f64 = -8.50538085819;
+    const C43: f64 = -2.08407513602;
+    const B1: f64 = 3.95750374663;
+    const B2: f64 = 4.62489238836;
+    const B3: f64 = 0.617477263873;
+    const B4: f64 = 1.282612945268;
+    const E1: f64 = -2.30215540292;
+    const E2: f64 = -3.07363448539;
+    const E3: f64 = 0.873280801802;
+    const E4: f64 = 1.282612945268;
+    const C1X: f64 = GAM;
+    const C2X: f64 = -0.396296677520e-1;
+    const C3X: f64 = 0.550778939579;
+    const C4X: f64 = -0.553509845700e-1;
+    const A2X: f64 = 0.462;
+    const A3X: f64 = 0.880208333333;
+
+    let n = y.len();
+    let a = (DMatrix::identity(n, n) / (GAM * h) - jac).lu();
+    let stage = |at: &[f64]| DVector::from_column_slice(at);
+    let g1 = a.solve(&(stage(f0) + dt * (h * C1X)))?;
+    let y2: Vec<f64> = (0..n).map(|i| y[i] + A21 * g1[i]).collect();
+    let f2 = stage(&f(t + A2X * h, &y2));
+    let g2 = a.solve(&(&f2 + dt * (h * C2X) + &g1 * (C21 / h)))?;
+    let y3: Vec<f64> = (0..n).map(|i| y[i] + A31 * g1[i] + A32 * g2[i]).collect();
+    let f3 = stage(&f(t + A3X * h, &y3));
+    let g3 = a.solve(&(&f3 + dt * (h * C3X) + (&g1 * C31 + &g2 * C32) / h))?;
+    let g4 = a.solve(&(&f3 + dt * (h * C4X) + (&g1 * C41 + &g2 * C42 + &g3 * C43) / h))?;
+    let y1: Vec<f64> = (0..n)
+        .map(|i| y[i] + B1 * g1[i] + B2 * g2[i] + B3 * g3[i] + B4 * g4[i])
+        .collect();
+    let err: Vec<f64> = (0..n)
+        .map(|i| E1 * g1[i] + E2 * g2[i] + E3 * g3[i] + E4 * g4[i])
+        .collect();
+    if !err.iter().chain(&y1).all(|e| e.is_finite()) {
+        return None;
+    }
+    Some(RosenbrockStep {
+        error: error_norm(&err, y, &y1, rtol, atol),
+        y1,
+    })
+}
@@ -0,0 +1,165 @@
+use super::{OptimControl, OptimStatus, Outcome, Problem};
+
+pub(super) const STEPREDN: f64 = 0.2;
+pub(super) const ACCTOL: f64 = 0.0001;
+pub(super) const RELTEST: f64 = 10.0;
+
+/// Variable metric method: BFGS updates of the inverse Hessian with a backtracking line search,
+/// restarting from the identity whenever the update breaks down.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/appl/optim.c#L245>
+pub(super) fn vmmin(
+    problem: &Problem,
+    mut b: Vec<f64>,
+    control: &OptimControl,
+    maxit: usize,
+) -> Outcome {
+    let n = b.len();
+    if maxit == 0 {
+        return Outcome {
+            value: problem.value(&b),
+            par: b,
+            status: OptimStatus::Converged,
+            fn_count: Some(0),
+            gr_count: Some(0),
+            message: None,
+        };
+    }
+    let (abstol, reltol) = (control.abstol, control.reltol);
+    let mut t = vec![0.; n];
+    let mut x = vec![0.; n];
+    let mut c = vec![0.; n];
+    // lower triangle of the inverse Hessian approximation
+    let mut bmat = vec![vec![0.; n]; n];
+    let mut f = problem.value(&b);
+    assert!(f.is_finite(), "initial value in 'vmmin' is not finite");
+    let mut fmin = f;
+    let mut funcount = 1;
+    let mut gradcount = 1;
+    let mut g = problem.gradient(&b);
+    let mut iter = 1;
+    let mut ilast = gradcount;
+    let mut count;
+    loop {
+        if ilast == gradcount {
+            for (i, row) in bmat.iter_mut().enumerate() {
+                row[..i].fill(0.);
+                row[i] = 1.;
+            }
+        }
+        x.copy_from_slice(&b);
+        c.copy_from_slice(&g);
+        let mut gradproj = 0.;
+        for i in 0..n {
+            let mut s = 0.;
+            for j in 0..=i {
+                s -= bmat[i][j] * g[j];
+            }
+            for j in i + 1..n {
+                s -= bmat[j][i] * g[j];
+            }
+            t[i] = s;
+            gradproj += s * g[i];
+        }
+        if gradproj < 0. {
+            // search direction is downhill
+            let mut steplength = 1.;
+            let mut accpoint = false;
+            loop {
+                count = 0;
+                for i in 0..n {
+                    b[i] = x[i] + steplength * t[i];
+                    if RELTEST + x[i] == RELTEST + b[i] {
+                        count += 1;
+                    }
+                }
+                if count < n {
+                    f = problem.value(&b);
+                    funcount += 1;
+                    accpoint = f.is_finite() && f <= fmin + gradproj * steplength * ACCTOL;
+                    if !accpoint {
+                        steplength *= STEPREDN;
+                    }
+                }
+                if count == n || accpoint {
+                    break;
+                }
+            }
+            // stop if the value is small or the relative change is low
+            let enough = abstol < f && reltol * (fmin.abs() + reltol) < (f - fmin).abs();
+            if !enough {
+                count = n;
+                fmin = f;
+            }
+            if count < n {
+                // making progress
+                fmin = f;
+                g = problem.gradient(&b);
+                gradcount += 1;
+                iter += 1;
+                let mut d1 = 0.;
+                for i in 0..n {
+                    t[i] *= steplength;
+                    c[i] = g[i] - c[i];
+                    d1 += t[i] * c[i];
+                }
+                if 0. < d1 {
+                    let mut d2 = 0.;
+                    for i in 0..n {
+                        let mut s = 0.;
+                        for j in 0..=i {
+                            s += bmat[i][j] * c[j];
+                        }
+                        for j in i + 1..n {
+                            s += bmat[j][i] * c[j];
+                        }
+                        x[i] = s;
+                        d2 += s * c[i];
+                    }
+                    d2 = 1. + d2 / d1;
+                    for i in 0..n {
+                        for j in 0..=i {
+                            bmat[i][j] += (d2 * t[i] * t[j] - x[i] * t[j] - t[i] * x[j]) / d1;
+                        }
+                    }
+                } else {
+                    ilast = gradcount;
+                }
+            } else if ilast < gradcount {
+                // no progress: retry from a reset
+                count = 0;
+                ilast = gradcount;
+            }
+        } else {
+            // uphill search: reset unless that has just been done
+            count = 0;
+            if ilast == gradcount {
+                count = n;
+            } else {
+                ilast = gradcount;
+            }
+        }
+        if maxit <= iter {
+            break;
+        }
+        if 2 * n < gradcount - ilast {
+            // periodic restart
+            ilast = gradcount;
+        }
+        if count == n && ilast == gradcount {
+            break;
+        }
+    }
+    Outcome {
+        par: b,
+        value: fmin,
+        status: if iter < maxit {
+            OptimStatus::Converged
+        } else {
+            OptimStatus::MaxIterReached
+        },
+        fn_count: Some(funcount),
+        gr_count: Some(gradcount),
+        message: None,
+    }
+}
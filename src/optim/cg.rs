@@ -0,0 +1,151 @@
+use super::{
+    CgType, OptimControl, OptimStatus, Outcome, Problem,
+    bfgs::{ACCTOL, RELTEST, STEPREDN},
+};
+
+/// Conjugate gradients, restarted every `n` steps, with a backtracking line search refined by
+/// one quadratic interpolation.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/appl/optim.c#L415>
+pub(super) fn cgmin(
+    problem: &Problem,
+    mut bvec: Vec<f64>,
+    control: &OptimControl,
+    maxit: usize,
+) -> Outcome {
+    let n = bvec.len();
+    let mut outcome = Outcome {
+        par: vec![],
+        value: 0.,
+        status: OptimStatus::Converged,
+        fn_count: Some(0),
+        gr_count: Some(0),
+        message: None,
+    };
+    if maxit == 0 {
+        outcome.value = problem.value(&bvec);
+        outcome.par = bvec;
+        return outcome;
+    }
+    let intol = control.reltol;
+    let setstep = 1.7;
+    let cyclimit = n;
+    let tol = intol * n as f64 * intol.sqrt();
+    let mut c = vec![0.; n];
+    let mut t = vec![0.; n];
+    let mut x = bvec.clone();
+    let mut f = problem.value(&bvec);
+    assert!(
+        f.is_finite(),
+        "Function cannot be evaluated at initial parameters"
+    );
+    let mut fmin = f;
+    let mut funcount = 1;
+    let mut gradcount = 0;
+    loop {
+        t.fill(0.);
+        c.fill(0.);
+        let mut cycle = 0;
+        let mut oldstep = 1.;
+        let mut count;
+        let mut g1;
+        loop {
+            cycle += 1;
+            gradcount += 1;
+            if maxit < gradcount {
+                outcome.par = bvec;
+                outcome.value = fmin;
+                outcome.status = OptimStatus::MaxIterReached;
+                outcome.fn_count = Some(funcount);
+                outcome.gr_count = Some(gradcount);
+                return outcome;
+            }
+            let g = problem.gradient(&bvec);
+            g1 = 0.;
+            let mut g2 = 0.;
+            for i in 0..n {
+                x[i] = bvec[i];
+                match control.cg_type {
+                    CgType::FletcherReeves => {
+                        g1 += g[i] * g[i];
+                        g2 += c[i] * c[i];
+                    }
+                    CgType::PolakRibiere => {
+                        g1 += g[i] * (g[i] - c[i]);
+                        g2 += c[i] * c[i];
+                    }
+                    CgType::BealeSorenson => {
+                        g1 += g[i] * (g[i] - c[i]);
+                        g2 += t[i] * (g[i] - c[i]);
+                    }
+                }
+                c[i] = g[i];
+            }
+            count = 0;
+            let mut steplength = oldstep;
+            if tol < g1 {
+                let g3 = if 0. < g2 { g1 / g2 } else { 1. };
+                let mut gradproj = 0.;
+                for i in 0..n {
+                    t[i] = t[i] * g3 - g[i];
+                    gradproj += t[i] * g[i];
+                }
+                let mut accpoint = false;
+                loop {
+                    count = 0;
+                    for i in 0..n {
+                        bvec[i] = x[i] + steplength * t[i];
+                        if RELTEST + x[i] == RELTEST + bvec[i] {
+                            count += 1;
+                        }
+                    }
+                    if count < n {
+                        f = problem.value(&bvec);
+                        funcount += 1;
+                        accpoint = f.is_finite() && f <= fmin + gradproj * steplength * ACCTOL;
+                        if accpoint {
+                            fmin = f;
+                        } else {
+                            steplength *= STEPREDN;
+                        }
+                    }
+                    if count == n || accpoint {
+                        break;
+                    }
+                }
+                if count < n {
+                    let newstep = 2. * (f - fmin - gradproj * steplength);
+                    if 0. < newstep {
+                        let newstep = -(gradproj * steplength * steplength / newstep);
+                        for i in 0..n {
+                            bvec[i] = x[i] + newstep * t[i];
+                        }
+                        fmin = f;
+                        f = problem.value(&bvec);
+                        funcount += 1;
+                        if f < fmin {
+                            fmin = f;
+                        } else {
+                            // reset to the best point
+                            for i in 0..n {
+                                bvec[i] = x[i] + steplength * t[i];
+                            }
+                        }
+                    }
+                }
+            }
+            oldstep = (setstep * steplength).min(1.);
+            if count == n || g1 <= tol || cycle == cyclimit {
+                break;
+            }
+        }
+        if cycle == 1 && (count == n || g1 <= tol || fmin <= control.abstol) {
+            break;
+        }
+    }
+    outcome.par = bvec;
+    outcome.value = fmin;
+    outcome.fn_count = Some(funcount);
+    outcome.gr_count = Some(gradcount);
+    outcome
+}
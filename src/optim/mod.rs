@@ -0,0 +1,476 @@
+mod bfgs;
+mod cg;
+mod nelder_mead;
+mod optimize;
+pub use optimize::*;
+mod projected_lbfgs;
+mod sann;
+
+use crate::{
+    matrix::{FillOrdering, MatrixBuf, matrix},
+    warning::warning,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptimMethod {
+    NelderMead,
+    Bfgs,
+    Cg,
+    /// box-constrained limited-memory BFGS by projection; not a port of R's L-BFGS-B, whose
+    /// results it does not reproduce step for step
+    ProjectedLbfgs,
+    /// simulated annealing
+    Sann,
+    /// `optimize()` on a one-dimensional finite interval
+    Brent,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CgType {
+    FletcherReeves,
+    PolakRibiere,
+    BealeSorenson,
+}
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/optim.R#L27>
+#[derive(Debug, Clone)]
+pub struct OptimControl {
+    /// applied to `f` and its gradient; negative to maximise
+    pub fnscale: f64,
+    /// recycled; the optimisation runs on `par / parscale`
+    pub parscale: Vec<f64>,
+    /// recycled step sizes of the finite-difference gradient, on the `par / parscale` scale
+    pub ndeps: Vec<f64>,
+    /// defaults to 100 for the gradient methods, 500 for Nelder-Mead and 10000 for SANN
+    pub maxit: Option<usize>,
+    pub abstol: f64,
+    pub reltol: f64,
+    /// Nelder-Mead reflection, contraction and expansion factors
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub cg_type: CgType,
+    /// number of BFGS updates kept by `ProjectedLbfgs`
+    pub lmm: usize,
+    /// `ProjectedLbfgs` stops when the reduction in the objective is within `factr * f64::EPSILON`
+    pub factr: f64,
+    /// `ProjectedLbfgs` stops when the projected gradient is within this
+    pub pgtol: f64,
+    /// SANN starting temperature
+    pub temp: f64,
+    /// SANN evaluations at each temperature
+    pub tmax: usize,
+    /// seeds the SANN candidate generator
+    pub seed: u64,
+}
+impl Default for OptimControl {
+    fn default() -> Self {
+        Self {
+            fnscale: 1.,
+            parscale: vec![1.],
+            ndeps: vec![1e-3],
+            maxit: None,
+            abstol: f64::NEG_INFINITY,
+            reltol: f64::EPSILON.sqrt(),
+            alpha: 1.,
+            beta: 0.5,
+            gamma: 2.,
+            cg_type: CgType::FletcherReeves,
+            lmm: 5,
+            factr: 1e7,
+            pgtol: 0.,
+            temp: 10.,
+            tmax: 10,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OptimParams {
+    pub method: OptimMethod,
+    /// recycled bounds; only honoured by `ProjectedLbfgs` and `Brent`
+    pub lower: Vec<f64>,
+    pub upper: Vec<f64>,
+    /// also estimate the Hessian at the optimum
+    pub hessian: bool,
+    pub control: OptimControl,
+}
+impl OptimParams {
+    pub fn new(method: OptimMethod) -> Self {
+        Self {
+            method,
+            lower: vec![f64::NEG_INFINITY],
+            upper: vec![f64::INFINITY],
+            hessian: false,
+            control: OptimControl::default(),
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<OptimParams> for OptimParams { fn as_ref(&self) -> &OptimParams { self } }
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/man/optim.Rd>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptimStatus {
+    Converged,
+    MaxIterReached,
+    /// the Nelder-Mead simplex stopped shrinking
+    Degenerate,
+    /// `ProjectedLbfgs` could not make progress, see `Optim::message`
+    Abnormal,
+}
+/// Gradient of the objective at a parameter vector
+pub type Gradient = dyn Fn(&[f64]) -> Vec<f64>;
+
+#[derive(Debug, Clone)]
+pub struct Optim {
+    pub par: Vec<f64>,
+    pub value: f64,
+    /// calls to `f` as counted by the method; `None` where R reports `NA`
+    pub fn_count: Option<usize>,
+    pub gr_count: Option<usize>,
+    pub convergence: OptimStatus,
+    pub message: Option<String>,
+    pub hessian: Option<MatrixBuf<f64>>,
+}
+
+/// General-purpose minimisation of `f`, or maximisation with a negative `fnscale`.
+///
+/// Without `gr`, gradients come from central differences with steps `ndeps`.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/optim.R>
+pub fn optim(
+    par: impl AsRef<[f64]>,
+    f: impl Fn(&[f64]) -> f64,
+    gr: Option<&Gradient>,
+    params: impl AsRef<OptimParams>,
+) -> Optim {
+    let params = params.as_ref();
+    let control = &params.control;
+    let par = par.as_ref();
+    let n = par.len();
+    let lower = recycle(&params.lower, n);
+    let upper = recycle(&params.upper, n);
+    let mut method = params.method;
+    let bounded = lower.iter().chain(&upper).any(|x| x.is_finite());
+    if bounded && method != OptimMethod::ProjectedLbfgs && method != OptimMethod::Brent {
+        warning("bounds can only be used with method ProjectedLbfgs (or Brent)");
+        method = OptimMethod::ProjectedLbfgs;
+    }
+    if method == OptimMethod::NelderMead && n == 1 {
+        warning(
+            "one-dimensional optimization by Nelder-Mead is unreliable:\nuse \"Brent\" or optimize() directly",
+        );
+    }
+    let parscale = recycle(&control.parscale, n);
+    let problem = Problem {
+        f: &f,
+        gr,
+        fnscale: control.fnscale,
+        ndeps: recycle(&control.ndeps, n),
+        bounds: (method == OptimMethod::ProjectedLbfgs).then(|| {
+            let lower = (0..n).map(|i| lower[i] / parscale[i]).collect();
+            let upper = (0..n).map(|i| upper[i] / parscale[i]).collect();
+            (lower, upper)
+        }),
+        parscale,
+    };
+    let start: Vec<f64> = (0..n).map(|i| par[i] / problem.parscale[i]).collect();
+    let maxit = |default| control.maxit.unwrap_or(default);
+    let outcome = match method {
+        OptimMethod::NelderMead => nelder_mead::nmmin(&problem, start, control, maxit(500)),
+        OptimMethod::Bfgs => bfgs::vmmin(&problem, start, control, maxit(100)),
+        OptimMethod::Cg => cg::cgmin(&problem, start, control, maxit(100)),
+        OptimMethod::ProjectedLbfgs => {
+            let (lower, upper) = problem.bounds.as_ref().unwrap();
+            projected_lbfgs::projected_lbfgs(&problem, start, [lower, upper], control, maxit(100))
+        }
+        OptimMethod::Sann => sann::samin(&problem, start, control, maxit(10000)),
+        OptimMethod::Brent => {
+            assert_eq!(
+                n, 1,
+                "method = \"Brent\" is only available for one-dimensional optimization"
+            );
+            assert!(
+                lower[0].is_finite() && upper[0].is_finite(),
+                "'lower' and 'upper' must be finite values"
+            );
+            let params = OptimizeParams {
+                tolerance: control.reltol,
+                ..OptimizeParams::new(lower[0], upper[0])
+            };
+            let res = optimize(|x| f(&[x]) / control.fnscale, params);
+            Outcome {
+                par: vec![res.optimum / problem.parscale[0]],
+                value: res.objective,
+                status: OptimStatus::Converged,
+                fn_count: None,
+                gr_count: None,
+                message: None,
+            }
+        }
+    };
+    let par = problem.unscale(&outcome.par);
+    let hessian = params.hessian.then(|| problem.hessian(&outcome.par));
+    Optim {
+        par,
+        value: outcome.value * control.fnscale,
+        fn_count: outcome.fn_count,
+        gr_count: outcome.gr_count,
+        convergence: outcome.status,
+        message: outcome.message,
+        hessian,
+    }
+}
+
+fn recycle(x: &[f64], n: usize) -> Vec<f64> {
+    assert!(x.len() == 1 || x.len() == n);
+    (0..n).map(|i| x[i % x.len()]).collect()
+}
+
+/// The objective on the `par / parscale` and `f / fnscale` scales that the methods work on.
+struct Problem<'a> {
+    f: &'a dyn Fn(&[f64]) -> f64,
+    gr: Option<&'a Gradient>,
+    fnscale: f64,
+    parscale: Vec<f64>,
+    ndeps: Vec<f64>,
+    /// scaled bounds that the finite differences must stay within
+    bounds: Option<(Vec<f64>, Vec<f64>)>,
+}
+impl Problem<'_> {
+    fn unscale(&self, p: &[f64]) -> Vec<f64> {
+        p.iter().zip(&self.parscale).map(|(p, s)| p * s).collect()
+    }
+    /// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/src/optim.c#L60>
+    fn value(&self, p: &[f64]) -> f64 {
+        (self.f)(&self.unscale(p)) / self.fnscale
+    }
+    /// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/src/optim.c#L77>
+    fn gradient(&self, p: &[f64]) -> Vec<f64> {
+        self.gradient_within(p, self.bounds.as_ref())
+    }
+    fn gradient_within(&self, p: &[f64], bounds: Option<&(Vec<f64>, Vec<f64>)>) -> Vec<f64> {
+        if let Some(gr) = self.gr {
+            let df = gr(&self.unscale(p));
+            assert_eq!(
+                df.len(),
+                p.len(),
+                "gradient in optim evaluated to length {} not {}",
+                df.len(),
+                p.len()
+            );
+            return (0..p.len())
+                .map(|i| df[i] * self.parscale[i] / self.fnscale)
+                .collect();
+        }
+        let mut x = p.to_vec();
+        let mut df = vec![0.; p.len()];
+        for i in 0..p.len() {
+            let mut eps_up = self.ndeps[i];
+            let mut eps_down = self.ndeps[i];
+            x[i] = p[i] + eps_up;
+            if let Some((_, upper)) = bounds
+                && upper[i] < x[i]
+            {
+                x[i] = upper[i];
+                eps_up = x[i] - p[i];
+            }
+            let val1 = self.value(&x);
+            x[i] = p[i] - eps_down;
+            if let Some((lower, _)) = bounds
+                && x[i] < lower[i]
+            {
+                x[i] = lower[i];
+                eps_down = p[i] - x[i];
+            }
+            let val2 = self.value(&x);
+            df[i] = (val1 - val2) / (eps_up + eps_down);
+            assert!(
+                df[i].is_finite(),
+                "non-finite finite-difference value [{}]",
+                i + 1
+            );
+            x[i] = p[i];
+        }
+        df
+    }
+    /// Differentiate the gradient and symmetrise.
+    ///
+    /// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/src/optim.c#L348>
+    fn hessian(&self, p: &[f64]) -> MatrixBuf<f64> {
+        let n = p.len();
+        let mut dpar = p.to_vec();
+        let mut hess = vec![0.; n * n];
+        for i in 0..n {
+            let eps = self.ndeps[i] / self.parscale[i];
+            dpar[i] = p[i] + eps;
+            let df1 = self.gradient_within(&dpar, None);
+            dpar[i] = p[i] - eps;
+            let df2 = self.gradient_within(&dpar, None);
+            for j in 0..n {
+                hess[i * n + j] = self.fnscale * (df1[j] - df2[j])
+                    / (2. * eps * self.parscale[i] * self.parscale[j]);
+            }
+            dpar[i] = p[i];
+        }
+        let sym: Vec<f64> = (0..n * n)
+            .map(|k| {
+                let (i, j) = (k / n, k % n);
+                0.5 * (hess[i * n + j] + hess[j * n + i])
+            })
+            .collect();
+        matrix(sym, [n, n], FillOrdering::RowByRow)
+    }
+}
+
+struct Outcome {
+    par: Vec<f64>,
+    value: f64,
+    status: OptimStatus,
+    fn_count: Option<usize>,
+    gr_count: Option<usize>,
+    message: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matrix::entry;
+
+    use super::*;
+
+    fn rosenbrock(x: &[f64]) -> f64 {
+        100. * (x[1] - x[0] * x[0]).powi(2) + (1. - x[0]).powi(2)
+    }
+    fn rosenbrock_gr(x: &[f64]) -> Vec<f64> {
+        vec![
+            -400. * x[0] * (x[1] - x[0] * x[0]) - 2. * (1. - x[0]),
+            200. * (x[1] - x[0] * x[0]),
+        ]
+    }
+
+    #[test]
+    fn test_nelder_mead() {
+        // R: optim(c(-1.2, 1), fr)
+        let r = optim(
+            [-1.2, 1.],
+            rosenbrock,
+            None,
+            OptimParams::new(OptimMethod::NelderMead),
+        );
+        assert_eq!(r.convergence, OptimStatus::Converged);
+        assert!((r.par[0] - 1.000260).abs() < 1e-3);
+        assert!((r.par[1] - 1.000506).abs() < 1e-3);
+        assert!((r.value - 8.825241e-08).abs() < 1e-14);
+        assert_eq!((r.fn_count, r.gr_count), (Some(195), None));
+    }
+
+    #[test]
+    fn test_gradient_methods() {
+        // R: optim(c(-1.2, 1), fr, grr, method = "BFGS", hessian = TRUE)
+        let params = OptimParams {
+            hessian: true,
+            ..OptimParams::new(OptimMethod::Bfgs)
+        };
+        let r = optim([-1.2, 1.], rosenbrock, Some(&rosenbrock_gr), &params);
+        assert_eq!(r.convergence, OptimStatus::Converged);
+        assert_eq!((r.fn_count, r.gr_count), (Some(110), Some(43)));
+        assert!((r.value - 9.594956e-18).abs() < 1e-23);
+        let h = r.hessian.unwrap();
+        assert!((entry(&h, [0, 0]) - 802.).abs() < 1.);
+        assert!((entry(&h, [1, 0]) + 400.).abs() < 1.);
+        assert!((entry(&h, [1, 1]) - 200.).abs() < 1e-3);
+        // numerical gradient
+        let r = optim(
+            [-1.2, 1.],
+            rosenbrock,
+            None,
+            OptimParams::new(OptimMethod::Bfgs),
+        );
+        assert!((r.par[0] - 1.).abs() < 1e-2);
+        // CG is slow on Rosenbrock and runs out of iterations, as in R
+        let r = optim(
+            [-1.2, 1.],
+            rosenbrock,
+            Some(&rosenbrock_gr),
+            OptimParams::new(OptimMethod::Cg),
+        );
+        assert_eq!(r.convergence, OptimStatus::MaxIterReached);
+        let mut params = OptimParams::new(OptimMethod::Cg);
+        params.control.maxit = Some(5000);
+        params.control.cg_type = CgType::PolakRibiere;
+        let r = optim([-1.2, 1.], rosenbrock, Some(&rosenbrock_gr), &params);
+        assert_eq!(r.convergence, OptimStatus::Converged);
+        assert!((r.par[0] - 1.).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_projected_lbfgs() {
+        let r = optim(
+            [-1.2, 1.],
+            rosenbrock,
+            Some(&rosenbrock_gr),
+            OptimParams::new(OptimMethod::ProjectedLbfgs),
+        );
+        assert_eq!(r.convergence, OptimStatus::Converged);
+        assert!((r.par[0] - 1.).abs() < 1e-3 && (r.par[1] - 1.).abs() < 1e-3);
+        // R: the 25-dimensional box-constrained example from ?optim
+        let flb = |x: &[f64]| {
+            let p = x.len();
+            let mut s = (x[0] - 1.).powi(2);
+            for i in 1..p {
+                s += 4. * (x[i] - x[i - 1] * x[i - 1]).powi(2);
+            }
+            s
+        };
+        let params = OptimParams {
+            lower: vec![2.],
+            upper: vec![4.],
+            ..OptimParams::new(OptimMethod::ProjectedLbfgs)
+        };
+        let r = optim(vec![3.; 25], flb, None, &params);
+        assert!((r.value - 368.105912874334).abs() < 1e-4);
+        assert!(r.par[..23].iter().all(|&x| x == 2.));
+        assert!((r.par[23] - 2.109093).abs() < 1e-4);
+        assert_eq!(r.par[24], 4.);
+        // bounds switch other methods over, with a warning
+        crate::warning::take_warnings();
+        let params = OptimParams {
+            lower: vec![1.5, f64::NEG_INFINITY],
+            ..OptimParams::new(OptimMethod::NelderMead)
+        };
+        let r = optim([2., 2.], rosenbrock, None, params);
+        assert_eq!(crate::warning::take_warnings().len(), 1);
+        assert!((r.par[0] - 1.5).abs() < 1e-8 && (r.par[1] - 2.25).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_maximise() {
+        let f = |x: &[f64]| -(x[0] - 2.).powi(2) - (x[1] + 1.).powi(2) + 3.;
+        let mut params = OptimParams::new(OptimMethod::Bfgs);
+        params.control.fnscale = -1.;
+        params.control.parscale = vec![10., 1.];
+        let r = optim([0., 0.], f, None, &params);
+        assert!((r.par[0] - 2.).abs() < 1e-3 && (r.par[1] + 1.).abs() < 1e-3);
+        assert!((r.value - 3.).abs() < 1e-6);
+        let params = OptimParams {
+            lower: vec![0.],
+            upper: vec![5.],
+            ..OptimParams::new(OptimMethod::Brent)
+        };
+        let r = optim([1.], |x| (x[0] - 1. / 3.).powi(2), None, params);
+        assert!((r.par[0] - 1. / 3.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sann() {
+        let mut params = OptimParams::new(OptimMethod::Sann);
+        params.control.seed = 42;
+        let r = optim([-1.2, 1.], rosenbrock, None, &params);
+        assert_eq!(r.fn_count, Some(10000));
+        assert!(r.value < rosenbrock(&[-1.2, 1.]));
+        assert!(r.value < 0.1);
+        let again = optim([-1.2, 1.], rosenbrock, None, &params);
+        assert_eq!(r.par, again.par);
+    }
+}
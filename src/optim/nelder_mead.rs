@@ -0,0 +1,185 @@
+use super::{OptimControl, OptimStatus, Outcome, Problem};
+
+/// Substitute for non-finite function values.
+const BIG: f64 = 1.0e35;
+
+/// Nelder-Mead simplex search.
+///
+/// The columns of `p` are the `n + 1` vertices, each followed by its function value, and a final
+/// column for the centroid.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/appl/optim.c#L108>
+#[allow(clippy::needless_range_loop)]
+pub(super) fn nmmin(
+    problem: &Problem,
+    mut bvec: Vec<f64>,
+    control: &OptimControl,
+    maxit: usize,
+) -> Outcome {
+    let n = bvec.len();
+    let (alpha, bet, gamm) = (control.alpha, control.beta, control.gamma);
+    if maxit == 0 {
+        return Outcome {
+            value: problem.value(&bvec),
+            par: bvec,
+            status: OptimStatus::Converged,
+            fn_count: Some(0),
+            gr_count: None,
+            message: None,
+        };
+    }
+    let value = |x: &[f64]| {
+        let f = problem.value(x);
+        if f.is_finite() { f } else { BIG }
+    };
+    let mut f = problem.value(&bvec);
+    assert!(
+        f.is_finite(),
+        "function cannot be evaluated at initial parameters"
+    );
+    let mut funcount = 1;
+    let convtol = control.reltol * (f.abs() + control.reltol);
+    let n1 = n + 1;
+    let c = n + 1;
+    let mut p = vec![vec![0.; n + 2]; n + 1];
+    p[n][0] = f;
+    for i in 0..n {
+        p[i][0] = bvec[i];
+    }
+    let mut l = 0;
+    let mut size = 0.;
+    let mut step = bvec.iter().map(|b| 0.1 * b.abs()).fold(0., f64::max);
+    if step == 0. {
+        step = 0.1;
+    }
+    for j in 1..n1 {
+        for i in 0..n {
+            p[i][j] = bvec[i];
+        }
+        let mut trystep = step;
+        while p[j - 1][j] == bvec[j - 1] {
+            p[j - 1][j] = bvec[j - 1] + trystep;
+            trystep *= 10.;
+        }
+        size += trystep;
+    }
+    let mut oldsize = size;
+    let mut calcvert = true;
+    let mut fail = OptimStatus::Converged;
+    loop {
+        if calcvert {
+            for j in 0..n1 {
+                if j != l {
+                    for i in 0..n {
+                        bvec[i] = p[i][j];
+                    }
+                    p[n][j] = value(&bvec);
+                    funcount += 1;
+                }
+            }
+            calcvert = false;
+        }
+        let mut vl = p[n][l];
+        let mut vh = vl;
+        let mut h = l;
+        for j in 0..n1 {
+            if j != l {
+                let f = p[n][j];
+                if f < vl {
+                    l = j;
+                    vl = f;
+                }
+                if vh < f {
+                    h = j;
+                    vh = f;
+                }
+            }
+        }
+        if vh <= vl + convtol || vl <= control.abstol {
+            break;
+        }
+        for i in 0..n {
+            let temp = p[i][..n1].iter().sum::<f64>() - p[i][h];
+            p[i][c] = temp / n as f64;
+        }
+        for i in 0..n {
+            bvec[i] = (1. + alpha) * p[i][c] - alpha * p[i][h];
+        }
+        f = value(&bvec);
+        funcount += 1;
+        let vr = f;
+        if vr < vl {
+            // expansion
+            p[n][c] = f;
+            for i in 0..n {
+                let f = gamm * bvec[i] + (1. - gamm) * p[i][c];
+                p[i][c] = bvec[i];
+                bvec[i] = f;
+            }
+            f = value(&bvec);
+            funcount += 1;
+            if f < vr {
+                for i in 0..n {
+                    p[i][h] = bvec[i];
+                }
+                p[n][h] = f;
+            } else {
+                for i in 0..n {
+                    p[i][h] = p[i][c];
+                }
+                p[n][h] = vr;
+            }
+        } else {
+            // reduction
+            if vr < vh {
+                for i in 0..n {
+                    p[i][h] = bvec[i];
+                }
+                p[n][h] = vr;
+            }
+            for i in 0..n {
+                bvec[i] = (1. - bet) * p[i][h] + bet * p[i][c];
+            }
+            f = value(&bvec);
+            funcount += 1;
+            if f < p[n][h] {
+                for i in 0..n {
+                    p[i][h] = bvec[i];
+                }
+                p[n][h] = f;
+            } else if vh <= vr {
+                // shrink towards the lowest vertex
+                calcvert = true;
+                size = 0.;
+                for j in 0..n1 {
+                    if j != l {
+                        for i in 0..n {
+                            p[i][j] = bet * (p[i][j] - p[i][l]) + p[i][l];
+                            size += (p[i][j] - p[i][l]).abs();
+                        }
+                    }
+                }
+                if size < oldsize {
+                    oldsize = size;
+                } else {
+                    fail = OptimStatus::Degenerate;
+                    break;
+                }
+            }
+        }
+        if maxit < funcount {
+            break;
+        }
+    }
+    if maxit < funcount {
+        fail = OptimStatus::MaxIterReached;
+    }
+    Outcome {
+        par: (0..n).map(|i| p[i][l]).collect(),
+        value: p[n][l],
+        status: fail,
+        fn_count: Some(funcount),
+        gr_count: None,
+        message: None,
+    }
+}
@@ -0,0 +1,199 @@
+use std::cell::RefCell;
+
+use num_traits::Float;
+
+use crate::warning::warning;
+
+#[derive(Debug, Clone, Copy)]
+pub struct OptimizeParams<T> {
+    pub interval: [T; 2],
+    /// maximise instead of minimise
+    pub maximum: bool,
+    pub tolerance: T,
+    /// record every evaluation `(x, f(x))` in `Optimize::trace`
+    pub trace: bool,
+}
+impl<T> OptimizeParams<T>
+where
+    T: Float,
+{
+    /// R's defaults: `maximum = FALSE`, `tol = .Machine$double.eps^0.25`.
+    pub fn new(lower: T, upper: T) -> Self {
+        Self {
+            interval: [lower, upper],
+            maximum: false,
+            tolerance: T::epsilon().powf(T::from(0.25).unwrap()),
+            trace: false,
+        }
+    }
+}
+#[rustfmt::skip]
+impl<T> AsRef<OptimizeParams<T>> for OptimizeParams<T> { fn as_ref(&self) -> &OptimizeParams<T> { self } }
+
+#[derive(Debug, Clone)]
+pub struct Optimize<T> {
+    /// the minimum, or the maximum if `OptimizeParams::maximum`
+    pub optimum: T,
+    pub objective: T,
+    pub iterations: usize,
+    pub trace: Vec<(T, T)>,
+}
+
+/// Brent's combination of golden-section search and successive parabolic interpolation.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/src/optimize.c#L39>
+pub fn optimize<T>(f: impl Fn(T) -> T, params: impl AsRef<OptimizeParams<T>>) -> Optimize<T>
+where
+    T: Float,
+{
+    let params = params.as_ref();
+    let [lower, upper] = params.interval;
+    assert!(lower < upper, "'xmin' not less than 'xmax'");
+    let trace = RefCell::new(vec![]);
+    let sign = if params.maximum { -T::one() } else { T::one() };
+    let f = |x: T| {
+        let y = f(x);
+        if params.trace {
+            trace.borrow_mut().push((x, y));
+        }
+        y
+    };
+    let objective = |x: T| {
+        let y = sign * f(x);
+        if y.is_finite() {
+            return y;
+        }
+        warning("NA/Inf replaced by maximum positive value");
+        T::max_value()
+    };
+
+    let two = T::one() + T::one();
+    let half = T::one() / two;
+    // the squared inverse of the golden ratio
+    let c = (T::from(3).unwrap() - T::from(5).unwrap().sqrt()) * half;
+    let eps = T::epsilon().sqrt();
+    let (mut a, mut b) = (lower, upper);
+    let mut v = a + c * (b - a);
+    let mut w = v;
+    let mut x = v;
+    let mut d = T::zero();
+    let mut e = T::zero();
+    let mut fx = objective(x);
+    let mut fv = fx;
+    let mut fw = fx;
+    let tol3 = params.tolerance / T::from(3).unwrap();
+    let mut iterations = 0;
+    loop {
+        let xm = (a + b) * half;
+        let tol1 = eps * x.abs() + tol3;
+        let t2 = tol1 * two;
+        if (x - xm).abs() <= t2 - (b - a) * half {
+            break;
+        }
+        let mut p = T::zero();
+        let mut q = T::zero();
+        let mut r = T::zero();
+        if tol1 < e.abs() {
+            // fit parabola
+            r = (x - w) * (fx - fv);
+            q = (x - v) * (fx - fw);
+            p = (x - v) * q - (x - w) * r;
+            q = (q - r) * two;
+            if T::zero() < q {
+                p = -p;
+            } else {
+                q = -q;
+            }
+            r = e;
+            e = d;
+        }
+        if (q * half * r).abs() <= p.abs() || p <= q * (a - x) || q * (b - x) <= p {
+            // golden-section step
+            e = if x < xm { b - x } else { a - x };
+            d = c * e;
+        } else {
+            // parabolic-interpolation step
+            d = p / q;
+            let u = x + d;
+            // f must not be evaluated too close to the ends
+            if u - a < t2 || b - u < t2 {
+                d = if x < xm { tol1 } else { -tol1 };
+            }
+        }
+        // f must not be evaluated too close to x
+        let u = if tol1 <= d.abs() {
+            x + d
+        } else if T::zero() < d {
+            x + tol1
+        } else {
+            x - tol1
+        };
+        let fu = objective(u);
+        iterations += 1;
+        if fu <= fx {
+            if u < x {
+                b = x;
+            } else {
+                a = x;
+            }
+            (v, w, x) = (w, x, u);
+            (fv, fw, fx) = (fw, fx, fu);
+        } else {
+            if u < x {
+                a = u;
+            } else {
+                b = u;
+            }
+            if fu <= fw || w == x {
+                (v, fv) = (w, fw);
+                (w, fw) = (u, fu);
+            } else if fu <= fv || v == x || v == w {
+                (v, fv) = (u, fu);
+            }
+        }
+    }
+    let objective = f(x);
+    Optimize {
+        optimum: x,
+        objective,
+        iterations,
+        trace: trace.into_inner(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optimize() {
+        // R: optimize(function(x) (x - 1/3)^2, c(0, 1))
+        let f = |x: f64| (x - 1. / 3.).powi(2);
+        let r = optimize(f, OptimizeParams::new(0., 1.));
+        assert!((r.optimum - 0.3333333).abs() < 1e-7);
+        assert!(r.objective < 1e-14);
+        assert!(r.trace.is_empty());
+        let params = OptimizeParams {
+            maximum: true,
+            trace: true,
+            ..OptimizeParams::new(0., 2.)
+        };
+        let r = optimize(|x: f64| x.sin(), params);
+        assert!((r.optimum - std::f64::consts::FRAC_PI_2).abs() < 1e-4);
+        assert!((r.objective - 1.).abs() < 1e-8);
+        assert_eq!(r.trace.len(), r.iterations + 2);
+        assert_eq!(r.trace.last().unwrap().0, r.optimum);
+        // R: optimize(function(x) ifelse(x > -1, ifelse(x < 4, exp(-1/abs(x - 1)), 10), 10), c(-4, 20))
+        // converges to the upper end instead of the minimum at 1
+        let f = |x: f64| {
+            if -1. < x && x < 4. {
+                (-1. / (x - 1.).abs()).exp()
+            } else {
+                10.
+            }
+        };
+        let r = optimize(f, OptimizeParams::new(-4., 20.));
+        assert!((r.optimum - 20.).abs() < 1e-4);
+        assert_eq!(r.objective, 10.);
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+
+use super::{OptimControl, OptimStatus, Outcome, Problem};
+
+/// Projected limited-memory BFGS for box constraints.
+///
+/// Variables held at a bound by the gradient are frozen, the search direction comes from the
+/// two-loop recursion over the free ones, and the step is projected back into the box with an
+/// Armijo backtracking search. Unlike R's L-BFGS-B there is no generalized Cauchy point or
+/// subspace minimization, so iterates and counts differ from `optim(method = "L-BFGS-B")`; only
+/// the `factr` and `pgtol` stopping rules are shared.
+///
+/// ref: <https://doi.org/10.1007/BF01589116>
+pub(super) fn projected_lbfgs(
+    problem: &Problem,
+    x0: Vec<f64>,
+    [lower, upper]: [&[f64]; 2],
+    control: &OptimControl,
+    maxit: usize,
+) -> Outcome {
+    let n = x0.len();
+    for i in 0..n {
+        assert!(lower[i] <= upper[i], "'lower' must not exceed 'upper'");
+    }
+    let project = |x: &mut [f64]| {
+        for i in 0..n {
+            x[i] = x[i].max(lower[i]).min(upper[i]);
+        }
+    };
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(a, b)| a * b).sum::<f64>();
+    let mut x = x0;
+    project(&mut x);
+    let mut f = problem.value(&x);
+    assert!(
+        f.is_finite(),
+        "projected L-BFGS needs finite values of 'fn'"
+    );
+    let mut g = problem.gradient(&x);
+    let mut fn_count = 1;
+    let mut gr_count = 1;
+    let mut history: VecDeque<(Vec<f64>, Vec<f64>, f64)> = VecDeque::new();
+    let mut status = OptimStatus::MaxIterReached;
+    let mut message = None;
+    let mut iter = 0;
+    loop {
+        let projected_gradient = (0..n)
+            .map(|i| ((x[i] - g[i]).max(lower[i]).min(upper[i]) - x[i]).abs())
+            .fold(0., f64::max);
+        if projected_gradient <= control.pgtol {
+            status = OptimStatus::Converged;
+            message = Some("projected gradient within pgtol");
+            break;
+        }
+        if maxit <= iter {
+            break;
+        }
+        iter += 1;
+        let free: Vec<bool> = (0..n)
+            .map(|i| (lower[i] < x[i] || g[i] < 0.) && (x[i] < upper[i] || 0. < g[i]))
+            .collect();
+        let mask = |v: &mut [f64]| {
+            for i in 0..n {
+                if !free[i] {
+                    v[i] = 0.;
+                }
+            }
+        };
+        // two-loop recursion: `d = -H g` on the free variables
+        let mut d = g.clone();
+        mask(&mut d);
+        let mut alphas = Vec::with_capacity(history.len());
+        for (s, y, rho) in history.iter().rev() {
+            let a = rho * dot(s, &d);
+            for i in 0..n {
+                d[i] -= a * y[i];
+            }
+            alphas.push(a);
+        }
+        if let Some((s, y, _)) = history.back() {
+            let gamma = dot(s, y) / dot(y, y);
+            d.iter_mut().for_each(|d| *d *= gamma);
+        }
+        for ((s, y, rho), a) in history.iter().zip(alphas.iter().rev()) {
+            let b = rho * dot(y, &d);
+            for i in 0..n {
+                d[i] += s[i] * (a - b);
+            }
+        }
+        d.iter_mut().for_each(|d| *d = -*d);
+        mask(&mut d);
+        if 0. <= dot(&g, &d) {
+            history.clear();
+            d = g.iter().map(|g| -g).collect();
+            mask(&mut d);
+        }
+        let mut alpha = if history.is_empty() {
+            1_f64.min(1. / dot(&d, &d).sqrt())
+        } else {
+            1.
+        };
+        let mut accepted = None;
+        for _ in 0..20 {
+            let mut x_new: Vec<f64> = (0..n).map(|i| x[i] + alpha * d[i]).collect();
+            project(&mut x_new);
+            let step: Vec<f64> = (0..n).map(|i| x_new[i] - x[i]).collect();
+            if step.iter().all(|s| *s == 0.) {
+                break;
+            }
+            let f_new = problem.value(&x_new);
+            fn_count += 1;
+            if f_new.is_finite() && f_new <= f + 1e-4 * dot(&g, &step) {
+                accepted = Some((x_new, f_new, step));
+                break;
+            }
+            alpha *= 0.5;
+        }
+        let Some((x_new, f_new, s)) = accepted else {
+            if !history.is_empty() {
+                // retry along the steepest descent
+                history.clear();
+                continue;
+            }
+            status = OptimStatus::Abnormal;
+            message = Some("line search failed along steepest descent");
+            break;
+        };
+        let g_new = problem.gradient(&x_new);
+        gr_count += 1;
+        let y: Vec<f64> = (0..n).map(|i| g_new[i] - g[i]).collect();
+        let sy = dot(&s, &y);
+        if f64::EPSILON * dot(&y, &y) < sy {
+            history.push_back((s, y, 1. / sy));
+            if control.lmm < history.len() {
+                history.pop_front();
+            }
+        }
+        let reduction = (f - f_new) / f.abs().max(f_new.abs()).max(1.);
+        x = x_new;
+        f = f_new;
+        g = g_new;
+        if reduction <= control.factr * f64::EPSILON {
+            status = OptimStatus::Converged;
+            message = Some("relative reduction of f within factr * epsilon");
+            break;
+        }
+    }
+    Outcome {
+        par: x,
+        value: f,
+        status,
+        fn_count: Some(fn_count),
+        gr_count: Some(gr_count),
+        message: message.map(String::from),
+    }
+}
@@ -0,0 +1,74 @@
+use rand::{Rng, SeedableRng, distributions::Distribution, rngs::StdRng};
+
+use super::{OptimControl, OptimStatus, Outcome, Problem};
+
+/// Substitute for non-finite function values.
+const BIG: f64 = 1.0e35;
+/// `exp(1) - 1`
+const E1: f64 = 1.7182818;
+
+/// Simulated annealing with a Gaussian Markov kernel whose scale follows the temperature and a
+/// logarithmic cooling schedule; always runs for `maxit` evaluations.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/appl/optim.c#L700>
+pub(super) fn samin(
+    problem: &Problem,
+    mut pb: Vec<f64>,
+    control: &OptimControl,
+    maxit: usize,
+) -> Outcome {
+    let n = pb.len();
+    let value = |x: &[f64]| {
+        let f = problem.value(x);
+        if f.is_finite() { f } else { BIG }
+    };
+    let mut yb = value(&pb);
+    if n == 0 {
+        return Outcome {
+            par: pb,
+            value: yb,
+            status: OptimStatus::Converged,
+            fn_count: Some(maxit),
+            gr_count: None,
+            message: None,
+        };
+    }
+    let mut rng = StdRng::seed_from_u64(control.seed);
+    let norm = statrs::distribution::Normal::new(0., 1.).unwrap();
+    let ti = control.temp;
+    let mut p = pb.clone();
+    let mut y = yb;
+    let mut ptry = vec![0.; n];
+    let scale = 1. / ti;
+    let mut its = 1;
+    while its < maxit {
+        // temperature annealing schedule
+        let t = ti / (its as f64 + E1).ln();
+        let mut k = 1;
+        while k <= control.tmax && its < maxit {
+            for j in 0..n {
+                ptry[j] = p[j] + scale * t * norm.sample(&mut rng);
+            }
+            let ytry = value(&ptry);
+            let dy = ytry - y;
+            if dy <= 0. || rng.r#gen::<f64>() < (-dy / t).exp() {
+                p.copy_from_slice(&ptry);
+                y = ytry;
+                if y <= yb {
+                    pb.copy_from_slice(&p);
+                    yb = y;
+                }
+            }
+            its += 1;
+            k += 1;
+        }
+    }
+    Outcome {
+        par: pb,
+        value: yb,
+        status: OptimStatus::Converged,
+        fn_count: Some(maxit),
+        gr_count: None,
+        message: None,
+    }
+}
@@ -0,0 +1,325 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+pub use nalgebra::Complex;
+use num_traits::{Float, FromPrimitive};
+
+/// Coefficients in increasing order of degree, as in R's `polyroot(z)`; trailing zeros are
+/// dropped so the last coefficient is the leading one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial<T> {
+    coefficients: Vec<T>,
+}
+impl<T> Polynomial<T>
+where
+    T: Float,
+{
+    pub fn new(coefficients: impl AsRef<[T]>) -> Self {
+        let mut coefficients = coefficients.as_ref().to_vec();
+        trim(&mut coefficients, T::zero());
+        Self { coefficients }
+    }
+    pub fn zero() -> Self {
+        Self {
+            coefficients: vec![],
+        }
+    }
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+    /// `0` for constants, including the zero polynomial.
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+    pub fn leading(&self) -> T {
+        self.coefficients.last().copied().unwrap_or(T::zero())
+    }
+
+    /// Horner's scheme.
+    pub fn eval(&self, x: T) -> T {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(T::zero(), |acc, &c| acc * x + c)
+    }
+    pub fn eval_vec(&self, x: impl AsRef<[T]>) -> Vec<T> {
+        x.as_ref().iter().map(|&x| self.eval(x)).collect()
+    }
+    pub fn eval_complex(&self, z: Complex<T>) -> Complex<T> {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Complex::new(T::zero(), T::zero()), |acc, &c| {
+                acc * z + Complex::new(c, T::zero())
+            })
+    }
+
+    pub fn derivative(&self) -> Self
+    where
+        T: FromPrimitive,
+    {
+        let coefficients: Vec<T> = (1..self.coefficients.len())
+            .map(|i| self.coefficients[i] * T::from_usize(i).unwrap())
+            .collect();
+        Self::new(coefficients)
+    }
+    /// Antiderivative with zero constant term.
+    pub fn integral(&self) -> Self
+    where
+        T: FromPrimitive,
+    {
+        let coefficients: Vec<T> = std::iter::once(T::zero())
+            .chain(
+                (0..self.coefficients.len())
+                    .map(|i| self.coefficients[i] / T::from_usize(i + 1).unwrap()),
+            )
+            .collect();
+        Self::new(coefficients)
+    }
+
+    /// `(quotient, remainder)` of polynomial long division.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "division by the zero polynomial");
+        let mut remainder = self.coefficients.clone();
+        if remainder.len() < divisor.coefficients.len() {
+            return (Self::zero(), self.clone());
+        }
+        let d = divisor.degree();
+        let lead = divisor.leading();
+        let mut quotient = vec![T::zero(); remainder.len() - d];
+        for k in (0..quotient.len()).rev() {
+            let q = remainder[k + d] / lead;
+            quotient[k] = q;
+            for (j, &c) in divisor.coefficients.iter().enumerate() {
+                remainder[k + j] = remainder[k + j] - q * c;
+            }
+            // the leading term cancels by construction
+            remainder[k + d] = T::zero();
+        }
+        (Self::new(quotient), Self::new(remainder))
+    }
+    /// Monic greatest common divisor by Euclid's algorithm; remainder coefficients below
+    /// `sqrt(ε)` relative to the inputs count as zero.
+    pub fn gcd(&self, other: &Self) -> Self {
+        let scale = self
+            .coefficients
+            .iter()
+            .chain(&other.coefficients)
+            .fold(T::zero(), |m, c| m.max(c.abs()));
+        let tolerance = scale * T::epsilon().sqrt();
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let (_, mut r) = a.div_rem(&b);
+            trim(&mut r.coefficients, tolerance);
+            (a, b) = (b, r);
+        }
+        if a.is_zero() {
+            return a;
+        }
+        let lead = a.leading();
+        Self::new(a.coefficients.iter().map(|&c| c / lead).collect::<Vec<T>>())
+    }
+}
+fn trim<T: Float>(coefficients: &mut Vec<T>, tolerance: T) {
+    while coefficients.last().is_some_and(|c| c.abs() <= tolerance) {
+        coefficients.pop();
+    }
+}
+
+impl<T: Float> Add for Polynomial<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let n = self.coefficients.len().max(rhs.coefficients.len());
+        let get = |p: &Self, i: usize| p.coefficients.get(i).copied().unwrap_or(T::zero());
+        Self::new(
+            (0..n)
+                .map(|i| get(&self, i) + get(&rhs, i))
+                .collect::<Vec<T>>(),
+        )
+    }
+}
+impl<T: Float> Neg for Polynomial<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(self.coefficients.iter().map(|&c| -c).collect::<Vec<T>>())
+    }
+}
+impl<T: Float> Sub for Polynomial<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+impl<T: Float> Mul for Polynomial<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        if self.is_zero() || rhs.is_zero() {
+            return Self::zero();
+        }
+        let mut out = vec![T::zero(); self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, &a) in self.coefficients.iter().enumerate() {
+            for (j, &b) in rhs.coefficients.iter().enumerate() {
+                out[i + j] = out[i + j] + a * b;
+            }
+        }
+        Self::new(out)
+    }
+}
+
+/// All complex roots of the polynomial with coefficients `z` in increasing order, sorted by
+/// modulus.
+///
+/// The roots are the eigenvalues of the companion matrix, each polished by Newton steps.
+///
+/// ref: <https://stat.ethz.ch/R-manual/R-devel/library/base/html/polyroot.html>
+pub fn polyroot(z: impl AsRef<[f64]>) -> Vec<Complex<f64>> {
+    let p = Polynomial::new(z);
+    assert!(
+        p.coefficients.iter().all(|c| c.is_finite()),
+        "invalid polynomial coefficient"
+    );
+    let n = p.degree();
+    if n == 0 {
+        return vec![];
+    }
+    let lead = p.leading();
+    let companion = nalgebra::DMatrix::from_fn(n, n, |r, c| {
+        if c == n - 1 {
+            -p.coefficients[r] / lead
+        } else if r == c + 1 {
+            1.
+        } else {
+            0.
+        }
+    });
+    let dp = p.derivative();
+    let mut roots: Vec<Complex<f64>> = companion
+        .complex_eigenvalues()
+        .iter()
+        .map(|&z| {
+            let mut z = z;
+            for _ in 0..3 {
+                let d = dp.eval_complex(z);
+                if d.norm() == 0. {
+                    break;
+                }
+                let value = p.eval_complex(z);
+                let polished = z - value / d;
+                // near multiple roots Newton steps can wander off, or divide 0 by 0
+                if p.eval_complex(polished).norm() >= value.norm() || polished.re.is_nan() {
+                    break;
+                }
+                z = polished;
+            }
+            z
+        })
+        .collect();
+    roots.sort_by(|a, b| {
+        a.norm()
+            .total_cmp(&b.norm())
+            .then(a.re.total_cmp(&b.re))
+            .then(a.im.total_cmp(&b.im))
+    });
+    roots
+}
+
+/// Least-squares polynomial of the given degree through `(x, y)`, solved by QR of the
+/// Vandermonde matrix; the coefficients of `lm(y ~ poly(x, degree, raw = TRUE))`.
+pub fn polyfit(x: impl AsRef<[f64]>, y: impl AsRef<[f64]>, degree: usize) -> Polynomial<f64> {
+    let x = x.as_ref();
+    let y = y.as_ref();
+    assert_eq!(x.len(), y.len());
+    let mut unique = x.to_vec();
+    unique.sort_by(f64::total_cmp);
+    unique.dedup();
+    assert!(
+        degree < unique.len(),
+        "'degree' must be less than number of unique points"
+    );
+    let vandermonde = nalgebra::DMatrix::from_fn(x.len(), degree + 1, |r, c| x[r].powi(c as i32));
+    let qr = vandermonde.qr();
+    let qty = qr.q().transpose() * nalgebra::DVector::from_column_slice(y);
+    let coefficients = qr
+        .r()
+        .solve_upper_triangular(&qty)
+        .expect("singular Vandermonde matrix");
+    Polynomial::new(coefficients.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_roots(roots: &[Complex<f64>], expected: &[Complex<f64>], tolerance: f64) {
+        assert_eq!(roots.len(), expected.len());
+        for (r, e) in roots.iter().zip(expected) {
+            assert!((r - e).norm() < tolerance, "{r} != {e}");
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let p = Polynomial::new([-1., 0., 1.]);
+        assert_eq!(p.eval_vec([-2., 0., 3.]), vec![3., -1., 8.]);
+        let q = Polynomial::new([1., 1., 0., 0.]);
+        assert_eq!(q.degree(), 1);
+        assert_eq!((p.clone() + q.clone()).coefficients(), &[0., 1., 1.]);
+        assert_eq!((p.clone() - p.clone()), Polynomial::zero());
+        assert_eq!((p.clone() * q.clone()).coefficients(), &[-1., -1., 1., 1.]);
+        let (quotient, remainder) = p.div_rem(&q);
+        assert_eq!(quotient.coefficients(), &[-1., 1.]);
+        assert!(remainder.is_zero());
+        let (quotient, remainder) = Polynomial::new([5., 0., 0., 2.]).div_rem(&q);
+        assert_eq!(quotient.coefficients(), &[2., -2., 2.]);
+        assert_eq!(remainder.coefficients(), &[3.]);
+        let cubic = Polynomial::new([1., 2., 3., 4.]);
+        assert_eq!(cubic.derivative().coefficients(), &[2., 6., 12.]);
+        assert_eq!(
+            cubic.derivative().integral().coefficients(),
+            &[0., 2., 3., 4.]
+        );
+        // (x - 1)(x - 2) and (x - 1)(x - 3)
+        let a = Polynomial::new([2., -3., 1.]);
+        let b = Polynomial::new([3., -4., 1.]);
+        assert_eq!(a.gcd(&b).coefficients(), &[-1., 1.]);
+        assert_eq!(a.gcd(&Polynomial::new([1., 1.])).coefficients(), &[1.]);
+    }
+
+    #[test]
+    fn test_polyroot() {
+        // R: polyroot(c(6, -5, 1))
+        let c = |re, im| Complex::new(re, im);
+        assert_roots(&polyroot([6., -5., 1.]), &[c(2., 0.), c(3., 0.)], 1e-12);
+        assert_roots(&polyroot([1., 0., 1.]), &[c(0., -1.), c(0., 1.)], 1e-12);
+        assert_roots(&polyroot([0., 0., 4., 0.]), &[c(0., 0.), c(0., 0.)], 1e-12);
+        assert!(polyroot([3.]).is_empty());
+        // (x - 1)(x - 2)(x - 3)(x - 4)(x - 5)
+        let p = (1..=5).fold(Polynomial::new([1.]), |p, k| {
+            p * Polynomial::new([-(k as f64), 1.])
+        });
+        let expected: Vec<_> = (1..=5).map(|k| c(k as f64, 0.)).collect();
+        assert_roots(&polyroot(p.coefficients()), &expected, 1e-9);
+    }
+
+    #[test]
+    fn test_polyfit() {
+        let x: Vec<f64> = (0..20).map(|i| i as f64 / 4.).collect();
+        let truth = Polynomial::new([1., -2., 0.5, 0.25]);
+        let fit = polyfit(&x, truth.eval_vec(&x), 3);
+        for (a, b) in fit.coefficients().iter().zip(truth.coefficients()) {
+            assert!((a - b).abs() < 1e-10);
+        }
+        // R: coef(lm(y ~ x)) with x = 1:5, y = c(1, 3, 2, 5, 4)
+        let fit = polyfit([1., 2., 3., 4., 5.], [1., 3., 2., 5., 4.], 1);
+        assert!((fit.coefficients()[0] - 0.6).abs() < 1e-12);
+        assert!((fit.coefficients()[1] - 0.8).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "'degree' must be less than number of unique points")]
+    fn test_polyfit_duplicates() {
+        polyfit([1., 1., 2., 2.], [1., 2., 3., 4.], 2);
+    }
+}
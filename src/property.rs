@@ -1,3 +1,6 @@
+pub use num_bigint::{BigInt, BigUint};
+use num_traits::{One, ToPrimitive};
+
 macro_rules! mass_impl {
     (
         impl $Trait: ident for { $( $Type: ty ),* }
@@ -49,6 +52,14 @@ mass_impl!(
         }
     }
 );
+mass_impl!(
+    impl CalcFactorial for { BigInt, BigUint } {
+        fn factorial(&self) -> Self {
+            let n = self.to_usize().expect("'n' must be non-negative");
+            (1..=n).fold(Self::one(), |cum, a| cum * Self::from(a))
+        }
+    }
+);
 mass_impl!(
     impl CalcFactorial for { f32, f64 } {
         #[allow(clippy::useless_conversion)]
@@ -65,21 +76,89 @@ pub trait CalcChoose {
 mass_impl!(
     impl CalcChoose for { u8, i8, u16, i16, u32, i32, u128, i128, usize, isize } {
         fn choose(&self, r: Self) -> Self {
-            assert!(r <= *self);
-            let a = *self - r + 1;
-            let a: Self = (a..=*self).product();
-            a / r.factorial()
+            checked_choose_multiplicative(self, &r).expect("integer overflow")
+        }
+    }
+);
+mass_impl!(
+    impl CalcChoose for { BigInt, BigUint } {
+        fn choose(&self, r: Self) -> Self {
+            checked_choose_multiplicative(self, &r).unwrap()
         }
     }
 );
 mass_impl!(
     impl CalcChoose for { f32, f64 } {
+        #[allow(clippy::useless_conversion)]
         fn choose(&self, r: Self) -> Self {
-            self.factorial() / (r.factorial() * (*self - r).factorial())
+            crate::special::choose((*self).into(), r.into()) as _
+        }
+    }
+);
+
+#[rustfmt::skip]
+pub trait CalcCheckedFactorial {
+    fn checked_factorial(&self) -> Option<Self> where Self: Sized;
+}
+mass_impl!(
+    impl CalcCheckedFactorial for { u8, i8, u16, i16, u32, i32, u128, i128, usize, isize } {
+        fn checked_factorial(&self) -> Option<Self> {
+            if *self < 0 as Self {
+                return None;
+            }
+            (1..=*self).try_fold(1 as Self, |cum, a| cum.checked_mul(a))
+        }
+    }
+);
+
+#[rustfmt::skip]
+pub trait CalcCheckedChoose {
+    fn checked_choose(&self, r: Self) -> Option<Self> where Self: Sized;
+}
+mass_impl!(
+    impl CalcCheckedChoose for { u8, i8, u16, i16, u32, i32, u128, i128, usize, isize } {
+        fn checked_choose(&self, r: Self) -> Option<Self> {
+            checked_choose_multiplicative(self, &r)
         }
     }
 );
 
+/// `choose(n, r) = prod_{i=1..r} (n - r + i) / i`, where every partial product is itself a binomial
+/// coefficient no larger than the result, so it overflows only if the result does.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/nmath/choose.c#L112>
+fn checked_choose_multiplicative<T>(n: &T, r: &T) -> Option<T>
+where
+    T: num_integer::Integer + num_traits::CheckedMul + num_traits::CheckedSub + Clone,
+{
+    // like R's `choose(n, k)`: 0 for `k < 0` or `0 <= n < k`, and reflected for `n < 0`
+    if *r < T::zero() {
+        return Some(T::zero());
+    }
+    if *n < T::zero() {
+        let m = r.checked_sub(n)?.checked_sub(&T::one())?;
+        let out = checked_choose_multiplicative(&m, r)?;
+        return match r.is_odd() {
+            true => T::zero().checked_sub(&out),
+            false => Some(out),
+        };
+    }
+    if n < r {
+        return Some(T::zero());
+    }
+    let n_r = n.clone() - r.clone();
+    let r = if n_r < *r { n_r } else { r.clone() };
+    let mut out = T::one();
+    let mut i = T::one();
+    while i <= r {
+        let g = out.gcd(&i);
+        let numerator = (n.clone() - r.clone() + i.clone()) / (i.clone() / g.clone());
+        out = (out / g).checked_mul(&numerator)?;
+        i = i + T::one();
+    }
+    Some(out)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::vector;
@@ -93,5 +172,45 @@ mod tests {
         assert!(vector::all_eq([(4.0_f64).choose(2.)], [6.], &vector::AllEqParams { tolerance: 10e-7, scale: vector::all_eq_no_scale() }));
         assert_eq!(6.choose(4), 15);
         assert!(vector::all_eq([(6.0_f64).choose(4.)], [15.], &vector::AllEqParams { tolerance: 10e-7, scale: vector::all_eq_no_scale() }));
+        assert!((200.0_f64).choose(100.).is_finite());
+        assert_eq!(6_i32.choose(-1), 0);
+        assert_eq!(4_u8.choose(5), 0);
+        assert_eq!((-3_i32).choose(2), 6);
+        assert_eq!((-2_isize).choose(3), -4);
+        assert_eq!(BigInt::from(6).choose(BigInt::from(-2)), BigInt::from(0));
+        assert_eq!(BigInt::from(4).choose(BigInt::from(5)), BigInt::from(0));
+        assert_eq!(BigUint::from(4_u8).choose(BigUint::from(5_u8)), BigUint::from(0_u8));
+    }
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(12_i32.checked_factorial(), Some(479001600));
+        assert_eq!(13_i32.checked_factorial(), None);
+        assert_eq!((-1_i32).checked_factorial(), None);
+        assert_eq!(6_i32.checked_choose(4), Some(15));
+        assert_eq!(6_u8.checked_choose(4), Some(15));
+        assert_eq!(20_u8.checked_choose(10), None);
+        assert_eq!(60_usize.checked_choose(30), Some(118264581564861424));
+        assert_eq!(6_i32.checked_choose(-1), Some(0));
+        assert_eq!(4_u8.checked_choose(5), Some(0));
+        assert_eq!((-3_i8).checked_choose(2), Some(6));
+        assert_eq!((-100_i8).checked_choose(100), None);
+    }
+
+    #[test]
+    fn test_bigint() {
+        let n = BigUint::from(100_u8);
+        assert_eq!(n.factorial().to_string().len(), 158);
+        assert_eq!(
+            n.choose(BigUint::from(50_u8)).to_string(),
+            "100891344545564193334812497256"
+        );
+        assert_eq!(BigInt::from(4).choose(BigInt::from(2)), BigInt::from(6));
+    }
+
+    #[test]
+    #[should_panic(expected = "'n' must be non-negative")]
+    fn test_bigint_negative_factorial() {
+        BigInt::from(-3).factorial();
     }
 }
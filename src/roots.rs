@@ -0,0 +1,500 @@
+use num_traits::Float;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RootStatus {
+    Converged,
+    MaxIterReached,
+    /// An iterate or its function value stopped being finite.
+    Diverged,
+}
+#[derive(Debug, Clone, Copy)]
+pub struct RootResult<T> {
+    pub root: T,
+    pub f_root: T,
+    /// function evaluations after the initial ones, including those spent extending the interval
+    pub iterations: usize,
+    /// approximate error bound on `root`
+    pub estim_prec: T,
+    pub status: RootStatus,
+}
+impl<T> RootResult<T> {
+    pub fn converged(&self) -> bool {
+        self.status == RootStatus::Converged
+    }
+}
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/uniroot.R#L20>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExtendInt {
+    /// the interval must already bracket a root
+    No,
+    /// widen both ends until the signs differ
+    Yes,
+    /// `f` is known to be increasing across the root
+    Upward,
+    /// `f` is known to be decreasing across the root
+    Downward,
+}
+#[derive(Debug, Clone, Copy)]
+pub struct BracketParams<T> {
+    pub interval: [T; 2],
+    pub tolerance: T,
+    pub max_iter: usize,
+    pub extend_int: ExtendInt,
+}
+impl<T> BracketParams<T>
+where
+    T: Float,
+{
+    /// R's defaults: `tol = .Machine$double.eps^0.25`, `maxiter = 1000`, `extendInt = "no"`.
+    pub fn new(lower: T, upper: T) -> Self {
+        Self {
+            interval: [lower, upper],
+            tolerance: T::epsilon().powf(T::from(0.25).unwrap()),
+            max_iter: 1000,
+            extend_int: ExtendInt::No,
+        }
+    }
+}
+#[rustfmt::skip]
+impl<T> AsRef<BracketParams<T>> for BracketParams<T> { fn as_ref(&self) -> &BracketParams<T> { self } }
+
+/// Brent's method, a safeguarded mix of bisection and inverse quadratic interpolation.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/src/zeroin.c#L67>
+pub fn uniroot<T>(f: impl Fn(T) -> T, params: impl AsRef<BracketParams<T>>) -> RootResult<T>
+where
+    T: Float,
+{
+    let params = params.as_ref();
+    let bracket = find_bracket(&f, params);
+    let two = T::one() + T::one();
+    let [mut a, mut b] = bracket.interval;
+    let [mut fa, mut fb] = bracket.f_interval;
+    let mut out = RootResult {
+        root: b,
+        f_root: fb,
+        iterations: bracket.iterations,
+        estim_prec: T::zero(),
+        status: RootStatus::Converged,
+    };
+    if fa.is_zero() {
+        out.root = a;
+        out.f_root = fa;
+        return out;
+    }
+    if fb.is_zero() {
+        return out;
+    }
+    let mut c = a;
+    let mut fc = fa;
+    for _ in 0..=params.max_iter {
+        let prev_step = b - a;
+        if fc.abs() < fb.abs() {
+            (a, b, c) = (b, c, b);
+            (fa, fb, fc) = (fb, fc, fb);
+        }
+        let tol_act = two * T::epsilon() * b.abs() + params.tolerance / two;
+        let mut new_step = (c - b) / two;
+        if new_step.abs() <= tol_act || fb.is_zero() {
+            out.root = b;
+            out.f_root = fb;
+            out.estim_prec = (c - b).abs();
+            return out;
+        }
+        if tol_act <= prev_step.abs() && fb.abs() < fa.abs() {
+            let cb = c - b;
+            let (mut p, mut q);
+            if a == c {
+                // linear interpolation
+                let t1 = fb / fa;
+                p = cb * t1;
+                q = T::one() - t1;
+            } else {
+                // inverse quadratic interpolation
+                let q_ = fa / fc;
+                let t1 = fb / fc;
+                let t2 = fb / fa;
+                p = t2 * (cb * q_ * (q_ - t1) - (b - a) * (t1 - T::one()));
+                q = (q_ - T::one()) * (t1 - T::one()) * (t2 - T::one());
+            }
+            if T::zero() < p {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            let three_quarters = T::from(0.75).unwrap();
+            if p < three_quarters * cb * q - (tol_act * q).abs() / two
+                && p < (prev_step * q / two).abs()
+            {
+                new_step = p / q;
+            }
+        }
+        if new_step.abs() < tol_act {
+            new_step = if T::zero() < new_step {
+                tol_act
+            } else {
+                -tol_act
+            };
+        }
+        a = b;
+        fa = fb;
+        b = b + new_step;
+        fb = f(b);
+        out.iterations += 1;
+        if (T::zero() < fb && T::zero() < fc) || (fb < T::zero() && fc < T::zero()) {
+            c = a;
+            fc = fa;
+        }
+    }
+    out.root = b;
+    out.f_root = fb;
+    out.estim_prec = T::nan();
+    out.status = RootStatus::MaxIterReached;
+    out
+}
+
+pub fn bisection<T>(f: impl Fn(T) -> T, params: impl AsRef<BracketParams<T>>) -> RootResult<T>
+where
+    T: Float,
+{
+    bracketing(f, params.as_ref(), |[a, b], _| {
+        (a + b) / (T::one() + T::one())
+    })
+}
+/// False position with the Illinois modification, which halves the retained end's function value
+/// whenever the same end is kept twice in a row so that convergence stays superlinear.
+pub fn regula_falsi<T>(f: impl Fn(T) -> T, params: impl AsRef<BracketParams<T>>) -> RootResult<T>
+where
+    T: Float,
+{
+    bracketing(f, params.as_ref(), |[a, b], [fa, fb]| {
+        let m = b - fb * (b - a) / (fb - fa);
+        if m.is_finite() && a.min(b) < m && m < a.max(b) {
+            m
+        } else {
+            (a + b) / (T::one() + T::one())
+        }
+    })
+}
+fn bracketing<T>(
+    f: impl Fn(T) -> T,
+    params: &BracketParams<T>,
+    next: impl Fn([T; 2], [T; 2]) -> T,
+) -> RootResult<T>
+where
+    T: Float,
+{
+    let bracket = find_bracket(&f, params);
+    let [mut a, mut b] = bracket.interval;
+    let [mut fa, mut fb] = bracket.f_interval;
+    let mut out = RootResult {
+        root: a,
+        f_root: fa,
+        iterations: bracket.iterations,
+        estim_prec: T::zero(),
+        status: RootStatus::Converged,
+    };
+    if fa.is_zero() {
+        return out;
+    }
+    if fb.is_zero() {
+        out.root = b;
+        out.f_root = fb;
+        return out;
+    }
+    let half = T::one() / (T::one() + T::one());
+    // which end was retained by the previous step: -1 for `a`, 1 for `b`
+    let mut retained = 0;
+    for _ in 0..params.max_iter {
+        let m = next([a, b], [fa, fb]);
+        let fm = f(m);
+        out.iterations += 1;
+        out.root = m;
+        out.f_root = fm;
+        if fm.is_zero() {
+            out.estim_prec = T::zero();
+            return out;
+        }
+        if fa.signum() == fm.signum() {
+            a = m;
+            fa = fm;
+            if retained == 1 {
+                fb = fb * half;
+            }
+            retained = 1;
+        } else {
+            b = m;
+            fb = fm;
+            if retained == -1 {
+                fa = fa * half;
+            }
+            retained = -1;
+        }
+        out.estim_prec = (b - a).abs();
+        if out.estim_prec <= params.tolerance {
+            return out;
+        }
+    }
+    out.status = RootStatus::MaxIterReached;
+    out
+}
+
+struct Bracket<T> {
+    interval: [T; 2],
+    f_interval: [T; 2],
+    iterations: usize,
+}
+/// Widen the interval in geometrically growing steps until `f` changes sign across it.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/uniroot.R#L58>
+fn find_bracket<T>(f: impl Fn(T) -> T, params: &BracketParams<T>) -> Bracket<T>
+where
+    T: Float,
+{
+    let [mut lower, mut upper] = params.interval;
+    assert!(lower < upper, "lower < upper  is not fulfilled");
+    let mut f_lower = f(lower);
+    let mut f_upper = f(upper);
+    let mut iterations = 0;
+    let extend_lower = |f_lower: T, f_upper: T| match params.extend_int {
+        ExtendInt::No => false,
+        ExtendInt::Yes => T::zero() < f_lower * f_upper,
+        ExtendInt::Upward => T::zero() < f_lower,
+        ExtendInt::Downward => f_lower < T::zero(),
+    };
+    let extend_upper = |f_lower: T, f_upper: T| match params.extend_int {
+        ExtendInt::No => false,
+        ExtendInt::Yes => T::zero() < f_lower * f_upper,
+        ExtendInt::Upward => f_upper < T::zero(),
+        ExtendInt::Downward => T::zero() < f_upper,
+    };
+    let hundredth = T::from(0.01).unwrap();
+    let delta = |u: T| hundredth * T::from(1e-4).unwrap().max(u.abs());
+    let mut delta = [delta(lower), delta(upper)];
+    while extend_lower(f_lower, f_upper) || extend_upper(f_lower, f_upper) {
+        iterations += 1;
+        assert!(
+            iterations <= params.max_iter,
+            "no sign change found in {} iterations",
+            params.max_iter
+        );
+        if extend_lower(f_lower, f_upper) {
+            lower = lower - delta[0];
+            f_lower = f(lower);
+        }
+        if extend_upper(f_lower, f_upper) {
+            upper = upper + delta[1];
+            f_upper = f(upper);
+        }
+        delta = delta.map(|d| d + d);
+    }
+    assert!(
+        f_lower * f_upper <= T::zero(),
+        "f() values at end points not of opposite sign"
+    );
+    Bracket {
+        interval: [lower, upper],
+        f_interval: [f_lower, f_upper],
+        iterations,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct IterParams<T> {
+    pub start: T,
+    /// stop once successive iterates are this close
+    pub tolerance: T,
+    pub max_iter: usize,
+}
+#[rustfmt::skip]
+impl<T> AsRef<IterParams<T>> for IterParams<T> { fn as_ref(&self) -> &IterParams<T> { self } }
+
+/// `f` returns `(f(x), f'(x))`.
+pub fn newton_raphson<T>(
+    f: impl Fn(T) -> (T, T),
+    params: impl AsRef<IterParams<T>>,
+) -> RootResult<T>
+where
+    T: Float,
+{
+    iterate(params.as_ref(), |x| {
+        let (y, d) = f(x);
+        (y, x - y / d)
+    })
+}
+/// `f` returns `(f(x), f'(x), f''(x))`.
+pub fn halley<T>(f: impl Fn(T) -> (T, T, T), params: impl AsRef<IterParams<T>>) -> RootResult<T>
+where
+    T: Float,
+{
+    let two = T::one() + T::one();
+    iterate(params.as_ref(), |x| {
+        let (y, d1, d2) = f(x);
+        (y, x - two * y * d1 / (two * d1 * d1 - y * d2))
+    })
+}
+/// Iterate `x = g(x)`; `f_root` is the residual `g(x) - x`.
+pub fn fixed_point<T>(g: impl Fn(T) -> T, params: impl AsRef<IterParams<T>>) -> RootResult<T>
+where
+    T: Float,
+{
+    iterate(params.as_ref(), |x| {
+        let y = g(x);
+        (y - x, y)
+    })
+}
+/// `step` maps `x` to `(f(x), next x)`.
+fn iterate<T>(params: &IterParams<T>, step: impl Fn(T) -> (T, T)) -> RootResult<T>
+where
+    T: Float,
+{
+    let mut x = params.start;
+    let mut out = RootResult {
+        root: x,
+        f_root: T::nan(),
+        iterations: 0,
+        estim_prec: T::infinity(),
+        status: RootStatus::MaxIterReached,
+    };
+    for _ in 0..params.max_iter {
+        let (y, next) = step(x);
+        out.iterations += 1;
+        out.root = x;
+        out.f_root = y;
+        if y.is_zero() {
+            out.estim_prec = T::zero();
+            out.status = RootStatus::Converged;
+            return out;
+        }
+        if !next.is_finite() || !y.is_finite() {
+            out.status = RootStatus::Diverged;
+            return out;
+        }
+        out.estim_prec = (next - x).abs();
+        x = next;
+        if out.estim_prec <= params.tolerance {
+            out.status = RootStatus::Converged;
+            break;
+        }
+    }
+    // the residual at the root returned, not at the iterate before it
+    out.root = x;
+    out.f_root = step(x).0;
+    out
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SecantParams<T> {
+    pub start: [T; 2],
+    pub tolerance: T,
+    pub max_iter: usize,
+}
+#[rustfmt::skip]
+impl<T> AsRef<SecantParams<T>> for SecantParams<T> { fn as_ref(&self) -> &SecantParams<T> { self } }
+pub fn secant<T>(f: impl Fn(T) -> T, params: impl AsRef<SecantParams<T>>) -> RootResult<T>
+where
+    T: Float,
+{
+    let params = params.as_ref();
+    let [mut x0, mut x1] = params.start;
+    let mut f0 = f(x0);
+    let mut out = RootResult {
+        root: x1,
+        f_root: f(x1),
+        iterations: 0,
+        estim_prec: (x1 - x0).abs(),
+        status: RootStatus::MaxIterReached,
+    };
+    for _ in 0..params.max_iter {
+        let f1 = out.f_root;
+        if f1.is_zero() {
+            out.estim_prec = T::zero();
+            out.status = RootStatus::Converged;
+            return out;
+        }
+        let x2 = x1 - f1 * (x1 - x0) / (f1 - f0);
+        if !x2.is_finite() {
+            out.status = RootStatus::Diverged;
+            return out;
+        }
+        (x0, f0, x1) = (x1, f1, x2);
+        out.root = x1;
+        out.f_root = f(x1);
+        out.iterations += 1;
+        out.estim_prec = (x1 - x0).abs();
+        if out.estim_prec <= params.tolerance {
+            out.status = RootStatus::Converged;
+            return out;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(x: f64) -> f64 {
+        x.ln() - (-x).exp()
+    }
+    const ROOT: f64 = 1.3097995858041505;
+
+    #[test]
+    fn test_uniroot() {
+        let r = uniroot(f, BracketParams::new(1., 2.));
+        assert!(r.converged());
+        assert!((r.root - ROOT).abs() < 1e-4);
+        assert!(r.estim_prec < 1e-4);
+        #[rustfmt::skip]
+        let r = uniroot(f, BracketParams { tolerance: 1e-12, ..BracketParams::new(1., 2.) });
+        assert!((r.root - ROOT).abs() < 1e-12);
+        // R: uniroot(function(x) x - 10, c(0, 1), extendInt = "yes")
+        #[rustfmt::skip]
+        let r = uniroot(|x: f64| x - 10., BracketParams { extend_int: ExtendInt::Yes, ..BracketParams::new(0., 1.) });
+        assert!((r.root - 10.).abs() < 1e-4);
+        assert!(0 < r.iterations);
+        #[rustfmt::skip]
+        let r = uniroot(|x: f32| 5. - x, BracketParams { extend_int: ExtendInt::Downward, ..BracketParams::new(0., 1.) });
+        assert!((r.root - 5.).abs() < 1e-3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_uniroot_no_sign_change() {
+        uniroot(|x: f64| x - 10., BracketParams::new(0., 1.));
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_bracketing() {
+        let params = BracketParams { tolerance: 1e-10, ..BracketParams::new(1., 2.) };
+        let r = bisection(f, params);
+        assert!(r.converged() && (r.root - ROOT).abs() < 1e-9);
+        let s = regula_falsi(f, params);
+        assert!(s.converged() && (s.root - ROOT).abs() < 1e-9);
+        assert!(s.iterations < r.iterations);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_open_methods() {
+        let params = IterParams { start: 2., tolerance: 1e-12, max_iter: 100 };
+        let r = newton_raphson(|x| (f(x), 1. / x + (-x).exp()), params);
+        assert!(r.converged() && (r.root - ROOT).abs() < 1e-12);
+        assert_eq!(r.f_root, f(r.root));
+        let r = halley(|x| (f(x), 1. / x + (-x).exp(), -1. / x.powi(2) - (-x).exp()), params);
+        assert!(r.converged() && (r.root - ROOT).abs() < 1e-12);
+        let r = fixed_point(|x| (-x).exp().exp(), params);
+        assert!(r.converged() && (r.root - ROOT).abs() < 1e-10);
+        assert_eq!(r.f_root, (-r.root).exp().exp() - r.root);
+        let capped = IterParams { max_iter: 3, ..params };
+        let r = fixed_point(|x| (-x).exp().exp(), capped);
+        assert_eq!(r.status, RootStatus::MaxIterReached);
+        assert_eq!(r.f_root, (-r.root).exp().exp() - r.root);
+        let r = fixed_point(|x| x.ln() - (-x).exp() + x, params);
+        assert!(!r.converged());
+        let r = secant(f, SecantParams { start: [1., 2.], tolerance: 1e-12, max_iter: 100 });
+        assert!(r.converged() && (r.root - ROOT).abs() < 1e-12);
+    }
+}
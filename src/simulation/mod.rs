@@ -0,0 +1,114 @@
+mod queue;
+mod resource;
+mod stats;
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+pub use queue::*;
+pub use resource::*;
+pub use stats::*;
+
+/// Pending events ordered by time; events at the same time come out in the order they were
+/// scheduled.
+#[derive(Debug, Clone)]
+pub struct Calendar<E> {
+    heap: BinaryHeap<Scheduled<E>>,
+    now: f64,
+    scheduled: u64,
+}
+impl<E> Default for Calendar<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<E> Calendar<E> {
+    pub fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            now: 0.,
+            scheduled: 0,
+        }
+    }
+    /// Time of the event popped last.
+    pub fn now(&self) -> f64 {
+        self.now
+    }
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+    pub fn schedule(&mut self, time: f64, event: E) {
+        assert!(
+            self.now <= time,
+            "cannot schedule an event in the past ({time} < {})",
+            self.now
+        );
+        self.heap.push(Scheduled {
+            time,
+            order: self.scheduled,
+            event,
+        });
+        self.scheduled += 1;
+    }
+    pub fn schedule_in(&mut self, delay: f64, event: E) {
+        self.schedule(self.now + delay, event);
+    }
+    pub fn peek_time(&self) -> Option<f64> {
+        self.heap.peek().map(|s| s.time)
+    }
+    /// Remove the next event and advance the clock to it.
+    pub fn pop(&mut self) -> Option<(f64, E)> {
+        let next = self.heap.pop()?;
+        self.now = next.time;
+        Some((next.time, next.event))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Scheduled<E> {
+    time: f64,
+    order: u64,
+    event: E,
+}
+impl<E> PartialEq for Scheduled<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl<E> Eq for Scheduled<E> {}
+impl<E> PartialOrd for Scheduled<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+/// Reversed, so the max-heap pops the earliest event.
+impl<E> Ord for Scheduled<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .time
+            .total_cmp(&self.time)
+            .then(other.order.cmp(&self.order))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calendar() {
+        let mut calendar = Calendar::new();
+        calendar.schedule(2., 'b');
+        calendar.schedule(1., 'a');
+        calendar.schedule(2., 'c');
+        assert_eq!(calendar.len(), 3);
+        assert_eq!(calendar.pop(), Some((1., 'a')));
+        calendar.schedule_in(0.5, 'd');
+        assert_eq!(calendar.peek_time(), Some(1.5));
+        let order: Vec<char> = std::iter::from_fn(|| calendar.pop().map(|(_, e)| e)).collect();
+        assert_eq!(order, ['d', 'b', 'c']);
+        assert_eq!(calendar.now(), 2.);
+        assert!(calendar.is_empty());
+    }
+}
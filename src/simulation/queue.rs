@@ -0,0 +1,127 @@
+use rand::{SeedableRng, distributions::Distribution, rngs::StdRng};
+
+use super::{Calendar, Entity, Resource, TimeWeighted};
+
+#[derive(Debug, Clone)]
+pub struct QueueParams<A, S> {
+    /// distribution of the times between arrivals
+    pub interarrival: A,
+    /// distribution of the service times
+    pub service: S,
+    pub servers: usize,
+    /// arrivals stop and statistics are taken at this time
+    pub horizon: f64,
+    pub seed: u64,
+}
+impl<A, S> QueueParams<A, S> {
+    pub fn new(interarrival: A, service: S, servers: usize, horizon: f64) -> Self {
+        Self {
+            interarrival,
+            service,
+            servers,
+            horizon,
+            seed: 0,
+        }
+    }
+}
+#[rustfmt::skip]
+impl<A, S> AsRef<QueueParams<A, S>> for QueueParams<A, S> { fn as_ref(&self) -> &QueueParams<A, S> { self } }
+
+#[derive(Debug, Clone)]
+pub struct QueueSim {
+    /// time in the queue of each entity that reached a server
+    pub waits: Vec<f64>,
+    /// time from arrival to departure of each entity that left
+    pub system_times: Vec<f64>,
+    pub queue_length: TimeWeighted,
+    pub busy: TimeWeighted,
+    /// time-weighted fraction of the servers in use
+    pub utilisation: f64,
+    /// time-weighted average queue length
+    pub mean_queue_length: f64,
+    pub arrivals: usize,
+}
+
+enum QueueEvent {
+    Arrival,
+    Departure(Entity),
+}
+
+/// Simulate a G/G/c queue: arrivals and service times drawn from the given distributions,
+/// `servers` servers and a first-come first-served queue, until `horizon`.
+pub fn simulate_queue<A, S>(params: impl AsRef<QueueParams<A, S>>) -> QueueSim
+where
+    A: Distribution<f64>,
+    S: Distribution<f64>,
+{
+    let params = params.as_ref();
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    let mut calendar = Calendar::new();
+    let mut servers = Resource::new(params.servers, 0.);
+    let mut system_times = vec![];
+    let mut arrivals = 0;
+    calendar.schedule(params.interarrival.sample(&mut rng), QueueEvent::Arrival);
+    while let Some((now, event)) = calendar.pop() {
+        if params.horizon < now {
+            break;
+        }
+        let started = match event {
+            QueueEvent::Arrival => {
+                let entity = Entity {
+                    id: arrivals,
+                    arrival: now,
+                };
+                arrivals += 1;
+                calendar.schedule_in(params.interarrival.sample(&mut rng), QueueEvent::Arrival);
+                servers.request(now, entity)
+            }
+            QueueEvent::Departure(entity) => {
+                system_times.push(now - entity.arrival);
+                servers.release(now)
+            }
+        };
+        if let Some(entity) = started {
+            let service = params.service.sample(&mut rng);
+            calendar.schedule_in(service, QueueEvent::Departure(entity));
+        }
+    }
+    QueueSim {
+        waits: servers.waits().values().to_vec(),
+        system_times,
+        utilisation: servers.utilisation(params.horizon),
+        mean_queue_length: servers.queue_trace().mean(params.horizon),
+        queue_length: servers.queue_trace().clone(),
+        busy: servers.busy_trace().clone(),
+        arrivals,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use statrs::distribution::Exp;
+
+    use super::*;
+    use crate::vector::mean;
+
+    #[test]
+    fn test_mm1() {
+        // M/M/1 with ρ = 0.8: Wq = ρ / (μ - λ) = 4, Lq = ρ² / (1 - ρ) = 3.2
+        let mut params = QueueParams::new(Exp::new(0.8).unwrap(), Exp::new(1.).unwrap(), 1, 1e5);
+        params.seed = 7;
+        let sim = simulate_queue(&params);
+        assert!((sim.utilisation - 0.8).abs() < 0.02, "{}", sim.utilisation);
+        assert!((mean(&sim.waits) - 4.).abs() < 0.4, "{}", mean(&sim.waits));
+        assert!((sim.mean_queue_length - 3.2).abs() < 0.32);
+        assert!((mean(&sim.system_times) - 5.).abs() < 0.5);
+        // Little's law holds for each run up to the entities still in the system
+        let lambda = sim.arrivals as f64 / 1e5;
+        assert!((sim.mean_queue_length - lambda * mean(&sim.waits)).abs() < 0.05);
+
+        // M/M/2 at the same load per server: Wq = 2 ρ³ / (λ (1 - ρ²)) ≈ 1.78
+        params.interarrival = Exp::new(1.6).unwrap();
+        params.servers = 2;
+        let sim = simulate_queue(&params);
+        assert!((sim.utilisation - 0.8).abs() < 0.02);
+        assert!((mean(&sim.waits) - 2. * 0.512 / (1.6 * 0.36)).abs() < 0.2);
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+
+use super::{Tally, TimeWeighted};
+
+/// Something that moves through the system, stamped with its arrival time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Entity {
+    pub id: usize,
+    pub arrival: f64,
+}
+
+/// `capacity` identical servers with a first-come first-served queue in front of them.
+#[derive(Debug, Clone)]
+pub struct Resource<T = Entity> {
+    capacity: usize,
+    queue: VecDeque<(T, f64)>,
+    busy: TimeWeighted,
+    queue_length: TimeWeighted,
+    waits: Tally,
+}
+impl<T> Resource<T> {
+    pub fn new(capacity: usize, start: f64) -> Self {
+        assert!(0 < capacity, "'capacity' must be positive");
+        Self {
+            capacity,
+            queue: VecDeque::new(),
+            busy: TimeWeighted::new(start, 0.),
+            queue_length: TimeWeighted::new(start, 0.),
+            waits: Tally::new(),
+        }
+    }
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    /// Servers in use.
+    pub fn busy(&self) -> usize {
+        self.busy.value() as usize
+    }
+    pub fn queue_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Seize a server for `entity` if one is free and return it, or queue it and return `None`.
+    pub fn request(&mut self, now: f64, entity: T) -> Option<T> {
+        if self.busy() < self.capacity {
+            self.busy.update(now, (self.busy() + 1) as f64);
+            self.waits.record(0.);
+            return Some(entity);
+        }
+        self.queue.push_back((entity, now));
+        self.queue_length.update(now, self.queue.len() as f64);
+        None
+    }
+    /// Free a server; the entity at the head of the queue, if any, takes it and is returned.
+    pub fn release(&mut self, now: f64) -> Option<T> {
+        assert!(0 < self.busy(), "release of an idle resource");
+        match self.queue.pop_front() {
+            Some((entity, since)) => {
+                self.queue_length.update(now, self.queue.len() as f64);
+                self.waits.record(now - since);
+                Some(entity)
+            }
+            None => {
+                self.busy.update(now, (self.busy() - 1) as f64);
+                None
+            }
+        }
+    }
+
+    /// Time-weighted fraction of the servers in use until `now`.
+    pub fn utilisation(&self, now: f64) -> f64 {
+        self.busy.mean(now) / self.capacity as f64
+    }
+    /// Servers in use over time.
+    pub fn busy_trace(&self) -> &TimeWeighted {
+        &self.busy
+    }
+    /// Queue length over time.
+    pub fn queue_trace(&self) -> &TimeWeighted {
+        &self.queue_length
+    }
+    /// Time each entity spent in the queue, in the order they got a server.
+    pub fn waits(&self) -> &Tally {
+        &self.waits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource() {
+        let mut r = Resource::new(1, 0.);
+        assert_eq!(r.request(0., 'a'), Some('a'));
+        assert_eq!(r.request(1., 'b'), None);
+        assert_eq!(r.request(2., 'c'), None);
+        assert_eq!(r.queue_len(), 2);
+        assert_eq!(r.release(3.), Some('b'));
+        assert_eq!(r.release(4.), Some('c'));
+        assert_eq!(r.release(6.), None);
+        assert_eq!(r.busy(), 0);
+        assert_eq!(r.waits().values(), &[0., 2., 2.]);
+        assert_eq!(r.utilisation(8.), 6. / 8.);
+        assert_eq!(r.queue_trace().mean(8.), (1. + 2. + 1.) / 8.);
+    }
+}
@@ -0,0 +1,102 @@
+/// A piecewise-constant quantity over time, such as a queue length, with its time-weighted
+/// average.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeWeighted {
+    start: f64,
+    area: f64,
+    times: Vec<f64>,
+    values: Vec<f64>,
+}
+impl TimeWeighted {
+    pub fn new(start: f64, value: f64) -> Self {
+        Self {
+            start,
+            area: 0.,
+            times: vec![start],
+            values: vec![value],
+        }
+    }
+    pub fn value(&self) -> f64 {
+        *self.values.last().unwrap()
+    }
+    fn last_time(&self) -> f64 {
+        *self.times.last().unwrap()
+    }
+    pub fn update(&mut self, now: f64, value: f64) {
+        let last = self.last_time();
+        assert!(last <= now, "time went backwards ({now} < {last})");
+        self.area += self.value() * (now - last);
+        if now == last {
+            *self.values.last_mut().unwrap() = value;
+        } else {
+            self.times.push(now);
+            self.values.push(value);
+        }
+    }
+    /// Time-weighted average from the start until `now`.
+    pub fn mean(&self, now: f64) -> f64 {
+        let last = self.last_time();
+        assert!(last <= now, "time went backwards ({now} < {last})");
+        let area = self.area + self.value() * (now - last);
+        if now == self.start {
+            return self.value();
+        }
+        area / (now - self.start)
+    }
+    /// Times at which the value changed, starting with the start time.
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+    /// The value from each of `times` on.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+/// Observations such as waiting times, kept in the order they were recorded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tally {
+    values: Vec<f64>,
+}
+impl Tally {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn record(&mut self, value: f64) {
+        self.values.push(value);
+    }
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+    pub fn into_values(self) -> Vec<f64> {
+        self.values
+    }
+}
+impl AsRef<[f64]> for Tally {
+    fn as_ref(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_weighted() {
+        let mut q = TimeWeighted::new(0., 0.);
+        q.update(1., 2.);
+        q.update(1., 3.);
+        q.update(4., 1.);
+        assert_eq!(q.mean(6.), (3. * 3. + 2. * 1.) / 6.);
+        assert_eq!(q.times(), &[0., 1., 4.]);
+        assert_eq!(q.values(), &[0., 3., 1.]);
+        assert_eq!(TimeWeighted::new(2., 5.).mean(2.), 5.);
+    }
+}
@@ -0,0 +1,373 @@
+use rand::{Rng, SeedableRng, distributions::Distribution, rngs::StdRng};
+use statrs::distribution::Normal;
+
+use crate::matrix::{FillOrdering, MatrixBuf, matrix};
+
+#[derive(Debug, Clone, Copy)]
+pub struct PathParams {
+    /// steps of the time grid `k · horizon / steps`
+    pub steps: usize,
+    pub horizon: f64,
+    pub paths: usize,
+    pub seed: u64,
+}
+impl PathParams {
+    pub fn new(steps: usize, horizon: f64) -> Self {
+        Self {
+            steps,
+            horizon,
+            paths: 1,
+            seed: 0,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<PathParams> for PathParams { fn as_ref(&self) -> &PathParams { self } }
+
+/// Paths sampled on a common time grid.
+#[derive(Debug, Clone)]
+pub struct Paths {
+    pub times: Vec<f64>,
+    /// one path per column, one time per row
+    pub values: MatrixBuf<f64>,
+}
+impl Paths {
+    /// Build the paths one at a time with `path(rng, dt)`, each returning the values on the grid.
+    fn generate(params: &PathParams, mut path: impl FnMut(&mut StdRng, f64) -> Vec<f64>) -> Self {
+        assert!(0 < params.steps, "'steps' must be positive");
+        assert!(0 < params.paths, "'paths' must be positive");
+        assert!(
+            0. < params.horizon && params.horizon.is_finite(),
+            "'horizon' must be positive and finite"
+        );
+        let dt = params.horizon / params.steps as f64;
+        let times = (0..=params.steps).map(|k| k as f64 * dt).collect();
+        let mut rng = StdRng::seed_from_u64(params.seed);
+        let columns: Vec<Vec<f64>> = (0..params.paths).map(|_| path(&mut rng, dt)).collect();
+        let data: Vec<f64> = (0..=params.steps)
+            .flat_map(|k| columns.iter().map(move |c| c[k]))
+            .collect();
+        Self {
+            times,
+            values: matrix(
+                data,
+                [params.paths, params.steps + 1],
+                FillOrdering::RowByRow,
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WalkBoundary {
+    Free,
+    /// a step that would leave `[lower, upper]` goes the other way instead
+    Reflecting {
+        lower: f64,
+        upper: f64,
+    },
+    /// the walk stops on reaching `lower` or `upper`
+    Absorbing {
+        lower: f64,
+        upper: f64,
+    },
+}
+/// Random walk from `start` with steps `+1` with probability `p` and `-1` otherwise, one per
+/// grid point. Boundaries must be a whole number of steps from `start`.
+pub fn random_walk(
+    p: f64,
+    start: f64,
+    boundary: WalkBoundary,
+    params: impl AsRef<PathParams>,
+) -> Paths {
+    assert!((0. ..=1.).contains(&p), "'p' must be in [0, 1]");
+    if let WalkBoundary::Reflecting { lower, upper } | WalkBoundary::Absorbing { lower, upper } =
+        boundary
+    {
+        assert!(
+            lower <= start && start <= upper,
+            "'start' must lie within the boundaries"
+        );
+        // on the lattice of the walk, so it meets a boundary exactly rather than stepping over it
+        let on_lattice = |b: f64| b.is_infinite() || (b - start).fract() == 0.;
+        assert!(
+            on_lattice(lower) && on_lattice(upper),
+            "the boundaries must be a whole number of steps from 'start'"
+        );
+        if let WalkBoundary::Reflecting { .. } = boundary {
+            assert!(
+                1. <= upper - lower,
+                "the boundaries must be at least a step apart"
+            );
+        }
+    }
+    let params = params.as_ref();
+    Paths::generate(params, |rng, _| {
+        let mut x = start;
+        let mut path = vec![x];
+        for _ in 0..params.steps {
+            let step = if rng.r#gen::<f64>() < p { 1. } else { -1. };
+            x = match boundary {
+                WalkBoundary::Free => x + step,
+                WalkBoundary::Reflecting { lower, upper } => {
+                    if upper < x + step || x + step < lower {
+                        x - step
+                    } else {
+                        x + step
+                    }
+                }
+                WalkBoundary::Absorbing { lower, upper } => {
+                    if x <= lower || upper <= x {
+                        x
+                    } else {
+                        x + step
+                    }
+                }
+            };
+            path.push(x);
+        }
+        path
+    })
+}
+
+/// Independent normal increments, scaled to variance `dt`, for each step of a path.
+fn increments(rng: &mut StdRng, steps: usize, dt: f64) -> impl Iterator<Item = f64> + '_ {
+    let normal = Normal::new(0., dt.sqrt()).unwrap();
+    (0..steps).map(move |_| normal.sample(rng))
+}
+/// `x0 + μ t + σ W_t` for standard Brownian motion `W`.
+pub fn brownian_motion(mu: f64, sigma: f64, x0: f64, params: impl AsRef<PathParams>) -> Paths {
+    let params = params.as_ref();
+    Paths::generate(params, |rng, dt| {
+        let mut x = x0;
+        let mut path = vec![x];
+        for dw in increments(rng, params.steps, dt) {
+            x += mu * dt + sigma * dw;
+            path.push(x);
+        }
+        path
+    })
+}
+/// `s0 exp((μ - σ²/2) t + σ W_t)`, sampled exactly on the grid.
+pub fn geometric_brownian_motion(
+    mu: f64,
+    sigma: f64,
+    s0: f64,
+    params: impl AsRef<PathParams>,
+) -> Paths {
+    let params = params.as_ref();
+    let drift = mu - sigma * sigma / 2.;
+    Paths::generate(params, |rng, dt| {
+        let mut log_s = 0.;
+        let mut path = vec![s0];
+        for dw in increments(rng, params.steps, dt) {
+            log_s += drift * dt + sigma * dw;
+            path.push(s0 * log_s.exp());
+        }
+        path
+    })
+}
+/// Brownian motion scaled by `sigma` and pinned to `a` at time 0 and `b` at the horizon:
+/// `a + (b - a) t / T + σ (W_t - t / T W_T)`.
+pub fn brownian_bridge(a: f64, b: f64, sigma: f64, params: impl AsRef<PathParams>) -> Paths {
+    let params = params.as_ref();
+    let horizon = params.horizon;
+    Paths::generate(params, |rng, dt| {
+        let mut w = vec![0.];
+        for dw in increments(rng, params.steps, dt) {
+            w.push(w.last().unwrap() + dw);
+        }
+        let w_end = *w.last().unwrap();
+        let mut path: Vec<f64> = w
+            .iter()
+            .enumerate()
+            .map(|(k, w)| {
+                let s = k as f64 * dt / horizon;
+                a + (b - a) * s + sigma * (w - s * w_end)
+            })
+            .collect();
+        // exact endpoints despite rounding in `s`
+        *path.last_mut().unwrap() = b;
+        path
+    })
+}
+/// `dX = θ (μ - X) dt + σ dW` from `x0`, sampled exactly on the grid.
+pub fn ornstein_uhlenbeck(
+    theta: f64,
+    mu: f64,
+    sigma: f64,
+    x0: f64,
+    params: impl AsRef<PathParams>,
+) -> Paths {
+    assert!(0. < theta, "'theta' must be positive");
+    let params = params.as_ref();
+    Paths::generate(params, |rng, dt| {
+        let decay = (-theta * dt).exp();
+        // standard deviation of X_{t + dt} given X_t, over that of an increment of W over dt
+        let scale = sigma * ((1. - decay * decay) / (2. * theta * dt)).sqrt();
+        let mut x = x0;
+        let mut path = vec![x];
+        for dw in increments(rng, params.steps, dt) {
+            x = mu + (x - mu) * decay + scale * dw;
+            path.push(x);
+        }
+        path
+    })
+}
+
+/// Event times on `[0, horizon]` of a Poisson process with constant `rate`.
+pub fn poisson_process(rate: f64, horizon: f64, seed: u64) -> Vec<f64> {
+    nonhomogeneous_poisson_process(|_| rate, rate, horizon, seed)
+}
+/// Event times on `[0, horizon]` of a Poisson process with intensity `rate(t) <= rate_max`, by
+/// thinning a homogeneous process with rate `rate_max`.
+///
+/// ref: Lewis and Shedler (1979), <https://doi.org/10.1002/nav.3800260304>
+pub fn nonhomogeneous_poisson_process(
+    rate: impl Fn(f64) -> f64,
+    rate_max: f64,
+    horizon: f64,
+    seed: u64,
+) -> Vec<f64> {
+    assert!(
+        0. <= rate_max && rate_max.is_finite(),
+        "'rate_max' must be non-negative and finite"
+    );
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut times = vec![];
+    if rate_max == 0. {
+        return times;
+    }
+    let mut t = 0.;
+    loop {
+        t += -(1. - rng.r#gen::<f64>()).ln() / rate_max;
+        if horizon < t {
+            return times;
+        }
+        let r = rate(t);
+        assert!(
+            (0. ..=rate_max).contains(&r),
+            "rate({t}) = {r} is outside [0, rate_max]"
+        );
+        if rng.r#gen::<f64>() * rate_max < r {
+            times.push(t);
+        }
+    }
+}
+/// The counting process `N(t)` of `events` as a step function: `(times, counts)` starting from
+/// `(0, 0)`, ready for `plot`.
+pub fn counting_path(events: impl AsRef<[f64]>) -> (Vec<f64>, Vec<f64>) {
+    let events = events.as_ref();
+    let times = std::iter::once(0.).chain(events.iter().copied()).collect();
+    let counts = (0..=events.len()).map(|n| n as f64).collect();
+    (times, counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        matrix::{column, entry},
+        vector::{mean, var},
+    };
+
+    /// Values at grid point `k` across the paths.
+    fn row(paths: &Paths, k: usize) -> Vec<f64> {
+        (0..paths.values.dim()[0])
+            .map(|j| entry(&paths.values, [j, k]))
+            .collect()
+    }
+
+    #[test]
+    fn test_random_walk() {
+        let mut params = PathParams::new(200, 200.);
+        params.paths = 50;
+        let free = random_walk(0.5, 0., WalkBoundary::Free, params);
+        assert_eq!(free.values.dim(), [50, 201]);
+        assert_eq!(free.times[1], 1.);
+        let path = column(&free.values, 0);
+        assert!(path.windows(2).all(|w| (w[1] - w[0]).abs() == 1.));
+
+        let reflecting = WalkBoundary::Reflecting {
+            lower: 0.,
+            upper: 3.,
+        };
+        let walk = random_walk(0.5, 0., reflecting, params);
+        for j in 0..50 {
+            let path = column(&walk.values, j);
+            assert!(path.iter().all(|x| (0. ..=3.).contains(x)));
+            assert!(path.windows(2).all(|w| (w[1] - w[0]).abs() == 1.));
+        }
+
+        let absorbing = WalkBoundary::Absorbing {
+            lower: 0.,
+            upper: 4.,
+        };
+        let walk = random_walk(0.5, 2., absorbing, params);
+        for j in 0..50 {
+            let path = column(&walk.values, j);
+            let end = *path.last().unwrap();
+            // 200 fair steps from 2 are all but certain to be absorbed
+            assert!(end == 0. || end == 4.);
+            let hit = path.iter().position(|&x| x == end).unwrap();
+            assert!(path[hit..].iter().all(|&x| x == end));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the boundaries must be a whole number of steps from 'start'")]
+    fn test_random_walk_off_lattice() {
+        let reflecting = WalkBoundary::Reflecting {
+            lower: 0.,
+            upper: 1.5,
+        };
+        random_walk(0.5, 0.75, reflecting, PathParams::new(10, 10.));
+    }
+
+    #[test]
+    fn test_brownian() {
+        let mut params = PathParams::new(10, 2.);
+        params.paths = 20_000;
+        params.seed = 5;
+        let bm = brownian_motion(0.5, 2., 1., params);
+        let end = row(&bm, 10);
+        assert!((mean(&end) - 2.).abs() < 0.06);
+        assert!((var(&end) - 8.).abs() < 0.25);
+
+        let gbm = geometric_brownian_motion(0.1, 0.2, 100., params);
+        assert!((mean(row(&gbm, 10)) - 100. * 0.2_f64.exp()).abs() < 0.5);
+
+        let bridge = brownian_bridge(1., -1., 1., params);
+        assert!(row(&bridge, 0).iter().all(|&x| x == 1.));
+        assert!(row(&bridge, 10).iter().all(|&x| x == -1.));
+        // Var(B_t) = t (T - t) / T = 0.5 at the midpoint
+        let mid = row(&bridge, 5);
+        assert!(mean(&mid).abs() < 0.02);
+        assert!((var(&mid) - 0.5).abs() < 0.02);
+
+        // started from the stationary mean, the variance approaches σ² / (2θ) = 0.25
+        let mut params = PathParams::new(50, 10.);
+        params.paths = 20_000;
+        let ou = ornstein_uhlenbeck(2., 3., 1., 3., params);
+        let end = row(&ou, 50);
+        assert!((mean(&end) - 3.).abs() < 0.02);
+        assert!((var(&end) - 0.25).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_poisson() {
+        let times = poisson_process(2., 10_000., 1);
+        assert!(times.windows(2).all(|w| w[0] < w[1]));
+        assert!(((times.len() as f64) - 20_000.).abs() < 3. * 20_000_f64.sqrt());
+        assert_eq!(times, poisson_process(2., 10_000., 1));
+        // ∫_0^T (1 + sin t) dt ≈ T
+        let times = nonhomogeneous_poisson_process(|t| 1. + t.sin(), 2., 10_000., 2);
+        assert!(((times.len() as f64) - 10_000.).abs() < 3. * 10_000_f64.sqrt());
+        // events cluster where the intensity is high
+        let high = times.iter().filter(|t| t.sin() > 0.).count();
+        assert!(high as f64 > 0.75 * times.len() as f64);
+        let (t, n) = counting_path(&times[..3]);
+        assert_eq!(t[0], 0.);
+        assert_eq!(n, [0., 1., 2., 3.]);
+    }
+}
@@ -0,0 +1,445 @@
+mod parse;
+pub use parse::*;
+
+use std::fmt;
+
+use crate::vector;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Num(f64),
+    Var(String),
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Pow(Box<Expr>, Box<Expr>),
+    Call(Func, Box<Expr>),
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Func {
+    Exp,
+    Log,
+    Sqrt,
+    Sin,
+    Cos,
+    Tan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Asin,
+    Acos,
+    Atan,
+    Lgamma,
+    Digamma,
+    Trigamma,
+    /// `psigamma(x, deriv)`, the derivative of `digamma` of the given order
+    Psigamma(u32),
+}
+#[rustfmt::skip]
+impl Func {
+    const ALL: [Func; 16] = [
+        Func::Exp, Func::Log, Func::Sqrt, Func::Sin, Func::Cos, Func::Tan, Func::Sinh, Func::Cosh,
+        Func::Tanh, Func::Asin, Func::Acos, Func::Atan, Func::Lgamma, Func::Digamma, Func::Trigamma,
+        Func::Psigamma(0),
+    ];
+    pub fn name(self) -> &'static str {
+        match self {
+            Func::Exp => "exp", Func::Log => "log", Func::Sqrt => "sqrt",
+            Func::Sin => "sin", Func::Cos => "cos", Func::Tan => "tan",
+            Func::Sinh => "sinh", Func::Cosh => "cosh", Func::Tanh => "tanh",
+            Func::Asin => "asin", Func::Acos => "acos", Func::Atan => "atan",
+            Func::Lgamma => "lgamma", Func::Digamma => "digamma", Func::Trigamma => "trigamma",
+            Func::Psigamma(_) => "psigamma",
+        } }
+    pub fn from_name(name: &str) -> Option<Func> {
+        Self::ALL.into_iter().find(|f| f.name() == name) }
+    pub fn apply(self, x: f64) -> f64 {
+        match self {
+            Func::Exp => x.exp(), Func::Log => x.ln(), Func::Sqrt => x.sqrt(),
+            Func::Sin => x.sin(), Func::Cos => x.cos(), Func::Tan => x.tan(),
+            Func::Sinh => x.sinh(), Func::Cosh => x.cosh(), Func::Tanh => x.tanh(),
+            Func::Asin => x.asin(), Func::Acos => x.acos(), Func::Atan => x.atan(),
+            Func::Lgamma => crate::special::lgamma(x), Func::Digamma => crate::special::digamma(x),
+            Func::Trigamma => crate::special::trigamma(x),
+            Func::Psigamma(deriv) => crate::special::psigamma(x, deriv),
+        } }
+    fn apply_vec(self, x: &[f64]) -> Vec<f64> {
+        match self {
+            Func::Exp => vector::exp(x), Func::Log => vector::ln(x), Func::Sqrt => vector::sqrt(x),
+            Func::Sin => vector::sin(x), Func::Cos => vector::cos(x), Func::Tan => vector::tan(x),
+            Func::Sinh => vector::sinh(x), Func::Cosh => vector::cosh(x), Func::Tanh => vector::tanh(x),
+            Func::Asin => vector::asin(x), Func::Acos => vector::acos(x), Func::Atan => vector::atan(x),
+            Func::Lgamma => vector::lgamma(x), Func::Digamma => vector::digamma(x),
+            Func::Trigamma => vector::trigamma(x),
+            Func::Psigamma(deriv) => vector::psigamma(x, deriv),
+        } }
+}
+
+/// Deparse the way R prints calls: spaces around `+ - *`, none around `/ ^`, and only the
+/// parentheses precedence requires.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operand = |f: &mut fmt::Formatter<'_>, e: &Expr, parens: bool| {
+            if parens {
+                write!(f, "({e})")
+            } else {
+                write!(f, "{e}")
+            }
+        };
+        let p = precedence(self);
+        match self {
+            Expr::Num(x) => write!(f, "{x}"),
+            Expr::Var(name) => write!(f, "{name}"),
+            Expr::Neg(a) => {
+                write!(f, "-")?;
+                operand(f, a, precedence(a) < p)
+            }
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) | Expr::Div(a, b) => {
+                let op = match self {
+                    Expr::Add(..) => " + ",
+                    Expr::Sub(..) => " - ",
+                    Expr::Mul(..) => " * ",
+                    _ => "/",
+                };
+                operand(f, a, precedence(a) < p)?;
+                write!(f, "{op}")?;
+                operand(f, b, precedence(b) <= p)
+            }
+            Expr::Pow(a, b) => {
+                operand(f, a, precedence(a) <= p)?;
+                write!(f, "^")?;
+                operand(f, b, precedence(b) < p)
+            }
+            Expr::Call(Func::Psigamma(deriv), a) => write!(f, "psigamma({a}, {deriv}L)"),
+            Expr::Call(func, a) => write!(f, "{}({a})", func.name()),
+        }
+    }
+}
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Add(..) | Expr::Sub(..) => 1,
+        Expr::Mul(..) | Expr::Div(..) => 2,
+        Expr::Neg(_) => 3,
+        Expr::Num(x) if x.is_sign_negative() => 3,
+        Expr::Pow(..) => 4,
+        Expr::Num(_) | Expr::Var(_) | Expr::Call(..) => 5,
+    }
+}
+
+/// Names of the variables in order of first appearance; R's `all.vars()`.
+pub fn variables(expr: &Expr) -> Vec<String> {
+    fn walk(expr: &Expr, out: &mut Vec<String>) {
+        match expr {
+            Expr::Num(_) => (),
+            Expr::Var(name) => {
+                if !out.contains(name) {
+                    out.push(name.clone());
+                }
+            }
+            Expr::Neg(a) | Expr::Call(_, a) => walk(a, out),
+            Expr::Add(a, b)
+            | Expr::Sub(a, b)
+            | Expr::Mul(a, b)
+            | Expr::Div(a, b)
+            | Expr::Pow(a, b) => {
+                walk(a, out);
+                walk(b, out);
+            }
+        }
+    }
+    let mut out = vec![];
+    walk(expr, &mut out);
+    out
+}
+fn depends(expr: &Expr, name: &str) -> bool {
+    variables(expr).iter().any(|v| v == name)
+}
+
+/// Fold numeric constants and drop the identities `0 + a`, `1 * a`, `0 * a`, `a / 1`, `a^1`,
+/// `a^0` and double negation, as R's `D()` does when it builds its result.
+pub fn simplify(expr: &Expr) -> Expr {
+    match expr {
+        Expr::Num(_) | Expr::Var(_) => expr.clone(),
+        Expr::Neg(a) => neg(simplify(a)),
+        Expr::Add(a, b) => add(simplify(a), simplify(b)),
+        Expr::Sub(a, b) => sub(simplify(a), simplify(b)),
+        Expr::Mul(a, b) => mul(simplify(a), simplify(b)),
+        Expr::Div(a, b) => div(simplify(a), simplify(b)),
+        Expr::Pow(a, b) => pow(simplify(a), simplify(b)),
+        Expr::Call(func, a) => call(*func, simplify(a)),
+    }
+}
+fn num(x: f64) -> Expr {
+    Expr::Num(x)
+}
+fn neg(a: Expr) -> Expr {
+    match a {
+        Expr::Num(x) => num(-x),
+        Expr::Neg(a) => *a,
+        a => Expr::Neg(Box::new(a)),
+    }
+}
+fn add(a: Expr, b: Expr) -> Expr {
+    match (a, b) {
+        (Expr::Num(x), Expr::Num(y)) => num(x + y),
+        (Expr::Num(0.), b) => b,
+        (a, Expr::Num(0.)) => a,
+        (a, Expr::Neg(b)) => sub(a, *b),
+        (a, Expr::Num(y)) if y < 0. => sub(a, num(-y)),
+        (Expr::Neg(a), b) => sub(b, *a),
+        (a, b) => Expr::Add(Box::new(a), Box::new(b)),
+    }
+}
+fn sub(a: Expr, b: Expr) -> Expr {
+    match (a, b) {
+        (Expr::Num(x), Expr::Num(y)) => num(x - y),
+        (a, Expr::Num(0.)) => a,
+        (Expr::Num(0.), b) => neg(b),
+        (a, Expr::Neg(b)) => add(a, *b),
+        (a, Expr::Num(y)) if y < 0. => add(a, num(-y)),
+        (a, b) => Expr::Sub(Box::new(a), Box::new(b)),
+    }
+}
+fn mul(a: Expr, b: Expr) -> Expr {
+    match (a, b) {
+        (Expr::Num(x), Expr::Num(y)) => num(x * y),
+        (Expr::Num(0.), _) | (_, Expr::Num(0.)) => num(0.),
+        (Expr::Num(1.), b) => b,
+        (a, Expr::Num(1.)) => a,
+        (Expr::Num(y), b) if y < 0. => neg(mul(num(-y), b)),
+        (a, Expr::Num(y)) if y < 0. => neg(mul(a, num(-y))),
+        (Expr::Neg(a), b) => neg(mul(*a, b)),
+        (a, Expr::Neg(b)) => neg(mul(a, *b)),
+        (a, b) => Expr::Mul(Box::new(a), Box::new(b)),
+    }
+}
+fn div(a: Expr, b: Expr) -> Expr {
+    match (a, b) {
+        (Expr::Num(0.), _) => num(0.),
+        (a, Expr::Num(1.)) => a,
+        (Expr::Num(y), b) if y < 0. => neg(div(num(-y), b)),
+        (Expr::Neg(a), b) => neg(div(*a, b)),
+        (a, Expr::Neg(b)) => neg(div(a, *b)),
+        (a, b) => Expr::Div(Box::new(a), Box::new(b)),
+    }
+}
+fn pow(a: Expr, b: Expr) -> Expr {
+    match (a, b) {
+        (Expr::Num(x), Expr::Num(n)) if n.fract() == 0. => num(x.powf(n)),
+        (_, Expr::Num(0.)) => num(1.),
+        (a, Expr::Num(1.)) => a,
+        (Expr::Num(1.), _) => num(1.),
+        (a, b) => Expr::Pow(Box::new(a), Box::new(b)),
+    }
+}
+fn call(func: Func, a: Expr) -> Expr {
+    Expr::Call(func, Box::new(a))
+}
+
+/// Symbolic derivative of `expr` with respect to `name`; R's `D()`.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/src/deriv.c>
+pub fn d(expr: &Expr, name: &str) -> Expr {
+    match expr {
+        Expr::Num(_) => num(0.),
+        Expr::Var(v) => num(if v == name { 1. } else { 0. }),
+        Expr::Neg(a) => neg(d(a, name)),
+        Expr::Add(a, b) => add(d(a, name), d(b, name)),
+        Expr::Sub(a, b) => sub(d(a, name), d(b, name)),
+        Expr::Mul(a, b) => add(mul(d(a, name), simplify(b)), mul(simplify(a), d(b, name))),
+        Expr::Div(a, b) => {
+            let (a, b, da, db) = (simplify(a), simplify(b), d(a, name), d(b, name));
+            sub(div(da, b.clone()), div(mul(a, db), pow(b, num(2.))))
+        }
+        Expr::Pow(a, b) => {
+            let (da, db) = (d(a, name), d(b, name));
+            let (a, b) = (simplify(a), simplify(b));
+            let power_rule = |da| mul(mul(b.clone(), pow(a.clone(), sub(b.clone(), num(1.)))), da);
+            let exponential_rule = |db| {
+                mul(
+                    mul(pow(a.clone(), b.clone()), call(Func::Log, a.clone())),
+                    db,
+                )
+            };
+            match (depends(&a, name), depends(&b, name)) {
+                (_, false) => power_rule(da),
+                (false, true) => exponential_rule(db),
+                (true, true) => add(power_rule(da), exponential_rule(db)),
+            }
+        }
+        Expr::Call(func, a) => {
+            let da = d(a, name);
+            let a = simplify(a);
+            match func {
+                Func::Exp => mul(call(Func::Exp, a), da),
+                Func::Log => div(da, a),
+                Func::Sqrt => div(da, mul(num(2.), call(Func::Sqrt, a))),
+                Func::Sin => mul(call(Func::Cos, a), da),
+                Func::Cos => neg(mul(call(Func::Sin, a), da)),
+                Func::Tan => div(da, pow(call(Func::Cos, a), num(2.))),
+                Func::Sinh => mul(call(Func::Cosh, a), da),
+                Func::Cosh => mul(call(Func::Sinh, a), da),
+                Func::Tanh => div(da, pow(call(Func::Cosh, a), num(2.))),
+                Func::Asin => div(da, call(Func::Sqrt, sub(num(1.), pow(a, num(2.))))),
+                Func::Acos => neg(div(da, call(Func::Sqrt, sub(num(1.), pow(a, num(2.)))))),
+                Func::Atan => div(da, add(num(1.), pow(a, num(2.)))),
+                Func::Lgamma => mul(da, call(Func::Digamma, a)),
+                Func::Digamma => mul(da, call(Func::Trigamma, a)),
+                Func::Trigamma => mul(da, call(Func::Psigamma(2), a)),
+                Func::Psigamma(deriv) => mul(da, call(Func::Psigamma(deriv + 1), a)),
+            }
+        }
+    }
+}
+
+pub type Compiled = Box<dyn Fn(&[f64]) -> f64>;
+pub type CompiledGradient = Box<dyn Fn(&[f64]) -> (f64, Vec<f64>)>;
+
+/// Turn `expr` into a closure over the values of `vars`, resolving names once up front.
+pub fn compile(expr: &Expr, vars: &[&str]) -> Compiled {
+    let binary = |a: &Expr, b: &Expr, op: fn(f64, f64) -> f64| -> Compiled {
+        let (a, b) = (compile(a, vars), compile(b, vars));
+        Box::new(move |x| op(a(x), b(x)))
+    };
+    match expr {
+        &Expr::Num(v) => Box::new(move |_| v),
+        Expr::Var(name) => {
+            let i = index_of(name, vars);
+            Box::new(move |x| x[i])
+        }
+        Expr::Neg(a) => {
+            let a = compile(a, vars);
+            Box::new(move |x| -a(x))
+        }
+        Expr::Add(a, b) => binary(a, b, |a, b| a + b),
+        Expr::Sub(a, b) => binary(a, b, |a, b| a - b),
+        Expr::Mul(a, b) => binary(a, b, |a, b| a * b),
+        Expr::Div(a, b) => binary(a, b, |a, b| a / b),
+        Expr::Pow(a, b) => binary(a, b, f64::powf),
+        &Expr::Call(func, ref a) => {
+            let a = compile(a, vars);
+            Box::new(move |x| func.apply(a(x)))
+        }
+    }
+}
+/// Closure returning the value and the gradient with respect to `vars`; R's `deriv()`.
+pub fn deriv(expr: &Expr, vars: &[&str]) -> CompiledGradient {
+    let value = compile(expr, vars);
+    let gradient: Vec<Compiled> = vars
+        .iter()
+        .map(|name| compile(&d(expr, name), vars))
+        .collect();
+    Box::new(move |x| (value(x), gradient.iter().map(|g| g(x)).collect()))
+}
+/// Evaluate `expr` elementwise over whole vectors, recycling shorter ones as R does.
+pub fn eval<Slice>(expr: &Expr, vars: &[&str], values: impl AsRef<[Slice]>) -> Vec<f64>
+where
+    Slice: AsRef<[f64]>,
+{
+    let values = values.as_ref();
+    assert_eq!(vars.len(), values.len());
+    let eval = |e| eval(e, vars, values);
+    match expr {
+        Expr::Num(v) => vec![*v],
+        Expr::Var(name) => values[index_of(name, vars)].as_ref().to_vec(),
+        Expr::Neg(a) => vector::neg(eval(a)),
+        Expr::Add(a, b) => vector::add(eval(a), eval(b)),
+        Expr::Sub(a, b) => vector::sub(eval(a), eval(b)),
+        Expr::Mul(a, b) => vector::mul(eval(a), eval(b)),
+        Expr::Div(a, b) => vector::div(eval(a), eval(b)),
+        Expr::Pow(a, b) => vector::pow(eval(a), eval(b)),
+        Expr::Call(func, a) => func.apply_vec(&eval(a)),
+    }
+}
+fn index_of(name: &str, vars: &[&str]) -> usize {
+    vars.iter()
+        .position(|v| *v == name)
+        .unwrap_or_else(|| panic!("object '{name}' not found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_display() {
+        let e = parse("log(x) - exp(-x)");
+        assert_eq!(e.to_string(), "log(x) - exp(-x)");
+        assert_eq!(parse("-x^2").to_string(), "-x^2");
+        assert_eq!(parse("(-x)^2").to_string(), "(-x)^2");
+        assert_eq!(parse("2^3^2").to_string(), "2^3^2");
+        assert_eq!(parse("(2^3)^2").to_string(), "(2^3)^2");
+        assert_eq!(
+            parse("a - (b - c) / (d * e)").to_string(),
+            "a - (b - c)/(d * e)"
+        );
+        assert_eq!(parse("1.5e-3*x").to_string(), "0.0015 * x");
+        assert_eq!(variables(&parse("x * y + sin(x)")), vec!["x", "y"]);
+        assert_eq!(
+            simplify(&parse("0 * y + 1 * x^1 - -2")).to_string(),
+            "x + 2"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "could not find function \"foo\"")]
+    fn test_parse_unknown_function() {
+        parse("foo(x)");
+    }
+
+    #[test]
+    fn test_d() {
+        // R: D(quote(...), "x")
+        let cases = [
+            ("log(x) - exp(-x)", "1/x + exp(-x)"),
+            ("x^2", "2 * x"),
+            ("sin(2 * x)", "cos(2 * x) * 2"),
+            ("cos(x)", "-sin(x)"),
+            ("sqrt(1 - x)", "-(1/(2 * sqrt(1 - x)))"),
+            ("x/y", "1/y"),
+            ("y/x", "-(y/x^2)"),
+            ("2^x", "2^x * log(2)"),
+            ("x^x", "x * x^(x - 1) + x^x * log(x)"),
+            ("atan(x)", "1/(1 + x^2)"),
+            ("digamma(x)", "trigamma(x)"),
+            ("trigamma(x)", "psigamma(x, 2L)"),
+            ("psigamma(x, 2L)", "psigamma(x, 3L)"),
+            ("psigamma(2 * x)", "2 * psigamma(2 * x, 1L)"),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(d(&parse(expr), "x").to_string(), expected, "{expr}");
+        }
+    }
+
+    #[test]
+    fn test_compile_eval() {
+        let e = parse("log(x) - exp(-x)");
+        let f = compile(&e, &["x"]);
+        assert_eq!(f(&[2.]), 2_f64.ln() - (-2_f64).exp());
+        let g = deriv(&parse("x^2 * y + sin(y)"), &["x", "y"]);
+        let (value, gradient) = g(&[3., 0.5]);
+        assert_eq!(value, 4.5 + 0.5_f64.sin());
+        assert_eq!(gradient, vec![3., 9. + 0.5_f64.cos()]);
+        let g = deriv(&parse("trigamma(x)"), &["x"]);
+        assert_eq!(g(&[2.5]).1, vec![crate::special::psigamma(2.5, 2)]);
+        let v = eval(
+            &parse("x * y + 1"),
+            &["x", "y"],
+            [vec![1., 2., 3., 4.], vec![10., 20.]],
+        );
+        assert_eq!(v, vec![11., 41., 31., 81.]);
+        // root of the bisection example from its symbolic derivative
+        let df = compile(&d(&e, "x"), &["x"]);
+        let a = crate::roots::newton_raphson(
+            |x| (f(&[x]), df(&[x])),
+            crate::roots::IterParams {
+                start: 2.,
+                tolerance: 1e-10,
+                max_iter: 100,
+            },
+        );
+        assert!((a.root - 1.30979958580415).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,168 @@
+use super::{Expr, Func};
+
+/// Parse arithmetic in R syntax: numbers, variables, `+ - * / ^`, unary minus, parentheses and
+/// calls to the functions in [`Func`].
+pub fn parse(text: &str) -> Expr {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        pos: 0,
+    };
+    let expr = parser.sum();
+    parser.skip_space();
+    if let Some(c) = parser.peek() {
+        panic!("unexpected '{c}'");
+    }
+    expr
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+    fn skip_space(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+    /// Consume `c` if it is the next non-space character.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_space();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+    fn expect(&mut self, c: char) {
+        if !self.eat(c) {
+            match self.peek() {
+                Some(found) => panic!("unexpected '{found}'"),
+                None => panic!("unexpected end of input"),
+            }
+        }
+    }
+
+    fn sum(&mut self) -> Expr {
+        let mut lhs = self.product();
+        loop {
+            if self.eat('+') {
+                lhs = Expr::Add(Box::new(lhs), Box::new(self.product()));
+            } else if self.eat('-') {
+                lhs = Expr::Sub(Box::new(lhs), Box::new(self.product()));
+            } else {
+                return lhs;
+            }
+        }
+    }
+    fn product(&mut self) -> Expr {
+        let mut lhs = self.unary();
+        loop {
+            if self.eat('*') {
+                lhs = Expr::Mul(Box::new(lhs), Box::new(self.unary()));
+            } else if self.eat('/') {
+                lhs = Expr::Div(Box::new(lhs), Box::new(self.unary()));
+            } else {
+                return lhs;
+            }
+        }
+    }
+    /// Unary minus binds looser than `^`: `-x^2` is `-(x^2)`.
+    fn unary(&mut self) -> Expr {
+        if self.eat('-') {
+            return Expr::Neg(Box::new(self.unary()));
+        }
+        if self.eat('+') {
+            return self.unary();
+        }
+        self.power()
+    }
+    /// `^` is right-associative and its exponent may carry a sign.
+    fn power(&mut self) -> Expr {
+        let base = self.primary();
+        if self.eat('^') {
+            return Expr::Pow(Box::new(base), Box::new(self.unary()));
+        }
+        base
+    }
+    fn primary(&mut self) -> Expr {
+        self.skip_space();
+        let Some(c) = self.peek() else {
+            panic!("unexpected end of input");
+        };
+        if c == '(' {
+            self.pos += 1;
+            let expr = self.sum();
+            self.expect(')');
+            return expr;
+        }
+        if c.is_ascii_digit()
+            || c == '.'
+                && self
+                    .chars
+                    .get(self.pos + 1)
+                    .is_some_and(char::is_ascii_digit)
+        {
+            return Expr::Num(self.number());
+        }
+        if c.is_alphabetic() || c == '.' {
+            let name = self.identifier();
+            if self.eat('(') {
+                let Some(func) = Func::from_name(&name) else {
+                    panic!("could not find function \"{name}\"");
+                };
+                let arg = self.sum();
+                // `psigamma(x, deriv)` takes the order as a second, constant argument
+                let func = match func {
+                    Func::Psigamma(_) if self.eat(',') => Func::Psigamma(self.order()),
+                    func => func,
+                };
+                self.expect(')');
+                return Expr::Call(func, Box::new(arg));
+            }
+            return Expr::Var(name);
+        }
+        panic!("unexpected '{c}'");
+    }
+    fn number(&mut self) -> f64 {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            self.pos += 1;
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.pos += 1;
+            }
+            while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                self.pos += 1;
+            }
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse()
+            .unwrap_or_else(|_| panic!("unexpected numeric constant '{text}'"))
+    }
+    /// A non-negative integer constant, with or without R's `L` suffix.
+    fn order(&mut self) -> u32 {
+        self.skip_space();
+        let x = self.number();
+        self.eat('L');
+        match x == x.trunc() && 0. <= x && x <= u32::MAX as f64 {
+            true => x as u32,
+            false => panic!("'deriv' must be a non-negative integer"),
+        }
+    }
+    fn identifier(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '.' || c == '_')
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{
+    matrix::{FillOrdering, MatrixBuf, matrix},
+    vector::{circle_zip, subset},
+};
+
+#[rustfmt::skip]
+pub fn sapply<A, B>(vector: impl AsRef<[A]>, f: impl Fn(A) -> B) -> Vec<B> where A: Clone {
+    vector.as_ref().iter().map(|x| f(x.clone())).collect() }
+/// Every call returns exactly `N` values, which become one row of the output.
+pub fn vapply<A, B, const N: usize>(
+    vector: impl AsRef<[A]>,
+    f: impl Fn(A) -> [B; N],
+) -> MatrixBuf<B>
+where
+    A: Clone,
+    B: Copy,
+{
+    let vector = vector.as_ref();
+    assert!(0 < N && !vector.is_empty());
+    let data: Vec<B> = vector.iter().flat_map(|x| f(x.clone())).collect();
+    matrix(data, [N, vector.len()], FillOrdering::RowByRow)
+}
+
+/// Apply `f` to the `i`-th element of every vector, recycling the shorter ones.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/main/mapply.c>
+pub fn mapply<Slice, T, B>(vectors: impl AsRef<[Slice]>, f: impl Fn(&[T]) -> B) -> Vec<B>
+where
+    Slice: AsRef<[T]>,
+    T: Clone,
+{
+    let vectors = vectors.as_ref();
+    let len = vectors.iter().map(|v| v.as_ref().len()).max().unwrap_or(0);
+    for vector in vectors {
+        let vector = vector.as_ref();
+        if vector.is_empty() || !len.is_multiple_of(vector.len()) {
+            panic!();
+        }
+    }
+    let mut args = Vec::with_capacity(vectors.len());
+    let mut out = Vec::with_capacity(len);
+    for i in 0..len {
+        args.clear();
+        args.extend(vectors.iter().map(|v| {
+            let v = v.as_ref();
+            v[i % v.len()].clone()
+        }));
+        out.push(f(&args));
+    }
+    out
+}
+#[rustfmt::skip]
+pub fn mapply2<A, B, C>(a: impl AsRef<[A]>, b: impl AsRef<[B]>, f: impl Fn(A, B) -> C) -> Vec<C> where A: Clone, B: Clone {
+    circle_zip(a, b, f) }
+#[rustfmt::skip]
+pub fn mapply3<A, B, C, D>(a: impl AsRef<[A]>, b: impl AsRef<[B]>, c: impl AsRef<[C]>, f: impl Fn(A, B, C) -> D) -> Vec<D> where A: Clone, B: Clone, C: Clone {
+    circle_zip(a, circle_zip(b, c, |b, c| (b, c)), |a, (b, c)| f(a, b, c)) }
+
+/// Lift a scalar function into one over vectors.
+#[rustfmt::skip]
+pub fn vectorize<A, B>(f: impl Fn(A) -> B) -> impl Fn(&[A]) -> Vec<B> where A: Clone {
+    move |a| sapply(a, &f) }
+/// Lift a scalar function of two arguments into a recycling function over vectors.
+#[rustfmt::skip]
+pub fn vectorize2<A, B, C>(f: impl Fn(A, B) -> C) -> impl Fn(&[A], &[B]) -> Vec<C> where A: Clone, B: Clone {
+    move |a, b| mapply2(a, b, &f) }
+/// Lift a scalar function of three arguments into a recycling function over vectors.
+#[rustfmt::skip]
+pub fn vectorize3<A, B, C, D>(f: impl Fn(A, B, C) -> D) -> impl Fn(&[A], &[B], &[C]) -> Vec<D> where A: Clone, B: Clone, C: Clone {
+    move |a, b, c| mapply3(a, b, c, &f) }
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReduceParams<T> {
+    /// prepended (or appended when `right`) to the vector
+    pub init: Option<T>,
+    /// fold from the right: `f(x_1, f(x_2, ... f(x_n-1, x_n)))`
+    pub right: bool,
+}
+#[rustfmt::skip]
+impl<T> AsRef<ReduceParams<T>> for ReduceParams<T> { fn as_ref(&self) -> &ReduceParams<T> { self } }
+/// `None` if the vector is empty and there is no `init`.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/base/R/funprog.R#L19>
+pub fn reduce<T>(
+    vector: impl AsRef<[T]>,
+    f: impl Fn(T, T) -> T,
+    params: impl AsRef<ReduceParams<T>>,
+) -> Option<T>
+where
+    T: Clone,
+{
+    let params = params.as_ref();
+    let vector = vector.as_ref();
+    if params.right {
+        let mut items = vector.iter().rev().cloned();
+        let init = params.init.clone().or_else(|| items.next())?;
+        Some(items.fold(init, |acc, item| f(item, acc)))
+    } else {
+        let mut items = vector.iter().cloned();
+        let init = params.init.clone().or_else(|| items.next())?;
+        Some(items.fold(init, f))
+    }
+}
+/// All the intermediate results of `reduce`; with `right`, the `i`-th entry is the reduction of
+/// the elements from `i` onwards.
+pub fn reduce_accumulate<T>(
+    vector: impl AsRef<[T]>,
+    f: impl Fn(T, T) -> T,
+    params: impl AsRef<ReduceParams<T>>,
+) -> Vec<T>
+where
+    T: Clone,
+{
+    let params = params.as_ref();
+    let vector = vector.as_ref();
+    let mut out = Vec::with_capacity(vector.len() + 1);
+    if params.right {
+        let mut items = vector.iter().rev().cloned();
+        let Some(mut acc) = params.init.clone().or_else(|| items.next()) else {
+            return out;
+        };
+        out.push(acc.clone());
+        for item in items {
+            acc = f(item, acc);
+            out.push(acc.clone());
+        }
+        out.reverse();
+    } else {
+        let mut items = vector.iter().cloned();
+        let Some(mut acc) = params.init.clone().or_else(|| items.next()) else {
+            return out;
+        };
+        out.push(acc.clone());
+        for item in items {
+            acc = f(acc, item);
+            out.push(acc.clone());
+        }
+    }
+    out
+}
+#[rustfmt::skip]
+pub fn filter<T>(vector: impl AsRef<[T]>, f: impl Fn(T) -> bool) -> Vec<T> where T: Clone {
+    subset(vector, f) }
+/// Index of the first element satisfying `f`, or the last one if `right`.
+pub fn position<T>(vector: impl AsRef<[T]>, f: impl Fn(T) -> bool, right: bool) -> Option<usize>
+where
+    T: Clone,
+{
+    let vector = vector.as_ref();
+    let hit = |(_, x): &(usize, &T)| f((*x).clone());
+    let mut iter = vector.iter().enumerate();
+    let found = if right {
+        iter.rfind(hit)
+    } else {
+        iter.find(hit)
+    };
+    found.map(|(i, _)| i)
+}
+/// First element satisfying `f`, or the last one if `right`.
+pub fn find<T>(vector: impl AsRef<[T]>, f: impl Fn(T) -> bool, right: bool) -> Option<T>
+where
+    T: Clone,
+{
+    let vector = vector.as_ref();
+    position(vector, f, right).map(|i| vector[i].clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_mapply() {
+        assert_eq!(mapply([vec![1, 2, 3, 4], vec![10, 20]], |x| x.iter().sum::<i32>()), [11, 22, 13, 24]);
+        assert_eq!(mapply3([1., 2.], [10.], [0.5, 0.25], |a, b, c| (a + b) * c), [5.5, 3.]);
+        let hypot = vectorize2(f64::hypot);
+        assert_eq!(hypot(&[3., 6.], &[4., 8.]), [5., 10.]);
+        let m = vapply([1, 2, 3], |x| [x, x * x]);
+        assert_eq!(m.dim(), [2, 3]);
+        assert_eq!(m.data(), [1, 1, 2, 4, 3, 9]);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_reduce() {
+        let x = [1, 2, 3, 4];
+        let sub = |a: i32, b: i32| a - b;
+        assert_eq!(reduce(x, sub, ReduceParams::default()), Some(-8));
+        assert_eq!(reduce(x, sub, ReduceParams { init: None, right: true }), Some(-2));
+        assert_eq!(reduce(x, sub, ReduceParams { init: Some(10), right: false }), Some(0));
+        assert_eq!(reduce([], sub, ReduceParams::default()), None);
+        let add = |a: i32, b: i32| a + b;
+        assert_eq!(reduce_accumulate(x, add, ReduceParams::default()), [1, 3, 6, 10]);
+        assert_eq!(reduce_accumulate(x, add, ReduceParams { init: None, right: true }), [10, 9, 7, 4]);
+        assert_eq!(reduce_accumulate(x, add, ReduceParams { init: Some(0), right: true }), [10, 9, 7, 4, 0]);
+    }
+
+    #[test]
+    fn test_find() {
+        let x = [1, 4, 6, 7];
+        let even = |x: i32| x % 2 == 0;
+        assert_eq!(filter(x, even), [4, 6]);
+        assert_eq!(position(x, even, false), Some(1));
+        assert_eq!(position(x, even, true), Some(2));
+        assert_eq!(find(x, even, true), Some(6));
+        assert_eq!(find(x, |x| 10 < x, false), None);
+    }
+}
@@ -0,0 +1,229 @@
+use num_traits::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, PrimInt, SaturatingAdd, SaturatingMul,
+    SaturatingSub, WrappingAdd, WrappingMul, WrappingSub,
+};
+
+use crate::{
+    property::{BigInt, CalcCheckedChoose, CalcCheckedFactorial},
+    vector::circle_zip,
+    warning::warning,
+};
+
+pub use checked::*;
+#[rustfmt::skip]
+mod checked {
+    use super::*;
+    pub fn checked_add<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<Option<T>> where T: Clone + CheckedAdd {
+        circle_zip(a, b, |a, b| a.checked_add(&b)) }
+    pub fn checked_sub<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<Option<T>> where T: Clone + CheckedSub {
+        circle_zip(a, b, |a, b| a.checked_sub(&b)) }
+    pub fn checked_mul<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<Option<T>> where T: Clone + CheckedMul {
+        circle_zip(a, b, |a, b| a.checked_mul(&b)) }
+    pub fn checked_div<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<Option<T>> where T: Clone + CheckedDiv {
+        circle_zip(a, b, |a, b| a.checked_div(&b)) }
+    pub fn checked_factorial<T>(vector: impl AsRef<[T]>) -> Vec<Option<T>> where T: CalcCheckedFactorial {
+        vector.as_ref().iter().map(|x| x.checked_factorial()).collect() }
+    pub fn checked_choose<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<Option<T>> where T: CalcCheckedChoose + Clone {
+        circle_zip(a, b, |a, b| a.checked_choose(b)) }
+}
+pub use wrapping::*;
+#[rustfmt::skip]
+mod wrapping {
+    use super::*;
+    pub fn wrapping_add<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<T> where T: Clone + WrappingAdd {
+        circle_zip(a, b, |a, b| a.wrapping_add(&b)) }
+    pub fn wrapping_sub<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<T> where T: Clone + WrappingSub {
+        circle_zip(a, b, |a, b| a.wrapping_sub(&b)) }
+    pub fn wrapping_mul<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<T> where T: Clone + WrappingMul {
+        circle_zip(a, b, |a, b| a.wrapping_mul(&b)) }
+}
+pub use saturating::*;
+#[rustfmt::skip]
+mod saturating {
+    use super::*;
+    pub fn saturating_add<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<T> where T: Clone + SaturatingAdd {
+        circle_zip(a, b, |a, b| a.saturating_add(&b)) }
+    pub fn saturating_sub<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<T> where T: Clone + SaturatingSub {
+        circle_zip(a, b, |a, b| a.saturating_sub(&b)) }
+    pub fn saturating_mul<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<T> where T: Clone + SaturatingMul {
+        circle_zip(a, b, |a, b| a.saturating_mul(&b)) }
+}
+
+// R's integer semantics: `None` is `NA`, which propagates, and overflow produces `NA` with a warning.
+// ref: <https://github.com/wch/r-source/blob/trunk/src/main/arithmetic.c#L600>
+pub use r_integer::*;
+#[rustfmt::skip]
+mod r_integer {
+    use super::*;
+    pub fn int_add<T>(a: impl AsRef<[Option<T>]>, b: impl AsRef<[Option<T>]>) -> Vec<Option<T>> where T: PrimInt {
+        int_op2(a, b, |a, b| Some(a.checked_add(&b))) }
+    pub fn int_sub<T>(a: impl AsRef<[Option<T>]>, b: impl AsRef<[Option<T>]>) -> Vec<Option<T>> where T: PrimInt {
+        int_op2(a, b, |a, b| Some(a.checked_sub(&b))) }
+    pub fn int_mul<T>(a: impl AsRef<[Option<T>]>, b: impl AsRef<[Option<T>]>) -> Vec<Option<T>> where T: PrimInt {
+        int_op2(a, b, |a, b| Some(a.checked_mul(&b))) }
+    /// `%/%`: `NA` on division by zero
+    pub fn int_div<T>(a: impl AsRef<[Option<T>]>, b: impl AsRef<[Option<T>]>) -> Vec<Option<T>> where T: PrimInt {
+        int_op2(a, b, |a, b| if b.is_zero() { None } else { Some(div_floor(a, b)) }) }
+    /// `%%`: the result takes the sign of `b`
+    pub fn int_modulo<T>(a: impl AsRef<[Option<T>]>, b: impl AsRef<[Option<T>]>) -> Vec<Option<T>> where T: PrimInt {
+        int_op2(a, b, |a, b| if b.is_zero() { None } else { Some(mod_floor(a, b)) }) }
+}
+/// `reduce_one` returns `None` for a plain `NA` and `Some(None)` for an overflow.
+fn int_op2<T>(
+    a: impl AsRef<[Option<T>]>,
+    b: impl AsRef<[Option<T>]>,
+    reduce_one: impl Fn(T, T) -> Option<Option<T>>,
+) -> Vec<Option<T>>
+where
+    T: PrimInt,
+{
+    let overflow = std::cell::Cell::new(false);
+    let out = circle_zip(a, b, |a, b| {
+        let (a, b) = (a?, b?);
+        let c = reduce_one(a, b)?;
+        if c.is_none() {
+            overflow.set(true);
+        }
+        c
+    });
+    if overflow.get() {
+        warning("NAs produced by integer overflow");
+    }
+    out
+}
+fn div_floor<T>(a: T, b: T) -> Option<T>
+where
+    T: PrimInt,
+{
+    let q = a.checked_div(&b)?;
+    let rounds_toward_zero = !(a % b).is_zero() && ((a < T::zero()) != (b < T::zero()));
+    if rounds_toward_zero {
+        return q.checked_sub(&T::one());
+    }
+    Some(q)
+}
+fn mod_floor<T>(a: T, b: T) -> Option<T>
+where
+    T: PrimInt,
+{
+    let q = div_floor(a, b)?;
+    a.checked_sub(&q.checked_mul(&b)?)
+}
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/main/summary.c#L113>
+pub fn int_sum<T>(vector: impl AsRef<[Option<T>]>) -> Option<T>
+where
+    T: PrimInt,
+{
+    let mut sum = T::zero();
+    for item in vector.as_ref() {
+        let item = (*item)?;
+        match sum.checked_add(&item) {
+            Some(s) => sum = s,
+            None => {
+                warning("integer overflow - use sum(as.numeric(.))");
+                return None;
+            }
+        }
+    }
+    Some(sum)
+}
+pub fn int_cumsum<T>(vector: impl AsRef<[Option<T>]>) -> Vec<Option<T>>
+where
+    T: PrimInt,
+{
+    let mut out = vec![];
+    let mut sum = Some(T::zero());
+    let mut overflow = false;
+    for item in vector.as_ref() {
+        sum = match (sum, item) {
+            (Some(s), Some(item)) => {
+                let s = s.checked_add(item);
+                overflow |= s.is_none();
+                s
+            }
+            _ => None,
+        };
+        out.push(sum);
+    }
+    if overflow {
+        warning("integer overflow in 'cumsum'; use 'cumsum(as.numeric(.))'");
+    }
+    out
+}
+
+/// Lift plain integers into the `NA`-aware representation.
+pub fn as_int<T>(vector: impl AsRef<[T]>) -> Vec<Option<T>>
+where
+    T: Copy,
+{
+    vector.as_ref().iter().map(|x| Some(*x)).collect()
+}
+/// Lift integers into arbitrary precision so `prod`, `cumsum`, `factorial` and `choose` never overflow.
+pub fn as_bigint<T>(vector: impl AsRef<[T]>) -> Vec<BigInt>
+where
+    T: Clone + Into<BigInt>,
+{
+    vector.as_ref().iter().map(|x| x.clone().into()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::warning::take_warnings;
+
+    use super::*;
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(checked_add([i32::MAX, 1], [1]), [None, Some(2)]);
+        assert_eq!(checked_div([1], [0]), [None]);
+        assert_eq!(wrapping_add([u8::MAX], [1]), [0]);
+        assert_eq!(saturating_mul([i8::MIN], [2]), [i8::MIN]);
+        assert_eq!(checked_factorial([20_i32, 5]), [None, Some(120)]);
+        assert_eq!(checked_factorial([20_u128]), [Some(2432902008176640000)]);
+    }
+
+    #[test]
+    fn test_r_integer() {
+        take_warnings();
+        let a = as_int([i32::MAX, 5]);
+        assert_eq!(int_add(&a, [Some(1)]), [None, Some(6)]);
+        assert_eq!(take_warnings().len(), 1);
+        assert_eq!(int_add(&a, [None]), [None, None]);
+        assert!(take_warnings().is_empty());
+
+        let a = as_int([5, -5, 5, -5]);
+        let b = as_int([3, 3, -3, -3]);
+        assert_eq!(int_div(&a, &b), as_int([1, -2, -2, 1]));
+        assert_eq!(int_modulo(&a, &b), as_int([2, 1, -1, -2]));
+        assert_eq!(int_div(&a, [Some(0)]), [None; 4]);
+        assert!(take_warnings().is_empty());
+
+        assert_eq!(int_sum(as_int([i32::MAX, 1])), None);
+        assert_eq!(
+            int_cumsum(as_int([1, 2, i32::MAX])),
+            [Some(1), Some(3), None]
+        );
+        assert_eq!(take_warnings().len(), 2);
+    }
+
+    #[test]
+    fn test_bigint() {
+        use crate::vector::{self, SeqParams, seq};
+
+        let x = as_bigint(seq(SeqParams::from(1..=30)));
+        assert_eq!(
+            vector::prod(&x).to_string(),
+            "265252859812191058636308480000000"
+        );
+        assert_eq!(vector::cumsum(&x)[29], BigInt::from(465));
+        assert_eq!(
+            vector::factorial(as_bigint([25]))[0].to_string(),
+            "15511210043330985984000000"
+        );
+        assert_eq!(
+            vector::choose(as_bigint([60]), as_bigint([30, 0])),
+            as_bigint([118264581564861424_i64, 1])
+        );
+    }
+}
@@ -0,0 +1,308 @@
+use num_traits::{Float, FloatConst};
+
+pub use logarithms::*;
+#[rustfmt::skip]
+mod logarithms {
+    use super::*;
+    pub fn ln<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.ln()).collect() }
+    pub fn log<T>(vector: impl AsRef<[T]>, base: T) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.log(base)).collect() }
+    pub fn log2<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.log2()).collect() }
+    pub fn log10<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.log10()).collect() }
+    pub fn log1p<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.ln_1p()).collect() }
+    pub fn expm1<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.exp_m1()).collect() }
+}
+pub use trigonometry::*;
+#[rustfmt::skip]
+mod trigonometry {
+    use super::*;
+    pub fn asin<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.asin()).collect() }
+    pub fn acos<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.acos()).collect() }
+    pub fn atan<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.atan()).collect() }
+    pub fn atan2<T>(y: impl AsRef<[T]>, x: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        crate::vector::circle_zip(y, x, |y, x| y.atan2(x)) }
+    pub fn sinh<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.sinh()).collect() }
+    pub fn cosh<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.cosh()).collect() }
+    pub fn tanh<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.tanh()).collect() }
+    pub fn asinh<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.asinh()).collect() }
+    pub fn acosh<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.acosh()).collect() }
+    pub fn atanh<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.atanh()).collect() }
+    pub fn sinpi<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float + FloatConst {
+        vector.as_ref().iter().map(|x| sinpi1(*x)).collect() }
+    pub fn cospi<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float + FloatConst {
+        vector.as_ref().iter().map(|x| cospi1(*x)).collect() }
+    pub fn tanpi<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float + FloatConst {
+        vector.as_ref().iter().map(|x| tanpi1(*x)).collect() }
+}
+pub use rounding::*;
+#[rustfmt::skip]
+mod rounding {
+    use super::*;
+    pub fn trunc<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| x.trunc()).collect() }
+    pub fn sign<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Signed + PartialOrd + Clone {
+        vector.as_ref().iter().map(|x| sign1(x.clone())).collect() }
+    pub fn round<T>(vector: impl AsRef<[T]>, digits: i32) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| fround(*x, digits)).collect() }
+    pub fn signif<T>(vector: impl AsRef<[T]>, digits: i32) -> Vec<T> where T: Float {
+        vector.as_ref().iter().map(|x| fprec(*x, digits)).collect() }
+}
+
+/// Reduce `x` into `(-1, 1]`, the period of `sin(πx)` and `cos(πx)`.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/nmath/cospi.c#L31>
+fn reduce_pi_period<T>(x: T) -> T
+where
+    T: Float,
+{
+    let two = T::one() + T::one();
+    let mut x = x % two;
+    if x <= -T::one() {
+        x = x + two;
+    } else if T::one() < x {
+        x = x - two;
+    }
+    x
+}
+fn sinpi1<T>(x: T) -> T
+where
+    T: Float + FloatConst,
+{
+    if !x.is_finite() {
+        return T::nan();
+    }
+    let x = reduce_pi_period(x);
+    let half = T::one() / (T::one() + T::one());
+    if x.is_zero() || x == T::one() {
+        return T::zero();
+    }
+    if x == half {
+        return T::one();
+    }
+    if x == -half {
+        return -T::one();
+    }
+    (T::PI() * x).sin()
+}
+fn cospi1<T>(x: T) -> T
+where
+    T: Float + FloatConst,
+{
+    if !x.is_finite() {
+        return T::nan();
+    }
+    let x = x.abs() % (T::one() + T::one());
+    let half = T::one() / (T::one() + T::one());
+    if x % T::one() == half {
+        return T::zero();
+    }
+    if x.is_zero() {
+        return T::one();
+    }
+    if x == T::one() {
+        return -T::one();
+    }
+    (T::PI() * x).cos()
+}
+fn tanpi1<T>(x: T) -> T
+where
+    T: Float + FloatConst,
+{
+    if !x.is_finite() {
+        return T::nan();
+    }
+    // tan(πx) has period 1; reduce into (-1/2, 1/2]
+    let half = T::one() / (T::one() + T::one());
+    let mut x = x % T::one();
+    if x <= -half {
+        x = x + T::one();
+    } else if half < x {
+        x = x - T::one();
+    }
+    if x.is_zero() {
+        return T::zero();
+    }
+    if x == half {
+        return T::nan();
+    }
+    (T::PI() * x).tan()
+}
+
+fn sign1<T>(x: T) -> T
+where
+    T: num_traits::Signed + PartialOrd,
+{
+    if T::zero() < x {
+        T::one()
+    } else if x < T::zero() {
+        -T::one()
+    } else {
+        x
+    }
+}
+
+/// Round half to even.
+fn rint<T>(x: T) -> T
+where
+    T: Float,
+{
+    let floor = x.floor();
+    let diff = x - floor;
+    let half = T::one() / (T::one() + T::one());
+    if diff < half {
+        return floor;
+    }
+    if half < diff {
+        return floor + T::one();
+    }
+    let floor_is_even = (floor / (T::one() + T::one())).fract().is_zero();
+    if floor_is_even {
+        floor
+    } else {
+        floor + T::one()
+    }
+}
+fn pow10<T>(e: i32) -> T
+where
+    T: Float,
+{
+    T::from(10).unwrap().powi(e)
+}
+
+/// Pick whichever of the two decimal candidates around `x` is closer, breaking ties to even.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/nmath/fround.c#L56>
+fn fround<T>(x: T, digits: i32) -> T
+where
+    T: Float,
+{
+    const MAX_DIGITS: i32 = 308;
+    const DBL_DIG: i32 = 15;
+    if x.is_nan() || !x.is_finite() || x.is_zero() || MAX_DIGITS + DBL_DIG < digits {
+        return x;
+    }
+    if digits < -MAX_DIGITS {
+        return T::zero();
+    }
+    if digits == 0 {
+        return rint(x);
+    }
+    if x < T::zero() {
+        return -fround(-x, digits);
+    }
+    let log10_x =
+        T::from(std::f64::consts::LOG10_2).unwrap() * (T::from(0.5).unwrap() + x.log2().floor());
+    if T::from(DBL_DIG - digits).unwrap() < log10_x {
+        return x;
+    }
+    let (p10, scale) = if digits <= MAX_DIGITS {
+        (T::one(), pow10::<T>(digits))
+    } else {
+        (pow10::<T>(digits - MAX_DIGITS), pow10::<T>(MAX_DIGITS))
+    };
+    let x10 = scale * x * p10;
+    let i10 = x10.floor();
+    let x_down = i10 / scale / p10;
+    let x_up = x10.ceil() / scale / p10;
+    let d_up = x_up - x;
+    let d_down = x - x_down;
+    let i10_is_odd = !(i10 / (T::one() + T::one())).fract().is_zero();
+    if d_up < d_down || (d_up == d_down && i10_is_odd) {
+        x_up
+    } else {
+        x_down
+    }
+}
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/nmath/fprec.c#L67>
+fn fprec<T>(x: T, digits: i32) -> T
+where
+    T: Float,
+{
+    const MAX_DIGITS: i32 = 22;
+    const MAX_10_EXP: i32 = 308;
+    if x.is_nan() || !x.is_finite() || x.is_zero() || MAX_DIGITS < digits {
+        return x;
+    }
+    let digits = digits.max(1);
+    let (sign, x) = if x < T::zero() {
+        (-T::one(), -x)
+    } else {
+        (T::one(), x)
+    };
+    let log10_x = x.log10();
+    let e10 = digits - 1 - log10_x.floor().to_i32().unwrap();
+    if e10 <= 0 {
+        let scale = pow10::<T>(-e10);
+        return sign * rint(x / scale) * scale;
+    }
+    // split the scaling so that neither factor overflows for tiny `x`
+    let (p10, e10) = if MAX_10_EXP < e10 {
+        (pow10::<T>(e10 - MAX_10_EXP), MAX_10_EXP)
+    } else {
+        (T::one(), e10)
+    };
+    let scale = pow10::<T>(e10);
+    sign * (rint(x * scale * p10) / scale) / p10
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round() {
+        assert_eq!(
+            round([0.5, 1.5, 2.5, -0.5, -2.5], 0),
+            [0., 2., 2., -0., -2.]
+        );
+        assert_eq!(round([0.15, 0.25, 0.35, 2.675], 1), [0.1, 0.2, 0.3, 2.7]);
+        assert_eq!(round([2.675], 2), [2.67]);
+        assert_eq!(round([1234.5678], -2), [1200.]);
+        assert!(round([f64::NAN], 2)[0].is_nan());
+        assert_eq!(
+            signif([123456., 0.00123456, -0.0456], 2),
+            [120000., 0.0012, -0.046]
+        );
+        assert_eq!(signif([2.5, 3.5], 1), [2., 4.]);
+        assert_eq!(trunc([-1.7, 1.7]), [-1., 1.]);
+        assert_eq!(sign([-2, 0, 3]), [-1, 0, 1]);
+        assert!(sign([f64::NAN])[0].is_nan());
+    }
+
+    #[test]
+    fn test_pi_functions() {
+        assert_eq!(
+            sinpi([0., 0.5, 1., 1.5, 2., -1., 100.]),
+            [0., 1., 0., -1., 0., 0., 0.]
+        );
+        assert_eq!(cospi([0., 0.5, 1., 1.5, -3.]), [1., 0., -1., 0., -1.]);
+        assert_eq!(tanpi([0., 0.25, 1.])[..1], [0.]);
+        assert!((tanpi([0.25])[0] - 1_f64).abs() < 1e-15);
+        assert!(tanpi([0.5])[0].is_nan());
+    }
+
+    #[test]
+    fn test_logarithms() {
+        assert_eq!(log([8., 1.], 2.), [3., 0.]);
+        assert_eq!(log2([8.]), [3.]);
+        assert_eq!(log10([1000.]), [3.]);
+        assert_eq!(log1p([1e-20]), [1e-20]);
+        assert_eq!(expm1([1e-20]), [1e-20]);
+        assert!((atanh(tanh([0.5]))[0] - 0.5_f64).abs() < 1e-15);
+    }
+}
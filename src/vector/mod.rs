@@ -1,7 +1,17 @@
+mod apply;
+pub use apply::*;
 mod constructor;
 pub use constructor::*;
+mod integer;
+pub use integer::*;
+mod math;
+pub use math::*;
 mod ops;
 pub use ops::*;
+mod random;
+pub use random::*;
+mod reshape;
+pub use reshape::*;
 
 pub fn set<T>(
     dst: &mut Vec<T>,
@@ -108,6 +108,67 @@ where
     }
     partial_var
 }
+/// Sample quantiles at `probs` by linear interpolation between order statistics, R's default
+/// `type = 7`.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/quantile.R>
+pub fn quantile<T>(vector: impl AsRef<[T]>, probs: impl AsRef<[T]>) -> Vec<T>
+where
+    T: num_traits::Float + num_traits::FromPrimitive,
+{
+    let vector = vector.as_ref();
+    assert!(!vector.is_empty());
+    assert!(
+        vector.iter().all(|x| !x.is_nan()),
+        "missing values and NaN's not allowed if 'na.rm' is FALSE"
+    );
+    let mut sorted = vector.to_vec();
+    sorted.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+    let n_1 = T::from_usize(sorted.len() - 1).unwrap();
+    let eps = T::from_f64(4. * f64::EPSILON).unwrap();
+    probs
+        .as_ref()
+        .iter()
+        .map(|&p| {
+            assert!(
+                T::zero() - eps <= p && p <= T::one() + eps,
+                "'probs' outside [0,1]"
+            );
+            let index = n_1 * p.max(T::zero()).min(T::one());
+            let lo = index.floor();
+            let hi = index.ceil();
+            let h = index - lo;
+            let x_lo = sorted[lo.to_usize().unwrap()];
+            let x_hi = sorted[hi.to_usize().unwrap()];
+            if h == T::zero() {
+                x_lo
+            } else {
+                x_lo + h * (x_hi - x_lo)
+            }
+        })
+        .collect()
+}
+/// `rank(x)`: ranks starting at 1, with ties given the average of their ranks.
+pub fn rank<T>(vector: impl AsRef<[T]>) -> Vec<f64>
+where
+    T: PartialOrd,
+{
+    let vector = vector.as_ref();
+    let mut order: Vec<usize> = (0..vector.len()).collect();
+    order.sort_by(|&a, &b| vector[a].partial_cmp(&vector[b]).unwrap());
+    let mut ranks = vec![0.; vector.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && vector[order[end]] == vector[order[start]] {
+            end += 1;
+        }
+        let average = (start + end + 1) as f64 / 2.;
+        order[start..end].iter().for_each(|&i| ranks[i] = average);
+        start = end;
+    }
+    ranks
+}
 pub fn any(vector: impl AsRef<[bool]>) -> bool {
     vector.as_ref().iter().any(|x| *x)
 }
@@ -156,8 +217,6 @@ mod single_vector_in_single_vector_out {
         vector.as_ref().iter().map(|x| x.floor()).collect() }
     pub fn ceil<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Float {
         vector.as_ref().iter().map(|x| x.ceil()).collect() }
-    pub fn round<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Float {
-        vector.as_ref().iter().map(|x| x.round()).collect() }
     pub fn factorial<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: CalcFactorial {
         vector.as_ref().iter().map(|x| x.factorial()).collect() }
 }
@@ -196,6 +255,41 @@ mod two_vectors_in_single_vector_out {
         circle_zip(a, b, |a, b| a.choose(b)) }
 }
 
+pub use special_functions::*;
+#[rustfmt::skip]
+mod special_functions {
+    use crate::special;
+    use super::*;
+    pub fn lgamma<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Float {
+        vector.as_ref().iter().map(|x| via_f64(*x, special::lgamma)).collect() }
+    pub fn lfactorial<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Float {
+        vector.as_ref().iter().map(|x| via_f64(*x, special::lfactorial)).collect() }
+    pub fn digamma<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Float {
+        vector.as_ref().iter().map(|x| via_f64(*x, special::digamma)).collect() }
+    pub fn trigamma<T>(vector: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Float {
+        vector.as_ref().iter().map(|x| via_f64(*x, special::trigamma)).collect() }
+    pub fn psigamma<T>(vector: impl AsRef<[T]>, deriv: u32) -> Vec<T> where T: num_traits::Float {
+        vector.as_ref().iter().map(|x| via_f64(*x, |x| special::psigamma(x, deriv))).collect() }
+    pub fn lchoose<T>(n: impl AsRef<[T]>, k: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Float {
+        circle_zip(n, k, |n, k| via_f64_2(n, k, special::lchoose)) }
+    pub fn beta<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Float {
+        circle_zip(a, b, |a, b| via_f64_2(a, b, special::beta)) }
+    pub fn lbeta<T>(a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Float {
+        circle_zip(a, b, |a, b| via_f64_2(a, b, special::lbeta)) }
+    pub fn gamma_inc_lower<T>(a: impl AsRef<[T]>, x: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Float {
+        circle_zip(a, x, |a, x| via_f64_2(a, x, special::gamma_inc_lower)) }
+    pub fn gamma_inc_upper<T>(a: impl AsRef<[T]>, x: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Float {
+        circle_zip(a, x, |a, x| via_f64_2(a, x, special::gamma_inc_upper)) }
+    pub fn beta_inc<T>(x: impl AsRef<[T]>, a: impl AsRef<[T]>, b: impl AsRef<[T]>) -> Vec<T> where T: num_traits::Float {
+        circle_zip(x, circle_zip(a, b, |a, b| (a, b)), |x, (a, b)| T::from(special::beta_inc(f64_of(x), f64_of(a), f64_of(b))).unwrap()) }
+    fn f64_of<T>(x: T) -> f64 where T: num_traits::Float {
+        x.to_f64().unwrap() }
+    fn via_f64<T>(x: T, f: impl Fn(f64) -> f64) -> T where T: num_traits::Float {
+        T::from(f(f64_of(x))).unwrap() }
+    fn via_f64_2<T>(a: T, b: T, f: impl Fn(f64, f64) -> f64) -> T where T: num_traits::Float {
+        T::from(f(f64_of(a), f64_of(b))).unwrap() }
+}
+
 #[rustfmt::skip]
 pub fn map<A, B>(vector: impl AsRef<[A]>, fmap: impl Fn(A) -> B) -> Vec<B> where A: Clone {
     vector.as_ref().iter().map(|x| fmap(x.clone())).collect() }
@@ -235,7 +329,7 @@ pub fn all_eq_no_scale<T>() -> Option<&'static [T]> {
     None
 }
 
-fn circle_zip<A, B, C>(
+pub(crate) fn circle_zip<A, B, C>(
     a: impl AsRef<[A]>,
     b: impl AsRef<[B]>,
     reduce_one: impl Fn(A, B) -> C,
@@ -356,6 +450,18 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_quantile() {
+        // R: quantile(c(3, 1, 4, 1, 5), c(0, 0.1, 0.5, 0.9, 1))
+        let q = quantile([3., 1., 4., 1., 5.], [0., 0.1, 0.5, 0.9, 1.]);
+        assert_eq!(q, [1., 1., 3., 4.6, 5.]);
+    }
+
+    #[test]
+    fn test_rank() {
+        assert_eq!(rank([3., 1., 4., 1., 5.]), [3., 1.5, 4., 1.5, 5.]);
+    }
+
     #[test]
     #[should_panic]
     fn not_aligned() {
@@ -448,6 +554,16 @@ mod tests {
             tolerance: 10e-7, scale: all_eq_no_scale() }));
     }
 
+    #[test]
+    fn test_special_functions() {
+        let x = lgamma([1_f32, 2., 3., 4.]);
+        assert_eq!(exp(x), [1., 1., 2., 6.]);
+        let c = exp(lchoose([200_f64], [100., 0.]));
+        assert!((c[0] / choose([200.], [100.])[0] - 1.).abs() < 1e-9);
+        assert_eq!(c[1], 1.);
+        assert_eq!(beta_inc([0., 1.], [2.], [3.]), [0., 1.]);
+    }
+
     #[test]
     fn test_cumsum() {
         assert_eq!(cumsum([1, 2, 3, 4, 5]), [1, 3, 6, 10, 15]);
@@ -0,0 +1,120 @@
+use rand::Rng;
+
+#[derive(Debug, Clone)]
+pub struct SampleParams {
+    pub size: usize,
+    pub replace: bool,
+    /// weights for drawing each element, not necessarily summing to 1
+    pub prob: Option<Vec<f64>>,
+}
+impl SampleParams {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            replace: false,
+            prob: None,
+        }
+    }
+}
+#[rustfmt::skip]
+impl AsRef<SampleParams> for SampleParams { fn as_ref(&self) -> &SampleParams { self } }
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/main/random.c>
+pub fn sample<T>(
+    vector: impl AsRef<[T]>,
+    params: impl AsRef<SampleParams>,
+    rng: &mut impl Rng,
+) -> Vec<T>
+where
+    T: Clone,
+{
+    let vector = vector.as_ref();
+    let params = params.as_ref();
+    let n = vector.len();
+    if !params.replace {
+        assert!(
+            params.size <= n,
+            "cannot take a sample larger than the population when 'replace = FALSE'"
+        );
+    }
+    if 0 < params.size {
+        assert!(0 < n, "cannot sample from an empty vector");
+    }
+    let Some(prob) = &params.prob else {
+        if params.replace {
+            return (0..params.size)
+                .map(|_| vector[rng.gen_range(0..n)].clone())
+                .collect();
+        }
+        // partial Fisher-Yates shuffle of the indices
+        let mut index: Vec<usize> = (0..n).collect();
+        for i in 0..params.size {
+            let j = rng.gen_range(i..n);
+            index.swap(i, j);
+        }
+        return index[..params.size]
+            .iter()
+            .map(|&i| vector[i].clone())
+            .collect();
+    };
+    assert_eq!(prob.len(), n, "incorrect number of probabilities");
+    assert!(
+        prob.iter().all(|p| p.is_finite() && 0. <= *p),
+        "invalid probabilities"
+    );
+    let mut weights = prob.clone();
+    let mut out = Vec::with_capacity(params.size);
+    for _ in 0..params.size {
+        let total: f64 = weights.iter().sum();
+        assert!(0. < total, "too few positive probabilities");
+        let u = rng.r#gen::<f64>() * total;
+        let mut cumulative = 0.;
+        let mut chosen = weights.iter().rposition(|&w| 0. < w).unwrap();
+        for (i, &w) in weights.iter().enumerate() {
+            cumulative += w;
+            if 0. < w && u < cumulative {
+                chosen = i;
+                break;
+            }
+        }
+        out.push(vector[chosen].clone());
+        if !params.replace {
+            weights[chosen] = 0.;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn test_sample() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let x = [1, 2, 3, 4, 5];
+        let mut permutation = sample(x, SampleParams::new(5), &mut rng);
+        permutation.sort();
+        assert_eq!(permutation, x);
+
+        let params = SampleParams {
+            size: 10_000,
+            replace: true,
+            prob: Some(vec![1., 0., 3.]),
+        };
+        let draws = sample(['a', 'b', 'c'], &params, &mut rng);
+        assert!(!draws.contains(&'b'));
+        let share = draws.iter().filter(|&&d| d == 'c').count() as f64 / 1e4;
+        assert!((share - 0.75).abs() < 0.02);
+
+        let params = SampleParams {
+            prob: Some(vec![1., 1., 100.]),
+            ..SampleParams::new(3)
+        };
+        let mut draws = sample(['a', 'b', 'c'], &params, &mut rng);
+        draws.sort();
+        assert_eq!(draws, ['a', 'b', 'c']);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::matrix::{FillOrdering, MatrixBuf, matrix};
+
+/// A negative `n` keeps all but the last `-n` elements.
+pub fn head<T>(vector: impl AsRef<[T]>, n: isize) -> Vec<T>
+where
+    T: Clone,
+{
+    let vector = vector.as_ref();
+    vector[..head_len(vector.len(), n)].to_vec()
+}
+/// A negative `n` keeps all but the first `-n` elements.
+pub fn tail<T>(vector: impl AsRef<[T]>, n: isize) -> Vec<T>
+where
+    T: Clone,
+{
+    let vector = vector.as_ref();
+    let len = head_len(vector.len(), n);
+    vector[vector.len() - len..].to_vec()
+}
+pub(crate) fn head_len(len: usize, n: isize) -> usize {
+    if n < 0 {
+        len.saturating_sub(n.unsigned_abs())
+    } else {
+        len.min(n as usize)
+    }
+}
+
+/// Insert `values` after the first `after` elements of `vector`.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/base/R/append.R>
+pub fn append<T>(vector: impl AsRef<[T]>, values: impl AsRef<[T]>, after: usize) -> Vec<T>
+where
+    T: Clone,
+{
+    let vector = vector.as_ref();
+    let after = after.min(vector.len());
+    let mut out = Vec::with_capacity(vector.len() + values.as_ref().len());
+    out.extend_from_slice(&vector[..after]);
+    out.extend_from_slice(values.as_ref());
+    out.extend_from_slice(&vector[after..]);
+    out
+}
+
+pub fn rev<T>(vector: impl AsRef<[T]>) -> Vec<T>
+where
+    T: Clone,
+{
+    vector.as_ref().iter().rev().cloned().collect()
+}
+/// Shift elements `n` places towards the front, wrapping around; a negative `n` shifts towards the back.
+pub fn rotate<T>(vector: impl AsRef<[T]>, n: isize) -> Vec<T>
+where
+    T: Clone,
+{
+    let mut out = vector.as_ref().to_vec();
+    if out.is_empty() {
+        return out;
+    }
+    let n = n.rem_euclid(out.len() as isize) as usize;
+    out.rotate_left(n);
+    out
+}
+
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/base/R/rle.R>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rle<T> {
+    pub lengths: Vec<usize>,
+    pub values: Vec<T>,
+}
+#[rustfmt::skip]
+impl<T> AsRef<Rle<T>> for Rle<T> { fn as_ref(&self) -> &Rle<T> { self } }
+pub fn rle<T>(vector: impl AsRef<[T]>) -> Rle<T>
+where
+    T: PartialEq + Clone,
+{
+    let mut out = Rle {
+        lengths: vec![],
+        values: vec![],
+    };
+    for item in vector.as_ref() {
+        if out.values.last() == Some(item) {
+            *out.lengths.last_mut().unwrap() += 1;
+            continue;
+        }
+        out.values.push(item.clone());
+        out.lengths.push(1);
+    }
+    out
+}
+pub fn inverse_rle<T>(rle: impl AsRef<Rle<T>>) -> Vec<T>
+where
+    T: Clone,
+{
+    let rle = rle.as_ref();
+    assert_eq!(rle.lengths.len(), rle.values.len());
+    let mut out = vec![];
+    for (length, value) in rle.lengths.iter().zip(&rle.values) {
+        out.extend(std::iter::repeat_n(value.clone(), *length));
+    }
+    out
+}
+
+/// Row `i` is `x[i + dimension - 1], x[i + dimension - 2], ..., x[i]`.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/library/stats/R/embed.R>
+pub fn embed<T>(vector: impl AsRef<[T]>, dimension: usize) -> MatrixBuf<T>
+where
+    T: Copy,
+{
+    let vector = vector.as_ref();
+    assert!(0 < dimension && dimension <= vector.len());
+    let n_rows = vector.len() - dimension + 1;
+    let mut data = Vec::with_capacity(n_rows * dimension);
+    for row_i in 0..n_rows {
+        data.extend(vector[row_i..row_i + dimension].iter().rev());
+    }
+    matrix(data, [dimension, n_rows], FillOrdering::RowByRow)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vector::{SeqParams, seq};
+
+    use super::*;
+
+    #[test]
+    fn test_head_tail() {
+        let x = seq(SeqParams::from(1..=5));
+        assert_eq!(head(&x, 2), [1, 2]);
+        assert_eq!(head(&x, -2), [1, 2, 3]);
+        assert_eq!(head(&x, 10), x);
+        assert_eq!(tail(&x, 2), [4, 5]);
+        assert_eq!(tail(&x, -2), [3, 4, 5]);
+        assert!(tail(&x, -10).is_empty());
+        assert_eq!(append(&x, [0, 0], 1), [1, 0, 0, 2, 3, 4, 5]);
+        assert_eq!(append(&x, [0], 10), [1, 2, 3, 4, 5, 0]);
+        assert_eq!(rev(&x), [5, 4, 3, 2, 1]);
+        assert_eq!(rotate(&x, 2), [3, 4, 5, 1, 2]);
+        assert_eq!(rotate(&x, -1), [5, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_rle() {
+        let x = [1, 1, 2, 2, 2, 1];
+        let r = rle(x);
+        assert_eq!(r.lengths, [2, 3, 1]);
+        assert_eq!(r.values, [1, 2, 1]);
+        assert_eq!(inverse_rle(&r), x);
+    }
+
+    #[test]
+    fn test_embed() {
+        let m = embed(seq(SeqParams::from(1..=5)), 2);
+        assert_eq!(m.dim(), [2, 4]);
+        assert_eq!(m.data(), [2, 1, 3, 2, 4, 3, 5, 4]);
+    }
+}
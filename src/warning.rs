@@ -0,0 +1,50 @@
+use std::{cell::RefCell, collections::VecDeque};
+
+/// How many warnings are kept for `take_warnings`, like R's `nwarnings`.
+pub const MAX_WARNINGS: usize = 50;
+
+thread_local! {
+    static WARNINGS: RefCell<VecDeque<String>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// Print `message` to stderr as R's "Warning message:" and keep it for `take_warnings`. Only the
+/// last `MAX_WARNINGS` are kept, so callers that never drain them do not grow without limit.
+///
+/// ref: <https://github.com/wch/r-source/blob/trunk/src/main/errors.c>
+pub fn warning(message: impl Into<String>) {
+    let message = message.into();
+    eprintln!("Warning message:\n{message}");
+    WARNINGS.with_borrow_mut(|warnings| {
+        if warnings.len() == MAX_WARNINGS {
+            warnings.pop_front();
+        }
+        warnings.push_back(message);
+    });
+}
+
+/// Drain the warnings raised on the current thread since the last call, oldest first.
+pub fn take_warnings() -> Vec<String> {
+    WARNINGS.with_borrow_mut(|warnings| warnings.drain(..).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_warnings() {
+        take_warnings();
+        warning("a");
+        warning("b");
+        assert_eq!(take_warnings(), ["a", "b"]);
+        assert!(take_warnings().is_empty());
+
+        for i in 0..MAX_WARNINGS + 10 {
+            warning(i.to_string());
+        }
+        let warnings = take_warnings();
+        assert_eq!(warnings.len(), MAX_WARNINGS);
+        assert_eq!(warnings[0], "10");
+        assert_eq!(warnings[MAX_WARNINGS - 1], (MAX_WARNINGS + 9).to_string());
+    }
+}